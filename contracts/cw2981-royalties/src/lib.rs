@@ -50,7 +50,11 @@ pub struct MetadataWithRoyalty {
     pub royalty_payment_address: Option<String>,
 }
 
-impl Cw721State for MetadataWithRoyalty {}
+impl Cw721State for MetadataWithRoyalty {
+    fn traits(&self) -> Vec<Trait> {
+        self.attributes.clone().unwrap_or_default()
+    }
+}
 impl Cw721CustomMsg for MetadataWithRoyalty {}
 
 #[cfg(not(feature = "library"))]
@@ -143,7 +147,7 @@ mod tests {
     use cosmwasm_std::{from_json, Uint128};
 
     use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
-    use cw721::msg::Cw721InstantiateMsg;
+    use cw721::msg::{Cw721InstantiateMsg, TokensByFilterResponse, TokensResponse, TraitFilter};
     use cw721::traits::Cw721Query;
     use state::Cw2981Contract;
 
@@ -345,4 +349,59 @@ mod tests {
         .unwrap();
         assert_eq!(res, voyager_expected);
     }
+
+    #[test]
+    fn query_tokens_by_trait_and_filter() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make(CREATOR);
+        let info = message_info(&creator, &[]);
+        let init_msg = Cw721InstantiateMsg {
+            name: "SpaceShips".to_string(),
+            symbol: "SPACE".to_string(),
+            collection_info_extension: Empty {},
+            minter: None,
+            creator: None,
+            withdraw_address: None,
+        };
+        entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+        for (token_id, class) in [("Enterprise", "cruiser"), ("Voyager", "intrepid")] {
+            let exec_msg = ExecuteMsg::Mint {
+                token_id: token_id.to_string(),
+                owner: deps.api.addr_make("john").to_string(),
+                token_uri: None,
+                extension: Some(MetadataWithRoyalty {
+                    attributes: Some(vec![Trait {
+                        display_type: None,
+                        trait_type: "class".to_string(),
+                        value: class.to_string(),
+                    }]),
+                    ..MetadataWithRoyalty::default()
+                }),
+            };
+            entry::execute(deps.as_mut(), mock_env(), info.clone(), exec_msg).unwrap();
+        }
+
+        let query_msg = QueryMsg::TokensByTrait {
+            trait_type: "class".to_string(),
+            value: "intrepid".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: TokensResponse =
+            from_json(entry::query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert_eq!(res.tokens, vec!["Voyager"]);
+
+        let query_msg = QueryMsg::TokensByFilter {
+            filter: TraitFilter::Prefix {
+                trait_type: "class".to_string(),
+                prefix: "cru".to_string(),
+            },
+            start_after: None,
+            limit: None,
+        };
+        let res: TokensByFilterResponse =
+            from_json(entry::query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert_eq!(res.tokens, vec!["Enterprise"]);
+    }
 }
@@ -5,14 +5,18 @@ use crate::{
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Deps, Empty, Env, MessageInfo, Uint128};
 use cw721::msg::{
-    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, MinterResponse, NftInfoResponse,
-    NumTokensResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, ApprovedTokensResponse,
+    AuthorizationResponse, BalanceOfResponse, CollectionStatsResponse, HoldersResponse,
+    MinterResponse, NftInfoResponse, NumTokensResponse, OperatorGrantsResponse, OperatorResponse,
+    OperatorsResponse, OrderBy, OwnerOfResponse, TokensByFilterResponse, TokensResponse,
+    TraitFilter,
 };
 use cw721::{
     error::Cw721ContractError,
     execute::{assert_creator, assert_minter},
     msg::{empty_as_none, CollectionInfoAndExtensionResponse, Cw721QueryMsg},
     traits::StateFactory,
+    Attribute,
 };
 use cw_ownable::Ownership;
 use url::Url;
@@ -77,10 +81,51 @@ pub enum QueryMsg {
         include_expired: Option<bool>,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
     },
+    /// Lists tokens `spender` is approved to transfer, ordered by token_id.
+    #[returns(ApprovedTokensResponse)]
+    ApprovedTokensFor {
+        spender: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Lists owners who granted `operator` control over all their tokens, ordered by owner.
+    #[returns(OperatorGrantsResponse)]
+    OperatorGrantsFor {
+        operator: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Dry-run of transferring (or sending, burning) a token: returns whether `sender`
+    /// is authorized and why.
+    #[returns(AuthorizationResponse)]
+    CanTransfer { sender: String, token_id: String },
+    /// Dry-run of approving a spender for a token: returns whether `sender` is authorized and why.
+    #[returns(AuthorizationResponse)]
+    CanApprove { sender: String, token_id: String },
     /// Total number of tokens issued
     #[returns(NumTokensResponse)]
     NumTokens {},
+    /// Minted and burned totals, current supply, holders and mint timestamps
+    #[returns(CollectionStatsResponse)]
+    CollectionStats {},
+    /// Number of tokens held by the given owner
+    #[returns(BalanceOfResponse)]
+    BalanceOf { owner: String },
+    /// Lists distinct owners and their number of tokens, ordered by owner address
+    #[returns(HoldersResponse)]
+    Holders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     #[deprecated(since = "0.19.0", note = "Please use GetCollectionInfo instead")]
     #[returns(CollectionInfoAndExtensionResponse<Option<Empty>>)]
@@ -123,6 +168,10 @@ pub enum QueryMsg {
         owner: String,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
     },
     /// With Enumerable extension.
     /// Requires pagination. Lists all token_ids controlled by the contract.
@@ -130,6 +179,26 @@ pub enum QueryMsg {
     AllTokens {
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
+    },
+    /// Lists token_ids having the given trait in their `attributes`.
+    #[returns(TokensResponse)]
+    TokensByTrait {
+        trait_type: String,
+        value: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Lists token_ids whose `attributes` match `filter`.
+    /// `limit` bounds the number of scanned tokens, use `next_start_after` for paging.
+    #[returns(TokensByFilterResponse)]
+    TokensByFilter {
+        filter: TraitFilter,
+        start_after: Option<String>,
+        limit: Option<u32>,
     },
 
     /// Return the minter
@@ -137,6 +206,10 @@ pub enum QueryMsg {
     #[returns(MinterResponse)]
     Minter {},
 
+    /// Returns a single attribute of the collection extension, None if unset.
+    #[returns(Option<Attribute>)]
+    GetCollectionExtensionAttribute { key: String },
+
     #[returns(Option<String>)]
     GetWithdrawAddress {},
 
@@ -161,7 +234,40 @@ impl From<QueryMsg> for Cw721QueryMsg<DefaultOptionMetadataExtensionWithRoyalty,
                 token_id,
                 include_expired,
             },
+            QueryMsg::ApprovedTokensFor {
+                spender,
+                include_expired,
+                start_after,
+                limit,
+            } => Cw721QueryMsg::ApprovedTokensFor {
+                spender,
+                include_expired,
+                start_after,
+                limit,
+            },
+            QueryMsg::OperatorGrantsFor {
+                operator,
+                include_expired,
+                start_after,
+                limit,
+            } => Cw721QueryMsg::OperatorGrantsFor {
+                operator,
+                include_expired,
+                start_after,
+                limit,
+            },
+            QueryMsg::CanTransfer { sender, token_id } => {
+                Cw721QueryMsg::CanTransfer { sender, token_id }
+            }
+            QueryMsg::CanApprove { sender, token_id } => {
+                Cw721QueryMsg::CanApprove { sender, token_id }
+            }
             QueryMsg::NumTokens {} => Cw721QueryMsg::NumTokens {},
+            QueryMsg::CollectionStats {} => Cw721QueryMsg::CollectionStats {},
+            QueryMsg::BalanceOf { owner } => Cw721QueryMsg::BalanceOf { owner },
+            QueryMsg::Holders { start_after, limit } => {
+                Cw721QueryMsg::Holders { start_after, limit }
+            }
             #[allow(deprecated)]
             QueryMsg::ContractInfo {} => Cw721QueryMsg::GetCollectionInfoAndExtension {},
             QueryMsg::GetCollectionInfoAndExtension {} => {
@@ -179,29 +285,68 @@ impl From<QueryMsg> for Cw721QueryMsg<DefaultOptionMetadataExtensionWithRoyalty,
                 owner,
                 start_after,
                 limit,
+                end_before,
+                order,
             } => Cw721QueryMsg::Tokens {
                 owner,
                 start_after,
                 limit,
+                end_before,
+                order,
+            },
+            QueryMsg::AllTokens {
+                start_after,
+                limit,
+                end_before,
+                order,
+            } => Cw721QueryMsg::AllTokens {
+                start_after,
+                limit,
+                end_before,
+                order,
+            },
+            QueryMsg::TokensByTrait {
+                trait_type,
+                value,
+                start_after,
+                limit,
+            } => Cw721QueryMsg::TokensByTrait {
+                trait_type,
+                value,
+                start_after,
+                limit,
+            },
+            QueryMsg::TokensByFilter {
+                filter,
+                start_after,
+                limit,
+            } => Cw721QueryMsg::TokensByFilter {
+                filter,
+                start_after,
+                limit,
             },
-            QueryMsg::AllTokens { start_after, limit } => {
-                Cw721QueryMsg::AllTokens { start_after, limit }
-            }
             #[allow(deprecated)]
             QueryMsg::Minter {} => Cw721QueryMsg::Minter {},
             QueryMsg::GetMinterOwnership {} => Cw721QueryMsg::GetMinterOwnership {},
             QueryMsg::GetCreatorOwnership {} => Cw721QueryMsg::GetCreatorOwnership {},
+            QueryMsg::GetCollectionExtensionAttribute { key } => {
+                Cw721QueryMsg::GetCollectionExtensionAttribute { key }
+            }
             QueryMsg::GetWithdrawAddress {} => Cw721QueryMsg::GetWithdrawAddress {},
             QueryMsg::AllOperators {
                 owner,
                 include_expired,
                 start_after,
                 limit,
+                end_before,
+                order,
             } => Cw721QueryMsg::AllOperators {
                 owner,
                 include_expired,
                 start_after,
                 limit,
+                end_before,
+                order,
             },
             QueryMsg::Approval {
                 token_id,
@@ -2,9 +2,7 @@ use std::{env::current_dir, fs::create_dir_all};
 
 use cosmwasm_schema::{export_schema_with_title, remove_schemas, schema_for};
 
-use cosmwasm_std::Empty;
-use cw721::msg::Cw721MigrateMsg;
-use cw721_expiration::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use cw721_expiration::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -15,6 +13,6 @@ fn main() {
     // entry points - generate always with title for avoiding name suffixes like "..._empty_for_..." due to generics
     export_schema_with_title(&schema_for!(InstantiateMsg), &out_dir, "InstantiateMsg");
     export_schema_with_title(&schema_for!(ExecuteMsg), &out_dir, "ExecuteMsg");
-    export_schema_with_title(&schema_for!(QueryMsg<Empty>), &out_dir, "QueryMsg");
-    export_schema_with_title(&schema_for!(Cw721MigrateMsg), &out_dir, "MigrateMsg");
+    export_schema_with_title(&schema_for!(QueryMsg), &out_dir, "QueryMsg");
+    export_schema_with_title(&schema_for!(MigrateMsg), &out_dir, "MigrateMsg");
 }
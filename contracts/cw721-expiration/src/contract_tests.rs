@@ -1,23 +1,38 @@
 use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
 
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, CosmosMsg, DepsMut, MessageInfo, Response, StdError, WasmMsg,
+    coin, coins, from_json, to_json_binary, Addr, CosmosMsg, Deps, DepsMut, Empty, Env,
+    MessageInfo, Response, StdError, WasmMsg,
 };
 
-use crate::state::DefaultCw721ExpirationContract;
+use crate::msg::{
+    Cw721ExpirationExecuteMsg, Cw721ExpirationInstantiateMsg, Cw721ExpirationQueryMsg,
+};
+use crate::state::{Cw721ExpirationContract, DefaultCw721ExpirationContract};
 use crate::{
-    error::ContractError, msg::InstantiateMsg, msg::QueryMsg, DefaultOptionalNftExtension,
+    error::ContractError, msg::ExecuteMsg, msg::ExpiringNft, msg::ExpiringNftsResponse,
+    msg::InstantiateMsg, msg::LegacyExpiration, msg::MigrateMsg, msg::NftExpirationMsg,
+    msg::NftExpirationResponse, msg::QueryMsg, DefaultOptionalNftExtension,
 };
 use cw721::error::Cw721ContractError;
 use cw721::msg::CollectionInfoAndExtensionResponse;
 use cw721::msg::{
-    ApprovalResponse, Cw721ExecuteMsg, NftInfoResponse, OperatorResponse, OperatorsResponse,
-    OwnerOfResponse, TokensResponse,
+    ApprovalResponse, ApprovedTokensResponse, AttributeMsg, AttributeType, AuthorizationReason,
+    AuthorizationResponse, BalanceOfResponse, CollectionStatsResponse, Holder, HoldersResponse,
+    NftExtensionMsg, NftInfoResponse, NftsByExtensionResponse, OperatorGrantsResponse,
+    OperatorResponse, OperatorsResponse, OrderBy, OwnerOfResponse, TokensByFilterResponse,
+    TokensResponse, TokensWithInfoResponse, TraitFilter,
 };
 use cw721::receiver::Cw721ReceiveMsg;
-use cw721::state::{CREATOR, MINTER};
-use cw721::{traits::Cw721Query, Approval, Expiration};
+use cw721::state::{NftExtension, Trait, CREATOR, MINTER};
+use cw721::traits::Cw721Execute;
+use cw721::{
+    traits::Cw721Query, Approval, EmptyOptionalCollectionExtension,
+    EmptyOptionalCollectionExtensionMsg, EmptyOptionalNftExtension, EmptyOptionalNftExtensionMsg,
+    Expiration,
+};
 use cw_ownable::{Action, Ownership, OwnershipError};
+use cw_utils::{Duration, PaymentError};
 pub struct MockAddrFactory<'a> {
     api: MockApi,
     addrs: std::collections::BTreeMap<&'a str, Addr>,
@@ -61,6 +76,8 @@ fn setup_contract(
         minter: Some(minter.to_string()),
         creator: Some(creator.to_string()),
         withdraw_address: None,
+        renewal_price: None,
+        renewal_grace_days: None,
     };
     let info = message_info(creator, &[]);
     let res = contract.instantiate(deps, mock_env(), info, msg).unwrap();
@@ -83,6 +100,8 @@ fn proper_instantiation() {
         minter: Some(minter.to_string()),
         creator: Some(creator.to_string()),
         withdraw_address: Some(creator.to_string()),
+        renewal_price: None,
+        renewal_grace_days: None,
     };
     let info = addrs.info("creator");
     let env = mock_env();
@@ -128,7 +147,15 @@ fn proper_instantiation() {
 
     // list the token_ids
     let tokens = contract
-        .query_all_tokens_include_expired_nft(deps.as_ref(), mock_env(), None, None, false)
+        .query_all_tokens_include_expired_nft(
+            deps.as_ref(),
+            mock_env(),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
         .unwrap();
     assert_eq!(0, tokens.tokens.len());
 }
@@ -148,6 +175,8 @@ fn proper_instantiation_with_collection_info() {
         minter: Some(minter.to_string()),
         creator: Some(creator.to_string()),
         withdraw_address: Some(creator.to_string()),
+        renewal_price: None,
+        renewal_grace_days: None,
     };
     let info = addrs.info("creator");
     let env = mock_env();
@@ -193,7 +222,15 @@ fn proper_instantiation_with_collection_info() {
 
     // list the token_ids
     let tokens = contract
-        .query_all_tokens_include_expired_nft(deps.as_ref(), mock_env(), None, None, false)
+        .query_all_tokens_include_expired_nft(
+            deps.as_ref(),
+            mock_env(),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
         .unwrap();
     assert_eq!(0, tokens.tokens.len());
 }
@@ -209,11 +246,12 @@ fn test_mint() {
     let token_id = "atomize".to_string();
     let token_uri = "https://www.merriam-webster.com/dictionary/atomize".to_string();
     let owner = addrs.addr("medusa");
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: owner.to_string(),
         token_uri: Some(token_uri.clone()),
         extension: None,
+        expiration: None,
     };
 
     // random cannot mint
@@ -280,11 +318,12 @@ fn test_mint() {
     assert_eq!(mint_timestamp, mock_env().block.time);
 
     // Cannot mint same token_id again
-    let mint_msg2 = Cw721ExecuteMsg::Mint {
+    let mint_msg2 = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: addrs.addr("hercules").to_string(),
         token_uri: None,
         extension: None,
+        expiration: None,
     };
 
     let allowed = addrs.info("minter");
@@ -295,7 +334,15 @@ fn test_mint() {
 
     // list the token_ids
     let tokens = contract
-        .query_all_tokens_include_expired_nft(deps.as_ref(), mock_env(), None, None, false)
+        .query_all_tokens_include_expired_nft(
+            deps.as_ref(),
+            mock_env(),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
         .unwrap();
     assert_eq!(1, tokens.tokens.len());
     assert_eq!(vec![token_id], tokens.tokens);
@@ -312,11 +359,12 @@ fn test_update_minter() {
     let token_id = "petrify".to_string();
     let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
     let medusa = addrs.addr("medusa");
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id,
         owner: medusa.to_string(),
         token_uri: Some(token_uri.clone()),
         extension: None,
+        expiration: None,
     };
 
     // Minter can mint
@@ -333,7 +381,7 @@ fn test_update_minter() {
             deps.as_mut(),
             mock_env(),
             minter_info.clone(),
-            Cw721ExecuteMsg::UpdateMinterOwnership(Action::TransferOwnership {
+            ExecuteMsg::UpdateMinterOwnership(Action::TransferOwnership {
                 new_owner: random.to_string(),
                 expiry: None,
             }),
@@ -365,7 +413,7 @@ fn test_update_minter() {
             deps.as_mut(),
             mock_env(),
             random_info.clone(),
-            Cw721ExecuteMsg::UpdateMinterOwnership(Action::AcceptOwnership),
+            ExecuteMsg::UpdateMinterOwnership(Action::AcceptOwnership),
         )
         .unwrap();
 
@@ -378,11 +426,12 @@ fn test_update_minter() {
     .unwrap();
     assert_eq!(minter_ownership.owner, Some(random));
 
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: "randoms_token".to_string(),
         owner: addrs.addr("medusa").to_string(),
         token_uri: Some(token_uri),
         extension: None,
+        expiration: None,
     };
 
     // Old owner can not mint.
@@ -408,14 +457,15 @@ fn test_burn() {
     let token_id = "petrify".to_string();
     let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
 
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: minter.to_string(),
         token_uri: Some(token_uri),
         extension: None,
+        expiration: None,
     };
 
-    let burn_msg = Cw721ExecuteMsg::Burn {
+    let burn_msg = ExecuteMsg::Burn {
         token_id: token_id.clone(),
     };
 
@@ -470,7 +520,15 @@ fn test_burn() {
 
     // list the token_ids
     let tokens = contract
-        .query_all_tokens_include_expired_nft(deps.as_ref(), env.clone(), None, None, false)
+        .query_all_tokens_include_expired_nft(
+            deps.as_ref(),
+            env.clone(),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
         .unwrap();
     assert!(tokens.tokens.is_empty());
 
@@ -491,7 +549,7 @@ fn test_burn() {
         ContractError::NftExpired {
             token_id,
             mint_date,
-            expiration
+            expiration: Expiration::AtTime(expiration)
         }
     );
 }
@@ -509,11 +567,12 @@ fn test_transfer_nft() {
     let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
 
     let owner = addrs.addr("owner");
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: owner.to_string(),
         token_uri: Some(token_uri),
         extension: None,
+        expiration: None,
     };
 
     let mut env = mock_env();
@@ -525,7 +584,7 @@ fn test_transfer_nft() {
     // random cannot transfer
     let random = addrs.addr("random");
     let random_info = addrs.info("random");
-    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+    let transfer_msg = ExecuteMsg::TransferNft {
         recipient: random.to_string(),
         token_id: token_id.clone(),
     };
@@ -541,7 +600,7 @@ fn test_transfer_nft() {
     // owner can
     let owner_info = addrs.info("owner");
     let new_owner = addrs.addr("random");
-    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+    let transfer_msg = ExecuteMsg::TransferNft {
         recipient: new_owner.to_string(),
         token_id: token_id.clone(),
     };
@@ -576,7 +635,7 @@ fn test_transfer_nft() {
         ContractError::NftExpired {
             token_id,
             mint_date,
-            expiration
+            expiration: Expiration::AtTime(expiration)
         }
     );
 }
@@ -593,11 +652,12 @@ fn test_send_nft() {
     let token_id = "melt".to_string();
     let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
     let venus = addrs.addr("venus");
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: venus.to_string(),
         token_uri: Some(token_uri),
         extension: None,
+        expiration: None,
     };
 
     let mut env = mock_env();
@@ -608,7 +668,7 @@ fn test_send_nft() {
 
     let msg = to_json_binary("You now have the melting power").unwrap();
     let target = addrs.addr("another_contract");
-    let send_msg = Cw721ExecuteMsg::SendNft {
+    let send_msg = ExecuteMsg::SendNft {
         contract: target.to_string(),
         token_id: token_id.clone(),
         msg: msg.clone(),
@@ -674,7 +734,7 @@ fn test_send_nft() {
         ContractError::NftExpired {
             token_id,
             mint_date,
-            expiration
+            expiration: Expiration::AtTime(expiration)
         }
     );
 }
@@ -691,11 +751,12 @@ fn test_approve_revoke() {
     let token_id = "grow".to_string();
     let token_uri = "https://www.merriam-webster.com/dictionary/grow".to_string();
     let demeter = addrs.addr("demeter");
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: demeter.to_string(),
         token_uri: Some(token_uri),
         extension: None,
+        expiration: None,
     };
 
     let mut env = mock_env();
@@ -727,7 +788,7 @@ fn test_approve_revoke() {
 
     // Give random transferring power
     let random = addrs.addr("random");
-    let approve_msg = Cw721ExecuteMsg::Approve {
+    let approve_msg = ExecuteMsg::Approve {
         spender: random.to_string(),
         token_id: token_id.clone(),
         expires: None,
@@ -769,7 +830,7 @@ fn test_approve_revoke() {
     // random can now transfer
     let random_info = addrs.info("random");
     let person = addrs.addr("person");
-    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+    let transfer_msg = ExecuteMsg::TransferNft {
         recipient: person.to_string(),
         token_id: token_id.clone(),
     };
@@ -798,7 +859,7 @@ fn test_approve_revoke() {
     );
 
     // Approve, revoke, and check for empty, to test revoke
-    let approve_msg = Cw721ExecuteMsg::Approve {
+    let approve_msg = ExecuteMsg::Approve {
         spender: random.to_string(),
         token_id: token_id.clone(),
         expires: None,
@@ -813,7 +874,7 @@ fn test_approve_revoke() {
         )
         .unwrap();
 
-    let revoke_msg = Cw721ExecuteMsg::Revoke {
+    let revoke_msg = ExecuteMsg::Revoke {
         spender: random.to_string(),
         token_id: token_id.clone(),
     };
@@ -853,7 +914,7 @@ fn test_approve_revoke() {
         ContractError::NftExpired {
             token_id: token_id.clone(),
             mint_date,
-            expiration
+            expiration: Expiration::AtTime(expiration)
         }
     );
 
@@ -866,7 +927,7 @@ fn test_approve_revoke() {
         ContractError::NftExpired {
             token_id,
             mint_date,
-            expiration
+            expiration: Expiration::AtTime(expiration)
         }
     );
 }
@@ -886,11 +947,12 @@ fn test_approve_all_revoke_all() {
     let token_id2 = "grow2".to_string();
     let token_uri2 = "https://www.merriam-webster.com/dictionary/grow2".to_string();
     let demeter = addrs.addr("demeter");
-    let mint_msg1 = Cw721ExecuteMsg::Mint {
+    let mint_msg1 = ExecuteMsg::Mint {
         token_id: token_id1.clone(),
         owner: demeter.to_string(),
         token_uri: Some(token_uri1),
         extension: None,
+        expiration: None,
     };
 
     let minter = addrs.info("minter");
@@ -898,11 +960,12 @@ fn test_approve_all_revoke_all() {
         .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg1)
         .unwrap();
 
-    let mint_msg2 = Cw721ExecuteMsg::Mint {
+    let mint_msg2 = ExecuteMsg::Mint {
         token_id: token_id2.clone(),
         owner: demeter.to_string(),
         token_uri: Some(token_uri2),
         extension: None,
+        expiration: None,
     };
 
     contract
@@ -911,7 +974,15 @@ fn test_approve_all_revoke_all() {
 
     // paginate the token_ids
     let tokens = contract
-        .query_all_tokens_include_expired_nft(deps.as_ref(), mock_env(), None, Some(1), false)
+        .query_all_tokens_include_expired_nft(
+            deps.as_ref(),
+            mock_env(),
+            None,
+            Some(1),
+            None,
+            None,
+            false,
+        )
         .unwrap();
     assert_eq!(1, tokens.tokens.len());
     assert_eq!(vec![token_id1.clone()], tokens.tokens);
@@ -921,6 +992,8 @@ fn test_approve_all_revoke_all() {
             mock_env(),
             Some(token_id1.clone()),
             Some(3),
+            None,
+            None,
             false,
         )
         .unwrap();
@@ -929,7 +1002,7 @@ fn test_approve_all_revoke_all() {
 
     // demeter gives random full (operator) power over her tokens
     let random = addrs.addr("random");
-    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
+    let approve_all_msg = ExecuteMsg::ApproveAll {
         operator: random.to_string(),
         expires: None,
     };
@@ -948,7 +1021,7 @@ fn test_approve_all_revoke_all() {
     // random can now transfer
     let random_info = addrs.info("random");
     let person = addrs.addr("person");
-    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+    let transfer_msg = ExecuteMsg::TransferNft {
         recipient: person.to_string(),
         token_id: token_id1,
     };
@@ -964,7 +1037,7 @@ fn test_approve_all_revoke_all() {
     };
     let msg: CosmosMsg = CosmosMsg::Wasm(inner_msg);
 
-    let send_msg = Cw721ExecuteMsg::SendNft {
+    let send_msg = ExecuteMsg::SendNft {
         contract: other_contract.to_string(),
         token_id: token_id2,
         msg: to_json_binary(&msg).unwrap(),
@@ -975,7 +1048,7 @@ fn test_approve_all_revoke_all() {
 
     // Approve_all, revoke_all, and check for empty, to test revoke_all
     let operator = addrs.addr("operator");
-    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
+    let approve_all_msg = ExecuteMsg::ApproveAll {
         operator: operator.to_string(),
         expires: None,
     };
@@ -1029,6 +1102,8 @@ fn test_approve_all_revoke_all() {
             true,
             None,
             None,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -1044,7 +1119,7 @@ fn test_approve_all_revoke_all() {
     // second approval
     let buddy_expires = Expiration::AtHeight(1234567);
     let buddy = addrs.addr("buddy");
-    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
+    let approve_all_msg = ExecuteMsg::ApproveAll {
         operator: buddy.to_string(),
         expires: Some(buddy_expires),
     };
@@ -1068,6 +1143,8 @@ fn test_approve_all_revoke_all() {
             true,
             Some(operator.to_string()),
             Some(1),
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -1088,6 +1165,8 @@ fn test_approve_all_revoke_all() {
             true,
             None,
             Some(2),
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -1106,7 +1185,7 @@ fn test_approve_all_revoke_all() {
         }
     );
 
-    let revoke_all_msg = Cw721ExecuteMsg::RevokeAll {
+    let revoke_all_msg = ExecuteMsg::RevokeAll {
         operator: operator.to_string(),
     };
     contract
@@ -1136,6 +1215,8 @@ fn test_approve_all_revoke_all() {
             false,
             None,
             None,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -1160,6 +1241,8 @@ fn test_approve_all_revoke_all() {
             false,
             None,
             None,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(0, res.operators.len());
@@ -1195,31 +1278,34 @@ fn test_tokens_by_owner() {
     let ceres = addrs.addr("ceres");
     let token_id3 = "sing".to_string();
 
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id1.clone(),
         owner: demeter.to_string(),
         token_uri: None,
         extension: None,
+        expiration: None,
     };
     contract
         .execute(deps.as_mut(), mock_env(), minter_info.clone(), mint_msg)
         .unwrap();
 
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id2.clone(),
         owner: ceres.to_string(),
         token_uri: None,
         extension: None,
+        expiration: None,
     };
     contract
         .execute(deps.as_mut(), mock_env(), minter_info.clone(), mint_msg)
         .unwrap();
 
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id3.clone(),
         owner: demeter.to_string(),
         token_uri: None,
         extension: None,
+        expiration: None,
     };
     contract
         .execute(deps.as_mut(), mock_env(), minter_info, mint_msg)
@@ -1228,12 +1314,28 @@ fn test_tokens_by_owner() {
     // get all tokens in order:
     let expected = vec![token_id1.clone(), token_id2.clone(), token_id3.clone()];
     let tokens = contract
-        .query_all_tokens_include_expired_nft(deps.as_ref(), mock_env(), None, None, false)
+        .query_all_tokens_include_expired_nft(
+            deps.as_ref(),
+            mock_env(),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
         .unwrap();
     assert_eq!(&expected, &tokens.tokens);
     // paginate
     let tokens = contract
-        .query_all_tokens_include_expired_nft(deps.as_ref(), mock_env(), None, Some(2), false)
+        .query_all_tokens_include_expired_nft(
+            deps.as_ref(),
+            mock_env(),
+            None,
+            Some(2),
+            None,
+            None,
+            false,
+        )
         .unwrap();
     assert_eq!(&expected[..2], &tokens.tokens[..]);
     let tokens = contract
@@ -1242,6 +1344,8 @@ fn test_tokens_by_owner() {
             mock_env(),
             Some(expected[1].clone()),
             None,
+            None,
+            None,
             false,
         )
         .unwrap();
@@ -1258,6 +1362,8 @@ fn test_tokens_by_owner() {
             demeter.to_string(),
             None,
             None,
+            None,
+            None,
             false,
         )
         .unwrap();
@@ -1269,6 +1375,8 @@ fn test_tokens_by_owner() {
             ceres.to_string(),
             None,
             None,
+            None,
+            None,
             false,
         )
         .unwrap();
@@ -1282,6 +1390,8 @@ fn test_tokens_by_owner() {
             demeter.to_string(),
             None,
             Some(1),
+            None,
+            None,
             false,
         )
         .unwrap();
@@ -1293,6 +1403,8 @@ fn test_tokens_by_owner() {
             demeter.to_string(),
             Some(by_demeter[0].clone()),
             Some(3),
+            None,
+            None,
             false,
         )
         .unwrap();
@@ -1312,11 +1424,12 @@ fn test_nft_info() {
     let owner = addrs.addr("ark");
 
     let mut env = mock_env();
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: owner.to_string(),
         token_uri: None,
         extension: None,
+        expiration: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter, mint_msg)
@@ -1339,7 +1452,7 @@ fn test_nft_info() {
         ContractError::NftExpired {
             token_id,
             mint_date,
-            expiration
+            expiration: Expiration::AtTime(expiration)
         }
     );
 }
@@ -1357,11 +1470,12 @@ fn test_all_nft_info() {
     let owner = addrs.addr("ark");
 
     let mut env = mock_env();
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: owner.to_string(),
         token_uri: None,
         extension: None,
+        expiration: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter_info, mint_msg)
@@ -1390,7 +1504,7 @@ fn test_all_nft_info() {
         ContractError::NftExpired {
             token_id,
             mint_date,
-            expiration
+            expiration: Expiration::AtTime(expiration)
         }
     );
 }
@@ -1408,11 +1522,12 @@ fn test_owner_of() {
     let owner = addrs.addr("ark");
 
     let mut env = mock_env();
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: owner.to_string(),
         token_uri: None,
         extension: None,
+        expiration: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter_info, mint_msg)
@@ -1441,7 +1556,7 @@ fn test_owner_of() {
         ContractError::NftExpired {
             token_id,
             mint_date,
-            expiration
+            expiration: Expiration::AtTime(expiration)
         }
     );
 }
@@ -1459,11 +1574,12 @@ fn test_approval() {
     let owner = addrs.addr("ark");
 
     let mut env = mock_env();
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: owner.to_string(),
         token_uri: None,
         extension: None,
+        expiration: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter_info, mint_msg)
@@ -1500,7 +1616,7 @@ fn test_approval() {
         ContractError::NftExpired {
             token_id,
             mint_date,
-            expiration
+            expiration: Expiration::AtTime(expiration)
         }
     );
 }
@@ -1518,11 +1634,12 @@ fn test_approvals() {
     let owner = addrs.addr("ark");
 
     let mut env = mock_env();
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: owner.to_string(),
         token_uri: None,
         extension: None,
+        expiration: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter_info, mint_msg)
@@ -1551,7 +1668,7 @@ fn test_approvals() {
         ContractError::NftExpired {
             token_id,
             mint_date,
-            expiration
+            expiration: Expiration::AtTime(expiration)
         }
     );
 }
@@ -1569,11 +1686,12 @@ fn test_tokens() {
     let owner = addrs.addr("ark");
 
     let mut env = mock_env();
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: owner.to_string(),
         token_uri: None,
         extension: None,
+        expiration: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter_info, mint_msg)
@@ -1587,6 +1705,8 @@ fn test_tokens() {
             owner.to_string(),
             None,
             None,
+            None,
+            None,
             false,
         )
         .unwrap();
@@ -1601,6 +1721,8 @@ fn test_tokens() {
             owner.to_string(),
             None,
             None,
+            None,
+            None,
             false,
         )
         .unwrap();
@@ -1608,7 +1730,16 @@ fn test_tokens() {
 
     // assert invalid nft is returned
     let tokens = contract
-        .query_tokens_include_expired_nft(deps.as_ref(), env, owner.to_string(), None, None, true)
+        .query_tokens_include_expired_nft(
+            deps.as_ref(),
+            env,
+            owner.to_string(),
+            None,
+            None,
+            None,
+            None,
+            true,
+        )
         .unwrap();
     assert_eq!(
         tokens,
@@ -1631,11 +1762,12 @@ fn test_all_tokens() {
     let owner = addrs.addr("ark");
 
     let mut env = mock_env();
-    let mint_msg = Cw721ExecuteMsg::Mint {
+    let mint_msg = ExecuteMsg::Mint {
         token_id: token_id.clone(),
         owner: owner.to_string(),
         token_uri: None,
         extension: None,
+        expiration: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter_info, mint_msg)
@@ -1643,7 +1775,15 @@ fn test_all_tokens() {
 
     // assert valid nft is returned
     contract
-        .query_all_tokens_include_expired_nft(deps.as_ref(), env.clone(), None, None, false)
+        .query_all_tokens_include_expired_nft(
+            deps.as_ref(),
+            env.clone(),
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
         .unwrap();
 
     // assert invalid nft is not returned
@@ -1656,6 +1796,8 @@ fn test_all_tokens() {
             owner.to_string(),
             None,
             None,
+            None,
+            None,
             false,
         )
         .unwrap();
@@ -1663,7 +1805,7 @@ fn test_all_tokens() {
 
     // assert invalid nft is returned
     let tokens = contract
-        .query_all_tokens_include_expired_nft(deps.as_ref(), env, None, None, true)
+        .query_all_tokens_include_expired_nft(deps.as_ref(), env, None, None, None, None, true)
         .unwrap();
     assert_eq!(
         tokens,
@@ -1672,3 +1814,1496 @@ fn test_all_tokens() {
         }
     );
 }
+
+#[test]
+fn test_tokens_and_operators_order_and_end_before() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let owner = addrs.addr("ark");
+
+    let mut env = mock_env();
+    for (token_id, expiration) in [
+        ("grow1", None),
+        (
+            "grow2",
+            Some(NftExpirationMsg::After(Duration::Time(2 * 86400))),
+        ),
+        (
+            "grow3",
+            Some(NftExpirationMsg::After(Duration::Time(2 * 86400))),
+        ),
+    ] {
+        let mint_msg = ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+            expiration,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+            .unwrap();
+    }
+    for operator in ["operator1", "operator2"] {
+        let approve_all_msg = ExecuteMsg::ApproveAll {
+            operator: addrs.addr(operator).to_string(),
+            expires: None,
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                addrs.info("ark"),
+                approve_all_msg,
+            )
+            .unwrap();
+    }
+
+    // expired nft is filtered out
+    env.block.time = env.block.time.plus_days(1);
+    for (end_before, order, expected) in [
+        (Some("grow3".to_string()), None, vec!["grow2"]),
+        (None, Some(OrderBy::Desc), vec!["grow3", "grow2"]),
+    ] {
+        for query_msg in [
+            QueryMsg::Tokens {
+                owner: owner.to_string(),
+                start_after: None,
+                limit: None,
+                end_before: end_before.clone(),
+                order,
+                include_expired_nft: None,
+            },
+            QueryMsg::AllTokens {
+                start_after: None,
+                limit: None,
+                end_before: end_before.clone(),
+                order,
+                include_expired_nft: None,
+            },
+        ] {
+            let tokens: TokensResponse = from_json(
+                contract
+                    .query(deps.as_ref(), env.clone(), query_msg)
+                    .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(tokens.tokens, expected);
+        }
+    }
+
+    let operators: OperatorsResponse = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                env,
+                QueryMsg::AllOperators {
+                    owner: owner.to_string(),
+                    include_expired: None,
+                    start_after: None,
+                    limit: None,
+                    end_before: None,
+                    order: Some(OrderBy::Desc),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    let spenders: Vec<_> = operators
+        .operators
+        .iter()
+        .map(|approval| approval.spender.clone())
+        .collect();
+    let mut expected = vec![addrs.addr("operator1"), addrs.addr("operator2")];
+    expected.sort();
+    expected.reverse();
+    assert_eq!(spenders, expected);
+}
+
+#[test]
+fn test_tokens_by_trait_and_filter() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let owner = addrs.addr("ark");
+
+    let mut env = mock_env();
+    for (token_id, expiration) in [
+        ("grow1", None),
+        (
+            "grow2",
+            Some(NftExpirationMsg::After(Duration::Time(2 * 86400))),
+        ),
+    ] {
+        let mint_msg = ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: Some(NftExtensionMsg {
+                attributes: Some(vec![Trait {
+                    display_type: None,
+                    trait_type: "color".to_string(),
+                    value: "red".to_string(),
+                }]),
+                ..NftExtensionMsg::default()
+            }),
+            expiration,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+            .unwrap();
+    }
+
+    // expired nft is filtered out, unless included
+    env.block.time = env.block.time.plus_days(1);
+    for (include_expired_nft, expected) in [(false, vec!["grow2"]), (true, vec!["grow1", "grow2"])]
+    {
+        let query_msg = QueryMsg::TokensByTrait {
+            trait_type: "color".to_string(),
+            value: "red".to_string(),
+            start_after: None,
+            limit: None,
+            include_expired_nft: Some(include_expired_nft),
+        };
+        let tokens: TokensResponse = from_json(
+            contract
+                .query(deps.as_ref(), env.clone(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(tokens.tokens, expected);
+
+        let query_msg = QueryMsg::TokensByFilter {
+            filter: TraitFilter::Eq {
+                trait_type: "color".to_string(),
+                value: "red".to_string(),
+            },
+            start_after: None,
+            limit: None,
+            include_expired_nft: Some(include_expired_nft),
+        };
+        let tokens: TokensByFilterResponse = from_json(
+            contract
+                .query(deps.as_ref(), env.clone(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(tokens.tokens, expected);
+        assert_eq!(tokens.next_start_after, None);
+    }
+}
+
+#[test]
+fn test_nfts_by_extension() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let owner = addrs.addr("ark");
+
+    let mut env = mock_env();
+    for (token_id, expiration) in [
+        ("grow1", None),
+        (
+            "grow2",
+            Some(NftExpirationMsg::After(Duration::Time(2 * 86400))),
+        ),
+    ] {
+        let mint_msg = ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: Some(NftExtensionMsg {
+                description: Some("access pass".to_string()),
+                ..NftExtensionMsg::default()
+            }),
+            expiration,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+            .unwrap();
+    }
+
+    // expired nft is filtered out, unless included
+    env.block.time = env.block.time.plus_days(1);
+    for (include_expired_nft, expected) in [(false, vec!["grow2"]), (true, vec!["grow1", "grow2"])]
+    {
+        let query_msg = QueryMsg::NftsByExtension {
+            extension: Some(NftExtension {
+                description: Some("pass".to_string()),
+                ..NftExtension::default()
+            }),
+            start_after: None,
+            limit: None,
+            include_expired_nft: Some(include_expired_nft),
+        };
+        let nfts: NftsByExtensionResponse<DefaultOptionalNftExtension> = from_json(
+            contract
+                .query(deps.as_ref(), env.clone(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        let token_ids: Vec<_> = nfts.nfts.iter().map(|nft| nft.token_id.as_str()).collect();
+        assert_eq!(token_ids, expected);
+        assert_eq!(nfts.nfts[0].owner, owner.to_string());
+    }
+}
+
+#[test]
+fn test_balance_of() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let owner = addrs.addr("ark");
+
+    let mut env = mock_env();
+    for (token_id, expiration) in [
+        ("grow1", None),
+        (
+            "grow2",
+            Some(NftExpirationMsg::After(Duration::Time(2 * 86400))),
+        ),
+    ] {
+        let mint_msg = ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+            expiration,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+            .unwrap();
+    }
+    let balance_of = |deps: Deps, env: Env| {
+        let query_msg = QueryMsg::BalanceOf {
+            owner: owner.to_string(),
+        };
+        from_json::<BalanceOfResponse>(contract.query(deps, env, query_msg).unwrap())
+            .unwrap()
+            .balance
+    };
+
+    // expired nfts count until swept
+    env.block.time = env.block.time.plus_days(1);
+    assert_eq!(balance_of(deps.as_ref(), env.clone()), 2);
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("random"),
+            ExecuteMsg::SweepExpired { limit: None },
+        )
+        .unwrap();
+    assert_eq!(balance_of(deps.as_ref(), env), 1);
+}
+
+#[test]
+fn test_collection_stats_and_holders() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+
+    let mut env = mock_env();
+    for (token_id, owner, expiration) in [
+        ("grow1", "ark", None),
+        (
+            "grow2",
+            "demeter",
+            Some(NftExpirationMsg::After(Duration::Time(2 * 86400))),
+        ),
+    ] {
+        let mint_msg = ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: addrs.addr(owner).to_string(),
+            token_uri: None,
+            extension: None,
+            expiration,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+            .unwrap();
+    }
+
+    // sweeping counts as burning
+    env.block.time = env.block.time.plus_days(1);
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("random"),
+            ExecuteMsg::SweepExpired { limit: None },
+        )
+        .unwrap();
+    let stats: CollectionStatsResponse = from_json(
+        contract
+            .query(deps.as_ref(), env.clone(), QueryMsg::CollectionStats {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        (stats.minted, stats.burned, stats.supply, stats.holders),
+        (2, 1, 1, 1)
+    );
+    let query_msg = QueryMsg::Holders {
+        start_after: None,
+        limit: None,
+    };
+    let holders: HoldersResponse =
+        from_json(contract.query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+    assert_eq!(
+        holders.holders,
+        vec![Holder {
+            owner: addrs.addr("demeter").to_string(),
+            balance: 1,
+        }]
+    );
+}
+
+#[test]
+fn test_get_collection_extension_attribute() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let env = mock_env();
+
+    let msg = ExecuteMsg::UpdateCollectionExtensionAttributes {
+        set: vec![AttributeMsg {
+            attr_type: AttributeType::U64,
+            key: "max_supply".to_string(),
+            value: "10000".to_string(),
+            data: None,
+        }],
+        remove: vec![],
+    };
+    contract
+        .execute(deps.as_mut(), env.clone(), addrs.info("creator"), msg)
+        .unwrap();
+
+    let max_supply: Option<cw721::Attribute> = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetCollectionExtensionAttribute {
+                    key: "max_supply".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(max_supply.unwrap().value::<u64>().unwrap(), 10000);
+
+    // unset attribute
+    let unknown: Option<cw721::Attribute> = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                env,
+                QueryMsg::GetCollectionExtensionAttribute {
+                    key: "unknown".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(unknown, None);
+}
+
+#[test]
+fn test_approved_tokens_and_operator_grants_for() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let owner = addrs.addr("ark");
+    let market = addrs.addr("market");
+
+    let mut env = mock_env();
+    for (token_id, expiration) in [
+        ("grow1", None),
+        (
+            "grow2",
+            Some(NftExpirationMsg::After(Duration::Time(2 * 86400))),
+        ),
+    ] {
+        let mint_msg = ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+            expiration,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+            .unwrap();
+        let approve_msg = ExecuteMsg::Approve {
+            spender: market.to_string(),
+            token_id: token_id.to_string(),
+            expires: None,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), addrs.info("ark"), approve_msg)
+            .unwrap();
+    }
+    let approve_all_msg = ExecuteMsg::ApproveAll {
+        operator: market.to_string(),
+        expires: None,
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("ark"),
+            approve_all_msg,
+        )
+        .unwrap();
+
+    // expired nft is filtered out, unless included
+    env.block.time = env.block.time.plus_days(1);
+    for (include_expired_nft, expected) in [(false, vec!["grow2"]), (true, vec!["grow1", "grow2"])]
+    {
+        let query_msg = QueryMsg::ApprovedTokensFor {
+            spender: market.to_string(),
+            include_expired: None,
+            start_after: None,
+            limit: None,
+            include_expired_nft: Some(include_expired_nft),
+        };
+        let tokens: ApprovedTokensResponse = from_json(
+            contract
+                .query(deps.as_ref(), env.clone(), query_msg)
+                .unwrap(),
+        )
+        .unwrap();
+        let token_ids: Vec<_> = tokens.tokens.iter().map(|t| t.token_id.as_str()).collect();
+        assert_eq!(token_ids, expected);
+    }
+
+    let query_msg = QueryMsg::OperatorGrantsFor {
+        operator: market.to_string(),
+        include_expired: None,
+        start_after: None,
+        limit: None,
+    };
+    let grants: OperatorGrantsResponse =
+        from_json(contract.query(deps.as_ref(), env, query_msg).unwrap()).unwrap();
+    assert_eq!(grants.grants.len(), 1);
+    assert_eq!(grants.grants[0].owner, owner.to_string());
+}
+
+#[test]
+fn test_can_transfer_and_can_approve() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let owner = addrs.addr("ark");
+
+    let mut env = mock_env();
+    let mint_date = env.block.time;
+    let mint_msg = ExecuteMsg::Mint {
+        token_id: "grow1".to_string(),
+        owner: owner.to_string(),
+        token_uri: None,
+        extension: None,
+        expiration: None,
+    };
+    contract
+        .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+        .unwrap();
+
+    let can_transfer = QueryMsg::CanTransfer {
+        sender: owner.to_string(),
+        token_id: "grow1".to_string(),
+    };
+    let res: AuthorizationResponse = from_json(
+        contract
+            .query(deps.as_ref(), env.clone(), can_transfer.clone())
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, AuthorizationReason::Owner {}.into());
+    let can_approve = QueryMsg::CanApprove {
+        sender: addrs.addr("random").to_string(),
+        token_id: "grow1".to_string(),
+    };
+    let res: AuthorizationResponse = from_json(
+        contract
+            .query(deps.as_ref(), env.clone(), can_approve.clone())
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, AuthorizationReason::NotAuthorized {}.into());
+
+    // expired nft throws an error, like transfer and approve do
+    let expiration = env.block.time.plus_days(1);
+    env.block.time = expiration;
+    for query_msg in [can_transfer, can_approve] {
+        let err = contract
+            .query(deps.as_ref(), env.clone(), query_msg)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NftExpired {
+                token_id: "grow1".to_string(),
+                mint_date,
+                expiration: Expiration::AtTime(expiration),
+            }
+        );
+    }
+}
+
+#[test]
+fn test_tokens_with_info() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let owner = addrs.addr("ark");
+
+    let mut env = mock_env();
+    for (token_id, expiration) in [
+        ("grow1", None),
+        (
+            "grow2",
+            Some(NftExpirationMsg::After(Duration::Time(2 * 86400))),
+        ),
+    ] {
+        let mint_msg = ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+            expiration,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+            .unwrap();
+    }
+
+    // expired nft is filtered out, unless included
+    env.block.time = env.block.time.plus_days(1);
+    for include_expired_nft in [false, true] {
+        let expected = if include_expired_nft {
+            vec!["grow1", "grow2"]
+        } else {
+            vec!["grow2"]
+        };
+        for query_msg in [
+            QueryMsg::TokensWithInfo {
+                owner: owner.to_string(),
+                start_after: None,
+                limit: None,
+                include_expired: None,
+                include_expired_nft: Some(include_expired_nft),
+            },
+            QueryMsg::AllTokensWithInfo {
+                start_after: None,
+                limit: None,
+                include_expired: None,
+                include_expired_nft: Some(include_expired_nft),
+            },
+        ] {
+            let tokens: TokensWithInfoResponse<DefaultOptionalNftExtension> = from_json(
+                contract
+                    .query(deps.as_ref(), env.clone(), query_msg)
+                    .unwrap(),
+            )
+            .unwrap();
+            let token_ids: Vec<_> = tokens.tokens.iter().map(|t| t.token_id.as_str()).collect();
+            assert_eq!(token_ids, expected);
+            assert_eq!(tokens.tokens[0].owner, owner.to_string());
+        }
+    }
+}
+
+#[test]
+fn test_renew() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = DefaultCw721ExpirationContract::default();
+    let msg = InstantiateMsg {
+        expiration_days: 1,
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        collection_info_extension: None,
+        minter: Some(minter.to_string()),
+        creator: Some(creator.to_string()),
+        withdraw_address: None,
+        renewal_price: Some(coin(10, "ujuno")),
+        renewal_grace_days: Some(2),
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), addrs.info("creator"), msg)
+        .unwrap();
+
+    let token_id = "grow1".to_string();
+    let owner = addrs.addr("ark");
+    let mut env = mock_env();
+    let mint_date = env.block.time;
+    let mint_msg = ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: owner.to_string(),
+        token_uri: None,
+        extension: None,
+        expiration: None,
+    };
+    contract
+        .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+        .unwrap();
+
+    // renewal requires payment
+    let renew_msg = ExecuteMsg::Renew {
+        token_id: token_id.clone(),
+        days: 3,
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("random"),
+            renew_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Payment(PaymentError::NoFunds {}));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&addrs.addr("random"), &coins(10, "ujuno")),
+            renew_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidRenewalPayment {
+            expected: coin(30, "ujuno")
+        }
+    );
+
+    // anyone can renew by paying the price
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&addrs.addr("random"), &coins(30, "ujuno")),
+            renew_msg,
+        )
+        .unwrap();
+    let expiration = contract
+        .query_nft_expiration(deps.as_ref(), token_id.clone())
+        .unwrap();
+    assert_eq!(
+        expiration,
+        NftExpirationResponse {
+            mint_timestamp: mint_date,
+            expiration: Expiration::AtTime(mint_date.plus_days(4)),
+            renewable_until: Expiration::AtTime(mint_date.plus_days(6)),
+        }
+    );
+
+    // expired nft can be renewed within grace period, minter renews for free
+    // the renewed period counts from now, not from the previous expiration
+    env.block.time = mint_date.plus_days(5);
+    let err = contract
+        .query_owner_of_include_expired_nft(
+            deps.as_ref(),
+            env.clone(),
+            token_id.clone(),
+            false,
+            false,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NftExpired {
+            token_id: token_id.clone(),
+            mint_date,
+            expiration: Expiration::AtTime(mint_date.plus_days(4))
+        }
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("minter"),
+            ExecuteMsg::Renew {
+                token_id: token_id.clone(),
+                days: 1,
+            },
+        )
+        .unwrap();
+    contract
+        .query_owner_of_include_expired_nft(
+            deps.as_ref(),
+            env.clone(),
+            token_id.clone(),
+            false,
+            false,
+        )
+        .unwrap();
+    let expiration = contract
+        .query_nft_expiration(deps.as_ref(), token_id.clone())
+        .unwrap();
+    assert_eq!(
+        expiration.expiration,
+        Expiration::AtTime(mint_date.plus_days(6))
+    );
+
+    // renewal not possible after grace period
+    env.block.time = mint_date.plus_days(8);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("minter"),
+            ExecuteMsg::Renew {
+                token_id: token_id.clone(),
+                days: 1,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::RenewalPeriodOver {
+            token_id,
+            expiration: Expiration::AtTime(mint_date.plus_days(6))
+        }
+    );
+}
+
+#[test]
+fn test_renew_without_price() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+
+    let token_id = "grow1".to_string();
+    let mint_msg = ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: addrs.addr("ark").to_string(),
+        token_uri: None,
+        extension: None,
+        expiration: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), addrs.info("minter"), mint_msg)
+        .unwrap();
+
+    // only minter can renew
+    let renew_msg = ExecuteMsg::Renew {
+        token_id: token_id.clone(),
+        days: 1,
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            addrs.info("ark"),
+            renew_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Cw721(Cw721ContractError::NotMinter {}));
+    contract
+        .execute(deps.as_mut(), mock_env(), addrs.info("minter"), renew_msg)
+        .unwrap();
+    let expiration = contract
+        .query_nft_expiration(deps.as_ref(), token_id)
+        .unwrap();
+    assert_eq!(
+        expiration.expiration,
+        Expiration::AtTime(mock_env().block.time.plus_days(2))
+    );
+}
+
+#[test]
+fn test_mint_with_expiration() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 30, &creator, &minter);
+    let owner = addrs.addr("ark");
+    let mut env = mock_env();
+    let mint_msg = |token_id: &str, expiration: Option<NftExpirationMsg>| ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: owner.to_string(),
+        token_uri: None,
+        extension: None,
+        expiration,
+    };
+
+    // expiration must be in the future
+    for expiration in [
+        NftExpirationMsg::At(Expiration::Never {}),
+        NftExpirationMsg::At(Expiration::AtHeight(env.block.height)),
+        NftExpirationMsg::After(Duration::Time(0)),
+    ] {
+        let err = contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                addrs.info("minter"),
+                mint_msg("invalid", Some(expiration)),
+            )
+            .unwrap_err();
+        assert_eq!(err, ContractError::InvalidExpiration {});
+    }
+
+    // mint passes of different tiers
+    for (token_id, expiration) in [
+        ("monthly", None),
+        (
+            "yearly",
+            Some(NftExpirationMsg::After(Duration::Time(365 * 86400))),
+        ),
+        (
+            "blocks",
+            Some(NftExpirationMsg::At(Expiration::AtHeight(
+                env.block.height + 100,
+            ))),
+        ),
+    ] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                addrs.info("minter"),
+                mint_msg(token_id, expiration),
+            )
+            .unwrap();
+    }
+    let expiration = |token_id: &str, deps: &cosmwasm_std::OwnedDeps<_, _, _>| {
+        contract
+            .query_nft_expiration(deps.as_ref(), token_id.to_string())
+            .unwrap()
+            .expiration
+    };
+    assert_eq!(
+        expiration("monthly", &deps),
+        Expiration::AtTime(env.block.time.plus_days(30))
+    );
+    assert_eq!(
+        expiration("yearly", &deps),
+        Expiration::AtTime(env.block.time.plus_days(365))
+    );
+    assert_eq!(
+        expiration("blocks", &deps),
+        Expiration::AtHeight(env.block.height + 100)
+    );
+
+    // height based expiration
+    env.block.height += 100;
+    let err = contract
+        .query_nft_info_include_expired_nft(deps.as_ref(), env.clone(), "blocks".to_string(), false)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NftExpired {
+            token_id: "blocks".to_string(),
+            mint_date: mock_env().block.time,
+            expiration: Expiration::AtHeight(env.block.height)
+        }
+    );
+    contract
+        .query_nft_info_include_expired_nft(
+            deps.as_ref(),
+            env.clone(),
+            "monthly".to_string(),
+            false,
+        )
+        .unwrap();
+
+    // only time based expirations can be renewed
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            addrs.info("minter"),
+            ExecuteMsg::Renew {
+                token_id: "blocks".to_string(),
+                days: 1,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NotRenewable {
+            token_id: "blocks".to_string(),
+            expiration: Expiration::AtHeight(env.block.height)
+        }
+    );
+}
+
+#[test]
+fn test_expiring_between_and_sweep_expired() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let owner = addrs.addr("ark");
+    let mut env = mock_env();
+    let now = env.block.time;
+    for (token_id, expiration) in [
+        ("a", None),
+        (
+            "b",
+            Some(NftExpirationMsg::After(Duration::Time(3 * 86400))),
+        ),
+        (
+            "c",
+            Some(NftExpirationMsg::At(Expiration::AtHeight(
+                env.block.height + 10,
+            ))),
+        ),
+        (
+            "d",
+            Some(NftExpirationMsg::After(Duration::Time(2 * 86400))),
+        ),
+    ] {
+        let mint_msg = ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+            expiration,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+            .unwrap();
+    }
+
+    // time based expirations ordered by expiration, end is exclusive
+    let expiring = contract
+        .query_expiring_between(deps.as_ref(), now, now.plus_days(3), None, None)
+        .unwrap();
+    assert_eq!(
+        expiring,
+        ExpiringNftsResponse {
+            nfts: vec![
+                ExpiringNft {
+                    token_id: "a".to_string(),
+                    expiration: now.plus_days(1),
+                },
+                ExpiringNft {
+                    token_id: "d".to_string(),
+                    expiration: now.plus_days(2),
+                },
+            ]
+        }
+    );
+    let expiring = contract
+        .query_expiring_between(
+            deps.as_ref(),
+            now,
+            now.plus_days(4),
+            Some(ExpiringNft {
+                token_id: "a".to_string(),
+                expiration: now.plus_days(1),
+            }),
+            Some(1),
+        )
+        .unwrap();
+    assert_eq!(
+        expiring.nfts,
+        vec![ExpiringNft {
+            token_id: "d".to_string(),
+            expiration: now.plus_days(2),
+        }]
+    );
+
+    // nothing to sweep yet
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("random"),
+            ExecuteMsg::SweepExpired { limit: None },
+        )
+        .unwrap();
+    assert_eq!(res.events.len(), 0);
+
+    // anyone can sweep expired tokens
+    env.block.time = now.plus_days(2);
+    env.block.height += 10;
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("random"),
+            ExecuteMsg::SweepExpired { limit: Some(2) },
+        )
+        .unwrap();
+    let burned: Vec<_> = res
+        .events
+        .iter()
+        .map(|event| event.attributes[1].value.clone())
+        .collect();
+    assert_eq!(burned, vec!["c", "a"]);
+    let count = contract
+        .base_contract
+        .query_num_tokens(deps.as_ref().storage)
+        .unwrap();
+    assert_eq!(2, count.count);
+    contract
+        .query_nft_info_include_expired_nft(deps.as_ref(), env.clone(), "a".to_string(), true)
+        .unwrap_err();
+
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("random"),
+            ExecuteMsg::SweepExpired { limit: None },
+        )
+        .unwrap();
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(
+        contract
+            .query_all_tokens_include_expired_nft(deps.as_ref(), env, None, None, None, None, true)
+            .unwrap()
+            .tokens,
+        vec!["b"]
+    );
+    let count = contract
+        .base_contract
+        .query_num_tokens(deps.as_ref().storage)
+        .unwrap();
+    assert_eq!(1, count.count);
+}
+
+#[test]
+fn test_expiring_between_pagination_after_burn_and_renew() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let owner = addrs.addr("ark");
+    let env = mock_env();
+    let now = env.block.time;
+    for (token_id, days) in [("a", 1), ("b", 2), ("c", 3), ("d", 4)] {
+        let mint_msg = ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+            expiration: Some(NftExpirationMsg::After(Duration::Time(days * 86400))),
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+            .unwrap();
+    }
+    let expiring_between = |deps: Deps, start_after: Option<ExpiringNft>| {
+        contract
+            .query_expiring_between(deps, now, now.plus_days(10), start_after, Some(2))
+            .unwrap()
+            .nfts
+    };
+    let token_ids = |nfts: &[ExpiringNft]| -> Vec<String> {
+        nfts.iter().map(|nft| nft.token_id.clone()).collect()
+    };
+
+    // burning the last token of a page neither repeats nor skips tokens
+    let page = expiring_between(deps.as_ref(), None);
+    assert_eq!(token_ids(&page), vec!["a", "b"]);
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("ark"),
+            ExecuteMsg::Burn {
+                token_id: "b".to_string(),
+            },
+        )
+        .unwrap();
+    let page = expiring_between(deps.as_ref(), page.last().cloned());
+    assert_eq!(token_ids(&page), vec!["c", "d"]);
+
+    // renewing the last token of a page doesn't skip tokens expiring before its new expiration
+    let page = expiring_between(deps.as_ref(), None);
+    assert_eq!(token_ids(&page), vec!["a", "c"]);
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("minter"),
+            ExecuteMsg::Renew {
+                token_id: "c".to_string(),
+                days: 5,
+            },
+        )
+        .unwrap();
+    let page = expiring_between(deps.as_ref(), page.last().cloned());
+    assert_eq!(
+        page,
+        vec![
+            ExpiringNft {
+                token_id: "d".to_string(),
+                expiration: now.plus_days(4),
+            },
+            ExpiringNft {
+                token_id: "c".to_string(),
+                expiration: now.plus_days(8),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_migrate_from_cw721() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let owner = addrs.addr("ark");
+    let mut env = mock_env();
+    let now = env.block.time;
+
+    // legacy tokens minted by plain cw721, without mint timestamp and expiration
+    for token_id in ["a", "b", "c"] {
+        contract
+            .base_contract
+            .mint(
+                deps.as_mut(),
+                &env,
+                &addrs.info("minter"),
+                token_id.to_string(),
+                owner.to_string(),
+                None,
+                None,
+            )
+            .unwrap();
+    }
+    contract
+        .query_nft_expiration(deps.as_ref(), "a".to_string())
+        .unwrap_err();
+
+    // no backfill pending before migration
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("random"),
+            ExecuteMsg::BackfillExpirations { limit: None },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoBackfillPending {});
+
+    let migrate_msg = |expiration_days| MigrateMsg::FromCw721 {
+        minter: None,
+        creator: None,
+        expiration_days,
+        legacy_expiration: LegacyExpiration::AfterDays(5),
+        limit: Some(2),
+    };
+    let err = contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            migrate_msg(0),
+            "crates.io:cw721-expiration",
+            "0.0.0",
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::MinExpiration {});
+    let res = contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            migrate_msg(2),
+            "crates.io:cw721-expiration",
+            "0.0.0",
+        )
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "backfilled" && attr.value == "2"));
+    assert_eq!(contract.expiration_days.load(&deps.storage).unwrap(), 2);
+
+    // pending token resolves to the legacy expiration before it is backfilled
+    assert!(!contract.expirations.has(&deps.storage, "c"));
+    let expected = NftExpirationResponse {
+        mint_timestamp: now,
+        expiration: Expiration::AtTime(now.plus_days(5)),
+        renewable_until: Expiration::AtTime(now.plus_days(5)),
+    };
+    for token_id in ["a", "c"] {
+        assert_eq!(
+            contract
+                .query_nft_expiration(deps.as_ref(), token_id.to_string())
+                .unwrap(),
+            expected
+        );
+    }
+
+    // anyone can continue the backfill
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("random"),
+            ExecuteMsg::BackfillExpirations { limit: None },
+        )
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "backfill_done" && attr.value == "true"));
+    assert!(contract.expirations.has(&deps.storage, "c"));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            addrs.info("random"),
+            ExecuteMsg::BackfillExpirations { limit: None },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoBackfillPending {});
+
+    // new mints use the migrated expiration days
+    let mint_msg = ExecuteMsg::Mint {
+        token_id: "d".to_string(),
+        owner: owner.to_string(),
+        token_uri: None,
+        extension: None,
+        expiration: None,
+    };
+    contract
+        .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+        .unwrap();
+    assert_eq!(
+        contract.nft_expiration(&deps.storage, "d").unwrap(),
+        Expiration::AtTime(now.plus_days(2))
+    );
+
+    // legacy tokens expire with the chosen policy and are sweepable
+    env.block.time = now.plus_days(5);
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            env,
+            addrs.info("random"),
+            ExecuteMsg::SweepExpired { limit: None },
+        )
+        .unwrap();
+    assert_eq!(res.events.len(), 4);
+}
+
+#[test]
+fn test_migrate_with_update_backfills_expiry_index() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let owner = addrs.addr("ark");
+    let mut env = mock_env();
+    let now = env.block.time;
+    for token_id in ["a", "b"] {
+        let mint_msg = ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+            expiration: None,
+        };
+        contract
+            .execute(deps.as_mut(), env.clone(), addrs.info("minter"), mint_msg)
+            .unwrap();
+        // versions before the expiry index only stored mint timestamps
+        contract
+            .expirations
+            .remove(deps.as_mut().storage, token_id)
+            .unwrap();
+    }
+    let expiring = contract
+        .query_expiring_between(deps.as_ref(), now, now.plus_days(2), None, None)
+        .unwrap();
+    assert_eq!(expiring.nfts.len(), 0);
+
+    let res = contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+            },
+            "crates.io:cw721-expiration",
+            "0.0.0",
+        )
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "backfill_done" && attr.value == "true"));
+    let expiring = contract
+        .query_expiring_between(deps.as_ref(), now, now.plus_days(2), None, None)
+        .unwrap();
+    let token_ids: Vec<_> = expiring.nfts.into_iter().map(|nft| nft.token_id).collect();
+    assert_eq!(token_ids, vec!["a", "b"]);
+
+    // nothing to backfill on later migrations
+    let res = contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+            },
+            "crates.io:cw721-expiration",
+            "0.0.0",
+        )
+        .unwrap();
+    assert!(!res.attributes.iter().any(|attr| attr.key == "backfilled"));
+
+    // backfilled tokens are sweepable
+    env.block.time = now.plus_days(1);
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            env,
+            addrs.info("random"),
+            ExecuteMsg::SweepExpired { limit: None },
+        )
+        .unwrap();
+    assert_eq!(res.events.len(), 2);
+}
+
+#[test]
+fn test_migrate_from_cw721_never_expiring() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), 1, &creator, &minter);
+    let mut env = mock_env();
+    contract
+        .base_contract
+        .mint(
+            deps.as_mut(),
+            &env,
+            &addrs.info("minter"),
+            "legacy".to_string(),
+            addrs.addr("ark").to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+    contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::FromCw721 {
+                minter: None,
+                creator: None,
+                expiration_days: 1,
+                legacy_expiration: LegacyExpiration::Never {},
+                limit: None,
+            },
+            "crates.io:cw721-expiration",
+            "0.0.0",
+        )
+        .unwrap();
+    assert!(contract
+        .legacy_backfill
+        .may_load(&deps.storage)
+        .unwrap()
+        .is_none());
+
+    env.block.time = env.block.time.plus_days(1000);
+    contract
+        .query_nft_info_include_expired_nft(deps.as_ref(), env, "legacy".to_string(), false)
+        .unwrap();
+}
+
+#[test]
+fn test_generic_extensions() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let contract = Cw721ExpirationContract::<
+        EmptyOptionalNftExtension,
+        EmptyOptionalNftExtensionMsg,
+        EmptyOptionalCollectionExtension,
+        EmptyOptionalCollectionExtensionMsg,
+        Empty,
+        Empty,
+        Empty,
+    >::default();
+    let msg = Cw721ExpirationInstantiateMsg {
+        expiration_days: 1,
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        collection_info_extension: None,
+        minter: Some(addrs.addr("minter").to_string()),
+        creator: None,
+        withdraw_address: None,
+        renewal_price: None,
+        renewal_grace_days: None,
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), addrs.info("creator"), msg)
+        .unwrap();
+
+    let mint_msg = Cw721ExpirationExecuteMsg::Mint {
+        token_id: "grow1".to_string(),
+        owner: addrs.addr("ark").to_string(),
+        token_uri: None,
+        extension: None,
+        expiration: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), addrs.info("minter"), mint_msg)
+        .unwrap();
+
+    // expiration applies the same way for custom extensions
+    let mut env = mock_env();
+    let query_msg = Cw721ExpirationQueryMsg::NftInfo {
+        token_id: "grow1".to_string(),
+        include_expired_nft: None,
+    };
+    let info: NftInfoResponse<EmptyOptionalNftExtension> = from_json(
+        contract
+            .query(deps.as_ref(), env.clone(), query_msg.clone())
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(info.extension, None);
+    env.block.time = env.block.time.plus_days(1);
+    let err = contract
+        .query(deps.as_ref(), env.clone(), query_msg)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NftExpired {
+            token_id: "grow1".to_string(),
+            mint_date: mock_env().block.time,
+            expiration: Expiration::AtTime(env.block.time)
+        }
+    );
+}
@@ -1,6 +1,8 @@
-use cosmwasm_std::Timestamp;
+use cosmwasm_std::{Coin, Timestamp};
+use cw721::Expiration;
 
 use cw721::error::Cw721ContractError;
+use cw_utils::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -11,6 +13,9 @@ pub enum ContractError {
     #[error(transparent)]
     Cw721(#[from] Cw721ContractError),
 
+    #[error(transparent)]
+    Payment(#[from] PaymentError),
+
     #[error("A minimum expiration day of 1 must be set")]
     MinExpiration {},
 
@@ -18,6 +23,30 @@ pub enum ContractError {
     NftExpired {
         token_id: String,
         mint_date: Timestamp,
-        expiration: Timestamp,
+        expiration: Expiration,
+    },
+
+    #[error("Expiration must be in the future")]
+    InvalidExpiration {},
+
+    #[error("Token {token_id} expired at {expiration} and can no longer be renewed")]
+    RenewalPeriodOver {
+        token_id: String,
+        expiration: Expiration,
     },
+
+    #[error("Token {token_id} with {expiration} can't be renewed, only time based expirations are renewable")]
+    NotRenewable {
+        token_id: String,
+        expiration: Expiration,
+    },
+
+    #[error("Renewal requires a payment of {expected}")]
+    InvalidRenewalPayment { expected: Coin },
+
+    #[error("Renewal price must not be zero")]
+    InvalidRenewalPrice {},
+
+    #[error("No legacy tokens left to backfill")]
+    NoBackfillPending {},
 }
@@ -1,32 +1,82 @@
 use crate::{
-    error::ContractError, msg::InstantiateMsg, state::DefaultCw721ExpirationContract,
+    error::ContractError,
+    msg::{
+        Cw721ExpirationExecuteMsg, Cw721ExpirationInstantiateMsg, LegacyExpiration, MigrateMsg,
+        NftExpirationMsg,
+    },
+    state::{Cw721ExpirationContract, LegacyBackfill, EXPIRY_AT_HEIGHT, EXPIRY_AT_TIME},
     CONTRACT_NAME, CONTRACT_VERSION,
 };
-use cosmwasm_std::{Binary, DepsMut, Empty, Env, MessageInfo, Response};
+use cosmwasm_std::{
+    Binary, Coin, CustomMsg, DepsMut, Env, Event, MessageInfo, Order, Response, StdError,
+    StdResult, Storage,
+};
 use cw721::{
+    error::Cw721ContractError,
+    execute::assert_minter,
     msg::{Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721MigrateMsg},
-    traits::Cw721Execute,
+    traits::{
+        Cw721CustomMsg, Cw721Execute, Cw721State, FromAttributesState, StateFactory,
+        ToAttributesState,
+    },
     Expiration,
 };
-use cw721::{DefaultOptionalCollectionExtensionMsg, DefaultOptionalNftExtensionMsg};
+use cw_storage_plus::Bound;
+use cw_utils::must_pay;
+
+const DEFAULT_SWEEP_LIMIT: u32 = 10;
+const MAX_SWEEP_LIMIT: u32 = 50;
+const DEFAULT_BACKFILL_LIMIT: u32 = 100;
+const MAX_BACKFILL_LIMIT: u32 = 1000;
 
-impl DefaultCw721ExpirationContract<'static> {
+impl<
+        TNftExtension,
+        TNftExtensionMsg,
+        TCollectionExtension,
+        TCollectionExtensionMsg,
+        TExtensionMsg,
+        TExtensionQueryMsg,
+        TCustomResponseMsg,
+    >
+    Cw721ExpirationContract<
+        'static,
+        TNftExtension,
+        TNftExtensionMsg,
+        TCollectionExtension,
+        TCollectionExtensionMsg,
+        TExtensionMsg,
+        TExtensionQueryMsg,
+        TCustomResponseMsg,
+    >
+where
+    TNftExtension: Cw721State,
+    TNftExtensionMsg: Cw721CustomMsg + StateFactory<TNftExtension>,
+    TCollectionExtension: Cw721State + ToAttributesState + FromAttributesState,
+    TCollectionExtensionMsg: Cw721CustomMsg + StateFactory<TCollectionExtension>,
+    TCustomResponseMsg: CustomMsg,
+{
     // -- instantiate --
     pub fn instantiate(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        msg: InstantiateMsg,
-    ) -> Result<Response<Empty>, ContractError> {
+        msg: Cw721ExpirationInstantiateMsg<TCollectionExtensionMsg>,
+    ) -> Result<Response<TCustomResponseMsg>, ContractError> {
         if msg.expiration_days == 0 {
             return Err(ContractError::MinExpiration {});
         }
-        let contract = DefaultCw721ExpirationContract::default();
-        contract
-            .expiration_days
+        self.expiration_days
             .save(deps.storage, &msg.expiration_days)?;
-        Ok(contract.base_contract.instantiate_with_version(
+        if let Some(renewal_price) = msg.renewal_price {
+            if renewal_price.amount.is_zero() {
+                return Err(ContractError::InvalidRenewalPrice {});
+            }
+            self.renewal_price.save(deps.storage, &renewal_price)?;
+        }
+        self.renewal_grace_days
+            .save(deps.storage, &msg.renewal_grace_days.unwrap_or_default())?;
+        Ok(self.base_contract.instantiate_with_version(
             deps,
             &env,
             &info,
@@ -49,47 +99,227 @@ impl DefaultCw721ExpirationContract<'static> {
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        msg: Cw721ExecuteMsg<
-            DefaultOptionalNftExtensionMsg,
-            DefaultOptionalCollectionExtensionMsg,
-            Empty,
-        >,
-    ) -> Result<Response<Empty>, ContractError> {
-        let contract = DefaultCw721ExpirationContract::default();
-        match msg {
-            Cw721ExecuteMsg::Mint {
-                token_id,
-                owner,
-                token_uri,
-                extension,
-            } => {
-                contract.mint_with_timestamp(deps, env, info, token_id, owner, token_uri, extension)
-            }
-            Cw721ExecuteMsg::Approve {
-                spender,
-                token_id,
-                expires,
-            } => contract.approve_include_nft_expired(deps, env, info, spender, token_id, expires),
-            Cw721ExecuteMsg::Revoke { spender, token_id } => {
-                contract.revoke_include_nft_expired(deps, env, info, spender, token_id)
-            }
-            Cw721ExecuteMsg::TransferNft {
-                recipient,
+        msg: Cw721ExpirationExecuteMsg<TNftExtensionMsg, TCollectionExtensionMsg, TExtensionMsg>,
+    ) -> Result<Response<TCustomResponseMsg>, ContractError> {
+        let base_msg: Cw721ExecuteMsg<TNftExtensionMsg, TCollectionExtensionMsg, TExtensionMsg> =
+            match msg {
+                Cw721ExpirationExecuteMsg::Renew { token_id, days } => {
+                    return self.renew(deps, env, info, token_id, days)
+                }
+                Cw721ExpirationExecuteMsg::BackfillExpirations { limit } => {
+                    return self.execute_backfill_expirations(deps, limit)
+                }
+                Cw721ExpirationExecuteMsg::SweepExpired { limit } => {
+                    return self.sweep_expired(deps, env, info, limit)
+                }
+                Cw721ExpirationExecuteMsg::Mint {
+                    token_id,
+                    owner,
+                    token_uri,
+                    extension,
+                    expiration,
+                } => {
+                    return self.mint_with_timestamp(
+                        deps, env, info, token_id, owner, token_uri, extension, expiration,
+                    )
+                }
+                Cw721ExpirationExecuteMsg::Approve {
+                    spender,
+                    token_id,
+                    expires,
+                } => {
+                    return self
+                        .approve_include_nft_expired(deps, env, info, spender, token_id, expires)
+                }
+                Cw721ExpirationExecuteMsg::Revoke { spender, token_id } => {
+                    return self.revoke_include_nft_expired(deps, env, info, spender, token_id)
+                }
+                Cw721ExpirationExecuteMsg::TransferNft {
+                    recipient,
+                    token_id,
+                } => {
+                    return self
+                        .transfer_nft_include_nft_expired(deps, env, info, recipient, token_id)
+                }
+                Cw721ExpirationExecuteMsg::SendNft {
+                    contract: recipient,
+                    token_id,
+                    msg,
+                } => {
+                    return self
+                        .send_nft_include_nft_expired(deps, env, info, recipient, token_id, msg)
+                }
+                Cw721ExpirationExecuteMsg::Burn { token_id } => {
+                    return self.burn_nft_include_nft_expired(deps, env, info, token_id)
+                }
+                // -------- below is forwarded to cw721 --------
+                #[allow(deprecated)]
+                Cw721ExpirationExecuteMsg::UpdateOwnership(action) => {
+                    Cw721ExecuteMsg::UpdateOwnership(action)
+                }
+                Cw721ExpirationExecuteMsg::UpdateMinterOwnership(action) => {
+                    Cw721ExecuteMsg::UpdateMinterOwnership(action)
+                }
+                Cw721ExpirationExecuteMsg::UpdateCreatorOwnership(action) => {
+                    Cw721ExecuteMsg::UpdateCreatorOwnership(action)
+                }
+                Cw721ExpirationExecuteMsg::UpdateCollectionInfo { collection_info } => {
+                    Cw721ExecuteMsg::UpdateCollectionInfo { collection_info }
+                }
+                Cw721ExpirationExecuteMsg::ApproveAll { operator, expires } => {
+                    Cw721ExecuteMsg::ApproveAll { operator, expires }
+                }
+                Cw721ExpirationExecuteMsg::RevokeAll { operator } => {
+                    Cw721ExecuteMsg::RevokeAll { operator }
+                }
+                Cw721ExpirationExecuteMsg::AddMinter { minter } => {
+                    Cw721ExecuteMsg::AddMinter { minter }
+                }
+                Cw721ExpirationExecuteMsg::RemoveMinter { minter } => {
+                    Cw721ExecuteMsg::RemoveMinter { minter }
+                }
+                Cw721ExpirationExecuteMsg::UpdateCollectionExtensionAttributes { set, remove } => {
+                    Cw721ExecuteMsg::UpdateCollectionExtensionAttributes { set, remove }
+                }
+                Cw721ExpirationExecuteMsg::BackfillIndexes { limit } => {
+                    Cw721ExecuteMsg::BackfillIndexes { limit }
+                }
+                Cw721ExpirationExecuteMsg::UpdateNftInfo {
+                    token_id,
+                    token_uri,
+                    extension,
+                } => Cw721ExecuteMsg::UpdateNftInfo {
+                    token_id,
+                    token_uri,
+                    extension,
+                },
+                Cw721ExpirationExecuteMsg::SetWithdrawAddress { address } => {
+                    Cw721ExecuteMsg::SetWithdrawAddress { address }
+                }
+                Cw721ExpirationExecuteMsg::RemoveWithdrawAddress {} => {
+                    Cw721ExecuteMsg::RemoveWithdrawAddress {}
+                }
+                Cw721ExpirationExecuteMsg::WithdrawFunds { amount } => {
+                    Cw721ExecuteMsg::WithdrawFunds { amount }
+                }
+                Cw721ExpirationExecuteMsg::UpdateExtension { msg } => {
+                    Cw721ExecuteMsg::UpdateExtension { msg }
+                }
+            };
+        let response = self.base_contract.execute(deps, &env, &info, base_msg)?;
+        Ok(response)
+    }
+
+    /// Moves the expiration of a token forward by `days`. Expired tokens can be renewed within
+    /// the grace period, counting from now, so a renewed token is never left expired.
+    pub fn renew(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        days: u16,
+    ) -> Result<Response<TCustomResponseMsg>, ContractError> {
+        if days == 0 {
+            return Err(ContractError::MinExpiration {});
+        }
+        let expiration = self.nft_expiration(deps.storage, &token_id)?;
+        let Expiration::AtTime(expiration_time) = expiration else {
+            return Err(ContractError::NotRenewable {
                 token_id,
-            } => contract.transfer_nft_include_nft_expired(deps, env, info, recipient, token_id),
-            Cw721ExecuteMsg::SendNft {
-                contract: recipient,
+                expiration,
+            });
+        };
+        if self
+            .renewable_until(deps.storage, expiration)?
+            .is_expired(&env.block)
+        {
+            return Err(ContractError::RenewalPeriodOver {
                 token_id,
-                msg,
-            } => contract.send_nft_include_nft_expired(deps, env, info, recipient, token_id, msg),
-            Cw721ExecuteMsg::Burn { token_id } => {
-                contract.burn_nft_include_nft_expired(deps, env, info, token_id)
-            }
-            _ => {
-                let response = contract.base_contract.execute(deps, &env, &info, msg)?;
-                Ok(response)
+                expiration,
+            });
+        }
+        // minter renews for free, e.g. for subscriptions paid off-chain
+        if assert_minter(deps.storage, &info.sender).is_err() {
+            let price = self
+                .renewal_price
+                .may_load(deps.storage)?
+                .ok_or(Cw721ContractError::NotMinter {})?;
+            let expected = Coin {
+                amount: price
+                    .amount
+                    .checked_mul(days.into())
+                    .map_err(StdError::from)?,
+                denom: price.denom,
+            };
+            if must_pay(&info, &expected.denom)? != expected.amount {
+                return Err(ContractError::InvalidRenewalPayment { expected });
             }
         }
+        let new_expiration =
+            Expiration::AtTime(expiration_time.max(env.block.time).plus_days(days.into()));
+        self.expirations
+            .save(deps.storage, &token_id, &new_expiration)?;
+        Ok(Response::new()
+            .add_attribute("action", "renew")
+            .add_attribute("token_id", token_id)
+            .add_attribute("days", days.to_string())
+            .add_attribute("expiration", new_expiration.to_string()))
+    }
+
+    /// Burns up to `limit` tokens which are expired and past their renewal grace period
+    pub fn sweep_expired(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        limit: Option<u32>,
+    ) -> Result<Response<TCustomResponseMsg>, ContractError> {
+        let limit = limit.unwrap_or(DEFAULT_SWEEP_LIMIT).min(MAX_SWEEP_LIMIT) as usize;
+        let grace_days = self
+            .renewal_grace_days
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        let renewable_since = env
+            .block
+            .time
+            .nanos()
+            .saturating_sub(u64::from(grace_days) * 86_400 * 1_000_000_000);
+        // expired: height or time (minus grace period) <= current one
+        let expired_until = |kind: u8, value: u64| {
+            self.expirations.idx.expiry.range(
+                deps.storage,
+                Some(Bound::inclusive(((kind, 0), String::new()))),
+                Some(Bound::exclusive((
+                    (kind, value.saturating_add(1)),
+                    String::new(),
+                ))),
+                Order::Ascending,
+            )
+        };
+        let token_ids = expired_until(EXPIRY_AT_HEIGHT, env.block.height)
+            .chain(expired_until(EXPIRY_AT_TIME, renewable_since))
+            .take(limit)
+            .map(|item| item.map(|(token_id, _)| token_id))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let config = &self.base_contract.config;
+        let mut res = Response::new()
+            .add_attribute("action", "sweep_expired")
+            .add_attribute("sender", info.sender)
+            .add_attribute("count", token_ids.len().to_string());
+        for token_id in token_ids {
+            let token = config.nft_info.load(deps.storage, &token_id)?;
+            config.remove_token(deps.storage, &token_id, &token)?;
+            self.mint_timestamps.remove(deps.storage, &token_id);
+            self.expirations.remove(deps.storage, &token_id)?;
+            res = res.add_event(
+                Event::new("burn")
+                    .add_attribute("owner", token.owner)
+                    .add_attribute("token_id", token_id),
+            );
+        }
+        Ok(res)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -101,15 +331,30 @@ impl DefaultCw721ExpirationContract<'static> {
         token_id: String,
         owner: String,
         token_uri: Option<String>,
-        extension: DefaultOptionalNftExtensionMsg,
-    ) -> Result<Response<Empty>, ContractError> {
+        extension: TNftExtensionMsg,
+        expiration: Option<NftExpirationMsg>,
+    ) -> Result<Response<TCustomResponseMsg>, ContractError> {
         let mint_timstamp = env.block.time;
+        let expiration = match expiration {
+            Some(NftExpirationMsg::At(expiration)) => expiration,
+            Some(NftExpirationMsg::After(duration)) => duration.after(&env.block),
+            None => {
+                let expiration_days = self.expiration_days.load(deps.storage)?;
+                Expiration::AtTime(mint_timstamp.plus_days(expiration_days.into()))
+            }
+        };
+        if matches!(expiration, Expiration::Never {}) || expiration.is_expired(&env.block) {
+            return Err(ContractError::InvalidExpiration {});
+        }
         self.mint_timestamps
             .save(deps.storage, &token_id, &mint_timstamp)?;
+        self.expirations
+            .save(deps.storage, &token_id, &expiration)?;
         let res = self
             .base_contract
             .mint(deps, &env, &info, token_id, owner, token_uri, extension)?
-            .add_attribute("mint_timestamp", mint_timstamp.to_string());
+            .add_attribute("mint_timestamp", mint_timstamp.to_string())
+            .add_attribute("expiration", expiration.to_string());
         Ok(res)
     }
 
@@ -121,7 +366,7 @@ impl DefaultCw721ExpirationContract<'static> {
         spender: String,
         token_id: String,
         expires: Option<Expiration>,
-    ) -> Result<Response<Empty>, ContractError> {
+    ) -> Result<Response<TCustomResponseMsg>, ContractError> {
         self.assert_nft_expired(deps.as_ref(), &env, token_id.as_str())?;
         Ok(self
             .base_contract
@@ -135,7 +380,7 @@ impl DefaultCw721ExpirationContract<'static> {
         info: MessageInfo,
         spender: String,
         token_id: String,
-    ) -> Result<Response<Empty>, ContractError> {
+    ) -> Result<Response<TCustomResponseMsg>, ContractError> {
         self.assert_nft_expired(deps.as_ref(), &env, token_id.as_str())?;
         Ok(self
             .base_contract
@@ -149,7 +394,7 @@ impl DefaultCw721ExpirationContract<'static> {
         info: MessageInfo,
         recipient: String,
         token_id: String,
-    ) -> Result<Response<Empty>, ContractError> {
+    ) -> Result<Response<TCustomResponseMsg>, ContractError> {
         self.assert_nft_expired(deps.as_ref(), &env, token_id.as_str())?;
         Ok(self
             .base_contract
@@ -164,7 +409,7 @@ impl DefaultCw721ExpirationContract<'static> {
         contract: String,
         token_id: String,
         msg: Binary,
-    ) -> Result<Response<Empty>, ContractError> {
+    ) -> Result<Response<TCustomResponseMsg>, ContractError> {
         self.assert_nft_expired(deps.as_ref(), &env, token_id.as_str())?;
         Ok(self
             .base_contract
@@ -173,26 +418,178 @@ impl DefaultCw721ExpirationContract<'static> {
 
     pub fn burn_nft_include_nft_expired(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         token_id: String,
-    ) -> Result<Response<Empty>, ContractError> {
+    ) -> Result<Response<TCustomResponseMsg>, ContractError> {
         self.assert_nft_expired(deps.as_ref(), &env, token_id.as_str())?;
-        Ok(self.base_contract.burn_nft(deps, &env, &info, token_id)?)
+        let res = self
+            .base_contract
+            .burn_nft(deps.branch(), &env, &info, token_id.clone())?;
+        self.mint_timestamps.remove(deps.storage, &token_id);
+        self.expirations.remove(deps.storage, &token_id)?;
+        Ok(res)
     }
 
     // -- migrate --
     pub fn migrate(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
-        msg: Cw721MigrateMsg,
+        msg: MigrateMsg,
         contract_name: &str,
         contract_version: &str,
-    ) -> Result<Response<Empty>, ContractError> {
-        Ok(self
+    ) -> Result<Response, ContractError> {
+        match msg {
+            MigrateMsg::WithUpdate { minter, creator } => {
+                let response = self.base_contract.migrate(
+                    deps.branch(),
+                    env.clone(),
+                    Cw721MigrateMsg::WithUpdate { minter, creator },
+                    contract_name,
+                    contract_version,
+                )?;
+                if !self.needs_expiry_index_backfill(deps.storage)? {
+                    return Ok(response);
+                }
+                // versions before the expiry index only stored mint timestamps
+                self.legacy_backfill.save(
+                    deps.storage,
+                    &LegacyBackfill {
+                        expiration: Expiration::Never {},
+                        migrated_at: env.block.time,
+                        last_token_id: None,
+                    },
+                )?;
+                let (count, done) = self.backfill_expirations(deps.storage, None)?;
+                Ok(response
+                    .add_attribute("backfilled", count.to_string())
+                    .add_attribute("backfill_done", done.to_string()))
+            }
+            MigrateMsg::FromCw721 {
+                minter,
+                creator,
+                expiration_days,
+                legacy_expiration,
+                limit,
+            } => {
+                if expiration_days == 0 {
+                    return Err(ContractError::MinExpiration {});
+                }
+                let response = self.base_contract.migrate(
+                    deps.branch(),
+                    env.clone(),
+                    Cw721MigrateMsg::WithUpdate { minter, creator },
+                    contract_name,
+                    contract_version,
+                )?;
+                self.expiration_days.save(deps.storage, &expiration_days)?;
+                let expiration = match legacy_expiration {
+                    LegacyExpiration::AfterDays(days) => {
+                        Expiration::AtTime(env.block.time.plus_days(days.into()))
+                    }
+                    LegacyExpiration::Never {} => Expiration::Never {},
+                };
+                self.legacy_backfill.save(
+                    deps.storage,
+                    &LegacyBackfill {
+                        expiration,
+                        migrated_at: env.block.time,
+                        last_token_id: None,
+                    },
+                )?;
+                let (count, done) = self.backfill_expirations(deps.storage, limit)?;
+                Ok(response
+                    .add_attribute("expiration_days", expiration_days.to_string())
+                    .add_attribute("legacy_expiration", expiration.to_string())
+                    .add_attribute("backfilled", count.to_string())
+                    .add_attribute("backfill_done", done.to_string()))
+            }
+        }
+    }
+
+    /// Tokens exist, but none of them is in the expiry index and no backfill is pending.
+    fn needs_expiry_index_backfill(&self, storage: &dyn Storage) -> StdResult<bool> {
+        if self.legacy_backfill.exists(storage) {
+            return Ok(false);
+        }
+        let has_tokens = self
+            .base_contract
+            .config
+            .nft_info
+            .keys(storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+        let has_expirations = self
+            .expirations
+            .keys(storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+        Ok(has_tokens && !has_expirations)
+    }
+
+    pub fn execute_backfill_expirations(
+        &self,
+        deps: DepsMut,
+        limit: Option<u32>,
+    ) -> Result<Response<TCustomResponseMsg>, ContractError> {
+        let (count, done) = self.backfill_expirations(deps.storage, limit)?;
+        Ok(Response::new()
+            .add_attribute("action", "backfill_expirations")
+            .add_attribute("backfilled", count.to_string())
+            .add_attribute("backfill_done", done.to_string()))
+    }
+
+    /// Stores expirations for up to `limit` tokens minted before migration. Returns the number of
+    /// backfilled tokens and whether all tokens are backfilled.
+    fn backfill_expirations(
+        &self,
+        storage: &mut dyn Storage,
+        limit: Option<u32>,
+    ) -> Result<(usize, bool), ContractError> {
+        let mut backfill = self
+            .legacy_backfill
+            .may_load(storage)?
+            .ok_or(ContractError::NoBackfillPending {})?;
+        let limit = limit
+            .unwrap_or(DEFAULT_BACKFILL_LIMIT)
+            .min(MAX_BACKFILL_LIMIT) as usize;
+        let start = backfill.last_token_id.as_deref().map(Bound::exclusive);
+        let token_ids = self
             .base_contract
-            .migrate(deps, env, msg, contract_name, contract_version)?)
+            .config
+            .nft_info
+            .keys(storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+        let mut count = 0;
+        for token_id in &token_ids {
+            if self.expirations.has(storage, token_id) {
+                continue;
+            }
+            // tokens minted without a stored expiration keep mint timestamp + expiration days
+            let expiration = match self.mint_timestamps.may_load(storage, token_id)? {
+                Some(mint_date) => {
+                    let expiration_days = self.expiration_days.load(storage)?;
+                    Expiration::AtTime(mint_date.plus_days(expiration_days.into()))
+                }
+                None => {
+                    self.mint_timestamps
+                        .save(storage, token_id, &backfill.migrated_at)?;
+                    backfill.expiration
+                }
+            };
+            self.expirations.save(storage, token_id, &expiration)?;
+            count += 1;
+        }
+        let done = token_ids.len() < limit;
+        if done {
+            self.legacy_backfill.remove(storage);
+        } else {
+            backfill.last_token_id = token_ids.last().cloned();
+            self.legacy_backfill.save(storage, &backfill)?;
+        }
+        Ok((count, done))
     }
 }
@@ -1,4 +1,4 @@
-mod error;
+pub mod error;
 mod execute;
 #[allow(deprecated)]
 pub mod msg;
@@ -8,7 +8,6 @@ pub mod state;
 #[cfg(test)]
 mod contract_tests;
 
-use cosmwasm_std::Empty;
 use cw721::DefaultOptionalNftExtension;
 
 // Version info for migration
@@ -22,7 +21,7 @@ pub type NftInfo = cw721::state::NftInfo<DefaultOptionalNftExtension>;
 pub mod entry {
     use crate::{
         error::ContractError,
-        msg::{InstantiateMsg, QueryMsg},
+        msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
         state::DefaultCw721ExpirationContract,
     };
 
@@ -31,9 +30,6 @@ pub mod entry {
     #[cfg(not(feature = "library"))]
     use cosmwasm_std::entry_point;
     use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response};
-    use cw721::{
-        msg::Cw721ExecuteMsg, DefaultOptionalCollectionExtensionMsg, DefaultOptionalNftExtensionMsg,
-    };
 
     // This makes a conscious choice on the various generics used by the contract
     #[cfg_attr(not(feature = "library"), entry_point)]
@@ -47,23 +43,19 @@ pub mod entry {
         contract.instantiate(deps, env, info, msg)
     }
 
-    #[entry_point]
+    #[cfg_attr(not(feature = "library"), entry_point)]
     pub fn execute(
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        msg: Cw721ExecuteMsg<
-            DefaultOptionalNftExtensionMsg,
-            DefaultOptionalCollectionExtensionMsg,
-            Empty,
-        >,
+        msg: ExecuteMsg,
     ) -> Result<Response, ContractError> {
         let contract = DefaultCw721ExpirationContract::default();
         contract.execute(deps, env, info, msg)
     }
 
-    #[entry_point]
-    pub fn query(deps: Deps, env: Env, msg: QueryMsg<Empty>) -> Result<Binary, ContractError> {
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
         let contract = DefaultCw721ExpirationContract::default();
         contract.query(deps, env, msg)
     }
@@ -72,7 +64,7 @@ pub mod entry {
     pub fn migrate(
         deps: DepsMut,
         env: Env,
-        msg: cw721::msg::Cw721MigrateMsg,
+        msg: crate::msg::MigrateMsg,
     ) -> Result<Response, ContractError> {
         let contract = DefaultCw721ExpirationContract::default();
         contract.migrate(deps, env, msg, CONTRACT_NAME, CONTRACT_VERSION)
@@ -107,6 +99,8 @@ mod tests {
                 minter: Some(minter.to_string()),
                 creator: Some(creator.to_string()),
                 withdraw_address: None,
+                renewal_price: None,
+                renewal_grace_days: None,
             },
         )
         .unwrap_err();
@@ -125,6 +119,8 @@ mod tests {
                 minter: Some(minter.to_string()),
                 creator: Some(creator.to_string()),
                 withdraw_address: None,
+                renewal_price: None,
+                renewal_grace_days: None,
             },
         )
         .unwrap();
@@ -1,19 +1,58 @@
 use crate::{DefaultOptionalNftExtension, MinterResponse};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Empty};
+use cosmwasm_std::{Addr, Binary, Coin, Empty, Timestamp};
 use cw721::{
-    msg::{CollectionInfoAndExtensionResponse, Cw721ExecuteMsg, Cw721MigrateMsg},
+    msg::{
+        AttributeMsg, CollectionInfoAndExtensionResponse, CollectionInfoMsg, OrderBy, TraitFilter,
+    },
     DefaultOptionalCollectionExtension, DefaultOptionalCollectionExtensionMsg,
-    DefaultOptionalNftExtensionMsg,
+    DefaultOptionalNftExtensionMsg, Expiration,
 };
-use cw_ownable::Ownership;
+use cw_ownable::{Action, Ownership};
+use cw_utils::Duration;
+
+pub type InstantiateMsg = Cw721ExpirationInstantiateMsg<DefaultOptionalCollectionExtensionMsg>;
+pub type ExecuteMsg = Cw721ExpirationExecuteMsg<
+    DefaultOptionalNftExtensionMsg,
+    DefaultOptionalCollectionExtensionMsg,
+    Empty,
+>;
+pub type QueryMsg =
+    Cw721ExpirationQueryMsg<DefaultOptionalNftExtension, DefaultOptionalCollectionExtension, Empty>;
+
+#[cw_serde]
+pub enum MigrateMsg {
+    // -------- below is from cw721/src/msg.rs --------
+    /// Contracts migrated from a version without the expiry index are backfilled, remaining
+    /// tokens via `ExecuteMsg::BackfillExpirations`.
+    WithUpdate {
+        minter: Option<String>,
+        creator: Option<String>,
+    },
+    /// Migrates a plain cw721 collection (e.g. cw721-base or cw721-metadata-onchain) into
+    /// cw721-expiration. Up to `limit` existing tokens are backfilled with the legacy expiration
+    /// during migration, remaining ones via `ExecuteMsg::BackfillExpirations`.
+    FromCw721 {
+        minter: Option<String>,
+        creator: Option<String>,
+        /// Default expiration for tokens minted after migration, max 65535 days
+        expiration_days: u16,
+        legacy_expiration: LegacyExpiration,
+        limit: Option<u32>,
+    },
+}
 
-pub type ExecuteMsg =
-    Cw721ExecuteMsg<DefaultOptionalNftExtensionMsg, DefaultOptionalCollectionExtensionMsg, Empty>;
-pub type MigrateMsg = Cw721MigrateMsg;
+/// Expiration of tokens minted before migrating into cw721-expiration
+#[cw_serde]
+pub enum LegacyExpiration {
+    /// Legacy tokens expire the given number of days after migration
+    AfterDays(u16),
+    /// Legacy tokens never expire
+    Never {},
+}
 
 #[cw_serde]
-pub struct InstantiateMsg {
+pub struct Cw721ExpirationInstantiateMsg<TCollectionExtensionMsg> {
     /// max 65535 days
     pub expiration_days: u16,
 
@@ -23,7 +62,7 @@ pub struct InstantiateMsg {
     /// Symbol of the NFT contract
     pub symbol: String,
     /// Optional extension of the collection metadata
-    pub collection_info_extension: DefaultOptionalCollectionExtensionMsg,
+    pub collection_info_extension: TCollectionExtensionMsg,
 
     /// The minter is the only one who can create new NFTs.
     /// This is designed for a base NFT that is controlled by an external program
@@ -34,11 +73,176 @@ pub struct InstantiateMsg {
     pub creator: Option<String>,
 
     pub withdraw_address: Option<String>,
+
+    /// Optional price per renewed day. If set, anyone may renew a token by paying
+    /// `days * renewal_price`. Otherwise only the minter may renew tokens.
+    pub renewal_price: Option<Coin>,
+    /// Number of days after expiration in which a token can still be renewed, defaults to 0
+    pub renewal_grace_days: Option<u16>,
+}
+
+#[cw_serde]
+pub enum Cw721ExpirationExecuteMsg<
+    // NftInfo extension msg for onchain metadata.
+    TNftExtensionMsg,
+    // CollectionInfo extension msg for onchain collection attributes.
+    TCollectionExtensionMsg,
+    // Custom extension msg for custom contract logic. Default implementation is a no-op.
+    TExtensionMsg,
+> {
+    /// Extends the expiration of a token by the given number of days. Expired tokens can be
+    /// renewed within the grace period. If a renewal price is set, the exact payment is required,
+    /// unless sent by the minter.
+    Renew {
+        token_id: String,
+        days: u16,
+    },
+    /// Backfills up to `limit` tokens minted before migrating into cw721-expiration with the
+    /// legacy expiration. Anyone can call this.
+    BackfillExpirations {
+        limit: Option<u32>,
+    },
+    /// Burns tokens that are expired and can no longer be renewed. Anyone can call this.
+    SweepExpired {
+        limit: Option<u32>,
+    },
+
+    // -------- below is from cw721/src/msg.rs --------
+    #[deprecated(since = "0.19.0", note = "Please use UpdateMinterOwnership instead")]
+    /// Deprecated: use UpdateMinterOwnership instead! Will be removed in next release!
+    UpdateOwnership(Action),
+    UpdateMinterOwnership(Action),
+    UpdateCreatorOwnership(Action),
+
+    /// The creator is the only one eligible to update `CollectionInfo`.
+    UpdateCollectionInfo {
+        collection_info: CollectionInfoMsg<TCollectionExtensionMsg>,
+    },
+    /// Transfer is a base message to move a token to another account without triggering actions
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    /// Send is a base message to transfer a token to a contract and trigger an action
+    /// on the receiving contract.
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// Allows operator to transfer / send the token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted Approval
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// Allows operator to transfer / send any token from the owner's account.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted ApproveAll permission
+    RevokeAll {
+        operator: String,
+    },
+
+    /// Mint a new NFT, can only be called by the contract minter
+    Mint {
+        /// Unique ID of the NFT
+        token_id: String,
+        /// The owner of the newly minter NFT
+        owner: String,
+        /// Universal resource identifier for this NFT
+        /// Should point to a JSON file that conforms to the ERC721
+        /// Metadata JSON Schema
+        token_uri: Option<String>,
+        /// Any custom extension used by this contract
+        extension: TNftExtensionMsg,
+        /// Optional expiration of this NFT, defaults to mint time + `expiration_days`
+        expiration: Option<NftExpirationMsg>,
+    },
+
+    /// Burn an NFT the sender has access to
+    Burn {
+        token_id: String,
+    },
+
+    /// Add an additional minter. Only the primary minter (manager) can call this.
+    AddMinter {
+        minter: String,
+    },
+    /// Remove an additional minter. Only the primary minter (manager) can call this.
+    RemoveMinter {
+        minter: String,
+    },
+
+    /// The creator is the only one eligible to add, update and remove custom attributes
+    /// of the collection extension. Keys of the default collection extension are reserved
+    /// and can only be changed via `UpdateCollectionInfo`.
+    UpdateCollectionExtensionAttributes {
+        /// Attributes to add, existing attributes with the same key are overwritten.
+        set: Vec<AttributeMsg>,
+        /// Keys of attributes to remove.
+        remove: Vec<String>,
+    },
+    /// Backfills indexes of collections created before they existed, in batches of `limit`
+    /// tokens. Started on migration, anyone can continue until done.
+    BackfillIndexes {
+        limit: Option<u32>,
+    },
+
+    /// Custom msg execution. This is a no-op in default implementation.
+    UpdateExtension {
+        msg: TExtensionMsg,
+    },
+
+    /// The creator is the only one eligible to update NFT's token uri and onchain metadata (`NftInfo.extension`).
+    /// NOTE: approvals and owner are not affected by this call, since they belong to the NFT owner.
+    UpdateNftInfo {
+        token_id: String,
+        /// NOTE: Empty string is handled as None
+        token_uri: Option<String>,
+        extension: TNftExtensionMsg,
+    },
+
+    /// Sets address to send withdrawn fees to. Only owner can call this.
+    SetWithdrawAddress {
+        address: String,
+    },
+    /// Removes the withdraw address, so fees are sent to the contract. Only owner can call this.
+    RemoveWithdrawAddress {},
+    /// Withdraw from the contract to the given address. Anyone can call this,
+    /// which is okay since withdraw address has been set by owner.
+    WithdrawFunds {
+        amount: Coin,
+    },
+}
+
+#[cw_serde]
+pub enum NftExpirationMsg {
+    /// NFT expires at the given height or time
+    At(Expiration),
+    /// NFT expires after the given number of blocks or seconds from mint
+    After(Duration),
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
-pub enum QueryMsg<TExtensionQueryMsg> {
+pub enum Cw721ExpirationQueryMsg<
+    // Return type of NFT metadata defined in `NftInfo` and `AllNftInfo`.
+    TNftExtension,
+    // Return type of collection extension defined in `GetCollectionInfo`.
+    TCollectionExtension,
+    // Custom query msg for custom contract logic. Default implementation returns an empty binary.
+    TExtensionQueryMsg,
+> {
     // -------- below adds `include_expired_nft` prop to cw721/src/msg.rs --------
     /// Return the owner of the given token, error if token does not exist
     #[returns(cw721::msg::OwnerOfResponse)]
@@ -70,17 +274,51 @@ pub enum QueryMsg<TExtensionQueryMsg> {
     /// With MetaData Extension.
     /// Returns metadata about one particular token, based on *ERC721 Metadata JSON Schema*
     /// but directly from the contract
-    #[returns(cw721::msg::NftInfoResponse<DefaultOptionalNftExtension>)]
+    #[returns(cw721::msg::NftInfoResponse<TNftExtension>)]
     NftInfo {
         token_id: String,
         /// unset or false will filter out expired nfts, you must set to true to see them
         include_expired_nft: Option<bool>,
     },
 
-    #[returns(Option<cw721::msg::NftInfoResponse<DefaultOptionalNftExtension>>)]
+    #[returns(Option<cw721::msg::NftInfoResponse<TNftExtension>>)]
     GetNftByExtension {
         token_id: String,
-        extension: DefaultOptionalNftExtension,
+        extension: TNftExtension,
+        /// unset or false will filter out expired nfts, you must set to true to see them
+        include_expired_nft: Option<bool>,
+    },
+
+    /// Like `GetNftByExtension`, but matches include `token_id` and `owner`.
+    /// `limit` bounds the number of scanned tokens, use `next_start_after` for paging.
+    #[returns(cw721::msg::NftsByExtensionResponse<TNftExtension>)]
+    NftsByExtension {
+        extension: TNftExtension,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// unset or false will filter out expired nfts, you must set to true to see them
+        include_expired_nft: Option<bool>,
+    },
+
+    /// Lists token_ids whose traits match `filter`.
+    /// `limit` bounds the number of scanned tokens, use `next_start_after` for paging.
+    #[returns(cw721::msg::TokensByFilterResponse)]
+    TokensByFilter {
+        filter: TraitFilter,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// unset or false will filter out expired nfts, you must set to true to see them
+        include_expired_nft: Option<bool>,
+    },
+
+    /// Lists token_ids having the given trait, using the trait index.
+    /// NOTE: incomplete while `BackfillIndexes` is pending after migration.
+    #[returns(cw721::msg::TokensResponse)]
+    TokensByTrait {
+        trait_type: String,
+        value: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
         /// unset or false will filter out expired nfts, you must set to true to see them
         include_expired_nft: Option<bool>,
     },
@@ -88,7 +326,7 @@ pub enum QueryMsg<TExtensionQueryMsg> {
     /// With MetaData Extension.
     /// Returns the result of both `NftInfo` and `OwnerOf` as one query as an optimization
     /// for clients
-    #[returns(cw721::msg::AllNftInfoResponse<DefaultOptionalNftExtension>)]
+    #[returns(cw721::msg::AllNftInfoResponse<TNftExtension>)]
     AllNftInfo {
         token_id: String,
         /// unset or false will filter out expired approvals, you must set to true to see them
@@ -104,6 +342,10 @@ pub enum QueryMsg<TExtensionQueryMsg> {
         owner: String,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
         /// unset or false will filter out expired nfts, you must set to true to see them
         include_expired_nft: Option<bool>,
     },
@@ -114,10 +356,62 @@ pub enum QueryMsg<TExtensionQueryMsg> {
     AllTokens {
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
+        /// unset or false will filter out expired nfts, you must set to true to see them
+        include_expired_nft: Option<bool>,
+    },
+
+    /// With Enumerable extension.
+    /// Returns all tokens owned by the given address including their details, [] if unset.
+    #[returns(cw721::msg::TokensWithInfoResponse<TNftExtension>)]
+    TokensWithInfo {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+        /// unset or false will filter out expired nfts, you must set to true to see them
+        include_expired_nft: Option<bool>,
+    },
+
+    /// With Enumerable extension.
+    /// Requires pagination. Lists all tokens controlled by the contract including their details.
+    #[returns(cw721::msg::TokensWithInfoResponse<TNftExtension>)]
+    AllTokensWithInfo {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
         /// unset or false will filter out expired nfts, you must set to true to see them
         include_expired_nft: Option<bool>,
     },
 
+    /// Lists tokens `spender` is approved to transfer, ordered by token_id.
+    /// NOTE: incomplete while `BackfillIndexes` is pending after migration.
+    #[returns(cw721::msg::ApprovedTokensResponse)]
+    ApprovedTokensFor {
+        spender: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        /// token_id
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// unset or false will filter out expired nfts, you must set to true to see them
+        include_expired_nft: Option<bool>,
+    },
+
+    /// Dry-run of transferring (or sending, burning) a token: returns whether `sender`
+    /// is authorized and why. Throws an error for expired NFTs, like execution does.
+    #[returns(cw721::msg::AuthorizationResponse)]
+    CanTransfer { sender: String, token_id: String },
+    /// Dry-run of approving a spender for a token: returns whether `sender` is authorized and why.
+    /// Throws an error for expired NFTs, like execution does.
+    #[returns(cw721::msg::AuthorizationResponse)]
+    CanApprove { sender: String, token_id: String },
+
     // -------- below is from cw721/src/msg.rs --------
     /// Return approval of a given operator for all tokens of an owner, error if not set
     #[returns(cw721::msg::OperatorResponse)]
@@ -134,22 +428,52 @@ pub enum QueryMsg<TExtensionQueryMsg> {
         include_expired: Option<bool>,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
+    },
+    /// Lists owners who granted `operator` control over all their tokens, ordered by owner.
+    /// NOTE: incomplete while `BackfillIndexes` is pending after migration.
+    #[returns(cw721::msg::OperatorGrantsResponse)]
+    OperatorGrantsFor {
+        operator: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        /// owner
+        start_after: Option<String>,
+        limit: Option<u32>,
     },
-    /// Total number of tokens issued, including all expired NFTs
+    /// Total number of tokens issued, including expired NFTs which are not swept yet
     #[returns(cw721::msg::NumTokensResponse)]
     NumTokens {},
+    /// Minted and burned totals, current supply, holders and mint timestamps.
+    /// Expired NFTs count until swept, sweeping counts as burning.
+    /// NOTE: `holders` is incomplete while `BackfillIndexes` is pending after migration.
+    #[returns(cw721::msg::CollectionStatsResponse)]
+    CollectionStats {},
+    /// Number of tokens held by the given owner, including expired NFTs which are not swept yet
+    #[returns(cw721::msg::BalanceOfResponse)]
+    BalanceOf { owner: String },
+    /// Lists distinct owners and their number of tokens, ordered by owner address.
+    /// Expired NFTs count until swept.
+    #[returns(cw721::msg::HoldersResponse)]
+    Holders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     #[deprecated(
         since = "0.19.0",
         note = "Please use GetCollectionInfoAndExtension instead"
     )]
-    #[returns(CollectionInfoAndExtensionResponse<cw721::DefaultOptionalCollectionExtension>)]
+    #[returns(CollectionInfoAndExtensionResponse<TCollectionExtension>)]
     /// Deprecated: use GetCollectionInfoAndExtension instead! Will be removed in next release!
     ContractInfo {},
 
     /// With MetaData Extension.
     /// Returns top-level metadata about the contract
-    #[returns(CollectionInfoAndExtensionResponse<DefaultOptionalCollectionExtension>)]
+    #[returns(CollectionInfoAndExtensionResponse<TCollectionExtension>)]
     GetCollectionInfo {},
 
     #[deprecated(since = "0.19.0", note = "Please use GetMinterOwnership instead")]
@@ -180,10 +504,46 @@ pub enum QueryMsg<TExtensionQueryMsg> {
     /// This is a workaround and dummy query like (same as for Extension) for avoiding this compiler error:
     /// `cannot infer type for type parameter `TCollectionExtension` declared on the enum `QueryMsg`
     #[returns(())]
-    GetCollectionExtension {
-        msg: DefaultOptionalCollectionExtension,
-    },
+    GetCollectionExtension { msg: TCollectionExtension },
+
+    /// Returns a single attribute of the collection extension, None if unset.
+    #[returns(Option<cw721::Attribute>)]
+    GetCollectionExtensionAttribute { key: String },
 
     #[returns(Option<String>)]
     GetWithdrawAddress {},
+
+    /// Returns mint timestamp, expiration and renewal deadline of a token, including expired ones
+    #[returns(NftExpirationResponse)]
+    NftExpiration { token_id: String },
+
+    /// Lists tokens with a time based expiration within `start` (inclusive) and `end` (exclusive),
+    /// ordered by expiration. Includes expired tokens, unless swept.
+    /// For paging, `start_after` is the last `ExpiringNft` of the previous page.
+    #[returns(ExpiringNftsResponse)]
+    ExpiringBetween {
+        start: Timestamp,
+        end: Timestamp,
+        start_after: Option<ExpiringNft>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct NftExpirationResponse {
+    pub mint_timestamp: Timestamp,
+    pub expiration: Expiration,
+    /// Token can be renewed until then, `expiration` plus grace period for time based expirations
+    pub renewable_until: Expiration,
+}
+
+#[cw_serde]
+pub struct ExpiringNft {
+    pub token_id: String,
+    pub expiration: Timestamp,
+}
+
+#[cw_serde]
+pub struct ExpiringNftsResponse {
+    pub nfts: Vec<ExpiringNft>,
 }
@@ -1,203 +1,378 @@
-use cosmwasm_std::{to_json_binary, Binary, Deps, Empty, Env, StdResult};
+use cosmwasm_std::{
+    to_json_binary, Binary, CustomMsg, Deps, Env, Order, StdError, StdResult, Storage, Timestamp,
+};
 use cw721::msg::{
-    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, NftInfoResponse, OwnerOfResponse,
-    TokensResponse,
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, ApprovedTokensResponse,
+    AuthorizationResponse, NftInfoResponse, NftsByExtensionResponse, OrderBy, OwnerOfResponse,
+    TokensByFilterResponse, TokensResponse, TokensWithInfoResponse, TraitFilter,
 };
-use cw721::traits::Cw721Query;
-use cw721::DefaultOptionalNftExtension;
+use cw721::query::{DEFAULT_LIMIT, MAX_LIMIT};
+use cw721::traits::{Contains, Cw721CustomMsg, Cw721Query, Cw721State, FromAttributesState};
+use cw721::Expiration;
 
-use crate::state::DefaultCw721ExpirationContract;
-use crate::{error::ContractError, msg::QueryMsg};
+use crate::state::{Cw721ExpirationContract, LegacyBackfill, EXPIRY_AT_TIME};
+use crate::{
+    error::ContractError,
+    msg::{Cw721ExpirationQueryMsg, ExpiringNft, ExpiringNftsResponse, NftExpirationResponse},
+};
+use cw_storage_plus::Bound;
 
-impl DefaultCw721ExpirationContract<'static> {
+impl<
+        TNftExtension,
+        TNftExtensionMsg,
+        TCollectionExtension,
+        TCollectionExtensionMsg,
+        TExtensionMsg,
+        TExtensionQueryMsg,
+        TCustomResponseMsg,
+    >
+    Cw721ExpirationContract<
+        'static,
+        TNftExtension,
+        TNftExtensionMsg,
+        TCollectionExtension,
+        TCollectionExtensionMsg,
+        TExtensionMsg,
+        TExtensionQueryMsg,
+        TCustomResponseMsg,
+    >
+where
+    TNftExtension: Cw721State + Contains,
+    TNftExtensionMsg: Cw721CustomMsg,
+    TCollectionExtension: Cw721State + FromAttributesState,
+    TCollectionExtensionMsg: Cw721CustomMsg,
+    TExtensionQueryMsg: Cw721CustomMsg,
+    TCustomResponseMsg: CustomMsg,
+{
     pub fn query(
         &self,
         deps: Deps,
         env: Env,
-        msg: QueryMsg<Empty>,
+        msg: Cw721ExpirationQueryMsg<TNftExtension, TCollectionExtension, TExtensionQueryMsg>,
     ) -> Result<Binary, ContractError> {
-        let contract = DefaultCw721ExpirationContract::default();
         match msg {
             // -------- msgs with `include_expired_nft` prop --------
-            QueryMsg::OwnerOf {
+            Cw721ExpirationQueryMsg::OwnerOf {
                 token_id,
                 include_expired: include_expired_approval,
                 include_expired_nft,
+            } => Ok(to_json_binary(&self.query_owner_of_include_expired_nft(
+                deps,
+                env,
+                token_id,
+                include_expired_approval.unwrap_or(false),
+                include_expired_nft.unwrap_or(false),
+            )?)?),
+            Cw721ExpirationQueryMsg::Approval {
+                token_id,
+                spender,
+                include_expired,
+                include_expired_nft,
+            } => Ok(to_json_binary(&self.query_approval_include_expired_nft(
+                deps,
+                env,
+                token_id,
+                spender,
+                include_expired.unwrap_or(false),
+                include_expired_nft.unwrap_or(false),
+            )?)?),
+            Cw721ExpirationQueryMsg::Approvals {
+                token_id,
+                include_expired,
+                include_expired_nft,
+            } => Ok(to_json_binary(&self.query_approvals_include_expired_nft(
+                deps,
+                env,
+                token_id,
+                include_expired.unwrap_or(false),
+                include_expired_nft.unwrap_or(false),
+            )?)?),
+            Cw721ExpirationQueryMsg::NftInfo {
+                token_id,
+                include_expired_nft,
+            } => Ok(to_json_binary(&self.query_nft_info_include_expired_nft(
+                deps,
+                env,
+                token_id,
+                include_expired_nft.unwrap_or(false),
+            )?)?),
+            Cw721ExpirationQueryMsg::GetNftByExtension {
+                token_id,
+                extension,
+                include_expired_nft,
             } => Ok(to_json_binary(
-                &contract.query_owner_of_include_expired_nft(
+                &self.query_nft_by_extension_include_expired_nft(
                     deps,
                     env,
                     token_id,
-                    include_expired_approval.unwrap_or(false),
+                    extension,
                     include_expired_nft.unwrap_or(false),
                 )?,
             )?),
-            QueryMsg::Approval {
-                token_id,
-                spender,
-                include_expired,
+            Cw721ExpirationQueryMsg::NftsByExtension {
+                extension,
+                start_after,
+                limit,
                 include_expired_nft,
             } => Ok(to_json_binary(
-                &contract.query_approval_include_expired_nft(
+                &self.query_nfts_by_extension_include_expired_nft(
                     deps,
                     env,
-                    token_id,
-                    spender,
-                    include_expired.unwrap_or(false),
+                    extension,
+                    start_after,
+                    limit,
                     include_expired_nft.unwrap_or(false),
                 )?,
             )?),
-            QueryMsg::Approvals {
-                token_id,
-                include_expired,
+            Cw721ExpirationQueryMsg::TokensByFilter {
+                filter,
+                start_after,
+                limit,
                 include_expired_nft,
             } => Ok(to_json_binary(
-                &contract.query_approvals_include_expired_nft(
+                &self.query_tokens_by_filter_include_expired_nft(
                     deps,
                     env,
-                    token_id,
-                    include_expired.unwrap_or(false),
+                    filter,
+                    start_after,
+                    limit,
                     include_expired_nft.unwrap_or(false),
                 )?,
             )?),
-            QueryMsg::NftInfo {
-                token_id,
+            Cw721ExpirationQueryMsg::TokensByTrait {
+                trait_type,
+                value,
+                start_after,
+                limit,
                 include_expired_nft,
             } => Ok(to_json_binary(
-                &contract.query_nft_info_include_expired_nft(
+                &self.query_tokens_by_trait_include_expired_nft(
                     deps,
                     env,
-                    token_id,
+                    trait_type,
+                    value,
+                    start_after,
+                    limit,
                     include_expired_nft.unwrap_or(false),
                 )?,
             )?),
-            QueryMsg::GetNftByExtension {
+            Cw721ExpirationQueryMsg::AllNftInfo {
                 token_id,
-                extension,
+                include_expired: include_expired_approval,
                 include_expired_nft,
             } => Ok(to_json_binary(
-                &contract.query_nft_by_extension_include_expired_nft(
+                &self.query_all_nft_info_include_expired_nft(
                     deps,
                     env,
                     token_id,
-                    extension,
+                    include_expired_approval.unwrap_or(false),
                     include_expired_nft.unwrap_or(false),
                 )?,
             )?),
-            QueryMsg::AllNftInfo {
-                token_id,
-                include_expired: include_expired_approval,
+            Cw721ExpirationQueryMsg::Tokens {
+                owner,
+                start_after,
+                limit,
+                end_before,
+                order,
+                include_expired_nft,
+            } => Ok(to_json_binary(&self.query_tokens_include_expired_nft(
+                deps,
+                env,
+                owner,
+                start_after,
+                limit,
+                end_before,
+                order,
+                include_expired_nft.unwrap_or(false),
+            )?)?),
+            Cw721ExpirationQueryMsg::AllTokens {
+                start_after,
+                limit,
+                end_before,
+                order,
                 include_expired_nft,
             } => Ok(to_json_binary(
-                &contract.query_all_nft_info_include_expired_nft(
+                &self.query_all_tokens_include_expired_nft(
                     deps,
                     env,
-                    token_id,
-                    include_expired_approval.unwrap_or(false),
+                    start_after,
+                    limit,
+                    end_before,
+                    order,
                     include_expired_nft.unwrap_or(false),
                 )?,
             )?),
-            QueryMsg::Tokens {
+            Cw721ExpirationQueryMsg::TokensWithInfo {
                 owner,
                 start_after,
                 limit,
+                include_expired: include_expired_approval,
                 include_expired_nft,
             } => Ok(to_json_binary(
-                &contract.query_tokens_include_expired_nft(
+                &self.query_tokens_with_info_include_expired_nft(
                     deps,
                     env,
                     owner,
                     start_after,
                     limit,
+                    include_expired_approval.unwrap_or(false),
+                    include_expired_nft.unwrap_or(false),
+                )?,
+            )?),
+            Cw721ExpirationQueryMsg::AllTokensWithInfo {
+                start_after,
+                limit,
+                include_expired: include_expired_approval,
+                include_expired_nft,
+            } => Ok(to_json_binary(
+                &self.query_all_tokens_with_info_include_expired_nft(
+                    deps,
+                    env,
+                    start_after,
+                    limit,
+                    include_expired_approval.unwrap_or(false),
                     include_expired_nft.unwrap_or(false),
                 )?,
             )?),
-            QueryMsg::AllTokens {
+            Cw721ExpirationQueryMsg::ApprovedTokensFor {
+                spender,
+                include_expired: include_expired_approval,
                 start_after,
                 limit,
                 include_expired_nft,
             } => Ok(to_json_binary(
-                &contract.query_all_tokens_include_expired_nft(
+                &self.query_approved_tokens_for_include_expired_nft(
                     deps,
                     env,
+                    spender,
+                    include_expired_approval.unwrap_or(false),
                     start_after,
                     limit,
                     include_expired_nft.unwrap_or(false),
                 )?,
             )?),
+            Cw721ExpirationQueryMsg::CanTransfer { sender, token_id } => Ok(to_json_binary(
+                &self.query_can_transfer(deps, env, sender, token_id)?,
+            )?),
+            Cw721ExpirationQueryMsg::CanApprove { sender, token_id } => Ok(to_json_binary(
+                &self.query_can_approve(deps, env, sender, token_id)?,
+            )?),
             // -------- below is from cw721/src/msg.rs --------
-            QueryMsg::Operator {
+            Cw721ExpirationQueryMsg::Operator {
                 owner,
                 operator,
                 include_expired: include_expired_approval,
-            } => Ok(to_json_binary(&contract.base_contract.query_operator(
+            } => Ok(to_json_binary(&self.base_contract.query_operator(
                 deps,
                 &env,
                 owner,
                 operator,
                 include_expired_approval.unwrap_or(false),
             )?)?),
-            QueryMsg::AllOperators {
+            Cw721ExpirationQueryMsg::AllOperators {
                 owner,
                 include_expired: include_expired_approval,
                 start_after,
                 limit,
-            } => Ok(to_json_binary(&contract.base_contract.query_operators(
+                end_before,
+                order,
+            } => Ok(to_json_binary(&self.base_contract.query_operators(
                 deps,
                 &env,
                 owner,
                 include_expired_approval.unwrap_or(false),
                 start_after,
                 limit,
+                end_before,
+                order,
             )?)?),
-            QueryMsg::NumTokens {} => Ok(to_json_binary(
-                &contract.base_contract.query_num_tokens(deps.storage)?,
+            Cw721ExpirationQueryMsg::OperatorGrantsFor {
+                operator,
+                include_expired: include_expired_approval,
+                start_after,
+                limit,
+            } => Ok(to_json_binary(
+                &self.base_contract.query_operator_grants_for(
+                    deps,
+                    &env,
+                    operator,
+                    include_expired_approval.unwrap_or(false),
+                    start_after,
+                    limit,
+                )?,
+            )?),
+            Cw721ExpirationQueryMsg::NumTokens {} => Ok(to_json_binary(
+                &self.base_contract.query_num_tokens(deps.storage)?,
+            )?),
+            Cw721ExpirationQueryMsg::CollectionStats {} => Ok(to_json_binary(
+                &self.base_contract.query_collection_stats(deps.storage)?,
+            )?),
+            Cw721ExpirationQueryMsg::BalanceOf { owner } => Ok(to_json_binary(
+                &self.base_contract.query_balance_of(deps, owner)?,
+            )?),
+            Cw721ExpirationQueryMsg::Holders { start_after, limit } => Ok(to_json_binary(
+                &self.base_contract.query_holders(deps, start_after, limit)?,
             )?),
             #[allow(deprecated)]
-            QueryMsg::ContractInfo {} => Ok(to_json_binary(
-                &contract
+            Cw721ExpirationQueryMsg::ContractInfo {} => Ok(to_json_binary(
+                &self
                     .base_contract
                     .query_collection_info_and_extension(deps)?,
             )?),
-            QueryMsg::GetCollectionInfo {} => Ok(to_json_binary(
-                &contract
+            Cw721ExpirationQueryMsg::GetCollectionInfo {} => Ok(to_json_binary(
+                &self
                     .base_contract
                     .query_collection_info_and_extension(deps)?,
             )?),
             #[allow(deprecated)]
-            QueryMsg::Ownership {} => Ok(to_json_binary(
-                &contract
-                    .base_contract
-                    .query_minter_ownership(deps.storage)?,
+            Cw721ExpirationQueryMsg::Ownership {} => Ok(to_json_binary(
+                &self.base_contract.query_minter_ownership(deps.storage)?,
             )?),
-            QueryMsg::GetMinterOwnership {} => Ok(to_json_binary(
-                &contract
-                    .base_contract
-                    .query_minter_ownership(deps.storage)?,
+            Cw721ExpirationQueryMsg::GetMinterOwnership {} => Ok(to_json_binary(
+                &self.base_contract.query_minter_ownership(deps.storage)?,
             )?),
-            QueryMsg::GetCreatorOwnership {} => Ok(to_json_binary(
-                &contract
-                    .base_contract
-                    .query_creator_ownership(deps.storage)?,
+            Cw721ExpirationQueryMsg::GetCreatorOwnership {} => Ok(to_json_binary(
+                &self.base_contract.query_creator_ownership(deps.storage)?,
             )?),
             #[allow(deprecated)]
-            QueryMsg::Minter {} => Ok(to_json_binary(
-                &contract.base_contract.query_minter(deps.storage)?,
+            Cw721ExpirationQueryMsg::Minter {} => Ok(to_json_binary(
+                &self.base_contract.query_minter(deps.storage)?,
             )?),
-            QueryMsg::Extension {
+            Cw721ExpirationQueryMsg::Extension {
                 msg,
                 include_expired_nft: _,
             } => Ok(to_json_binary(
-                &contract.base_contract.query_extension(deps, &env, msg)?,
+                &self.base_contract.query_extension(deps, &env, msg)?,
             )?),
-            QueryMsg::GetCollectionExtension { msg } => Ok(to_json_binary(
-                &contract
+            Cw721ExpirationQueryMsg::GetCollectionExtension { msg } => Ok(to_json_binary(
+                &self
                     .base_contract
                     .query_custom_collection_extension(deps, &env, msg)?,
             )?),
-            QueryMsg::GetWithdrawAddress {} => Ok(to_json_binary(
-                &contract.base_contract.query_withdraw_address(deps)?,
+            Cw721ExpirationQueryMsg::GetCollectionExtensionAttribute { key } => Ok(to_json_binary(
+                &self
+                    .base_contract
+                    .query_collection_extension_attribute(deps, key)?,
+            )?),
+            Cw721ExpirationQueryMsg::GetWithdrawAddress {} => Ok(to_json_binary(
+                &self.base_contract.query_withdraw_address(deps)?,
             )?),
+            Cw721ExpirationQueryMsg::NftExpiration { token_id } => {
+                Ok(to_json_binary(&self.query_nft_expiration(deps, token_id)?)?)
+            }
+            Cw721ExpirationQueryMsg::ExpiringBetween {
+                start,
+                end,
+                start_after,
+                limit,
+            } => Ok(to_json_binary(&self.query_expiring_between(
+                deps,
+                start,
+                end,
+                start_after,
+                limit,
+            )?)?),
         }
     }
 
@@ -207,7 +382,7 @@ impl DefaultCw721ExpirationContract<'static> {
         env: Env,
         token_id: String,
         include_expired_nft: bool,
-    ) -> Result<NftInfoResponse<DefaultOptionalNftExtension>, ContractError> {
+    ) -> Result<NftInfoResponse<TNftExtension>, ContractError> {
         if !include_expired_nft {
             self.assert_nft_expired(deps, &env, token_id.as_str())?;
         }
@@ -219,15 +394,88 @@ impl DefaultCw721ExpirationContract<'static> {
         deps: Deps,
         env: Env,
         token_id: String,
-        _extension: DefaultOptionalNftExtension,
+        _extension: TNftExtension,
         include_expired_nft: bool,
-    ) -> Result<NftInfoResponse<DefaultOptionalNftExtension>, ContractError> {
+    ) -> Result<NftInfoResponse<TNftExtension>, ContractError> {
         if !include_expired_nft {
             self.assert_nft_expired(deps, &env, token_id.as_str())?;
         }
         Ok(self.base_contract.query_nft_info(deps.storage, token_id)?)
     }
 
+    pub fn query_nfts_by_extension_include_expired_nft(
+        &self,
+        deps: Deps,
+        env: Env,
+        extension: TNftExtension,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        include_expired_nft: bool,
+    ) -> StdResult<NftsByExtensionResponse<TNftExtension>> {
+        let mut nfts = self.base_contract.query_nfts_by_extension(
+            deps.storage,
+            extension,
+            start_after,
+            limit,
+        )?;
+        // `next_start_after` is the last scanned token, so paging is unaffected by filtering
+        if !include_expired_nft {
+            nfts.nfts.retain(|nft| {
+                self.is_nft_expired(deps, &env, &nft.token_id)
+                    .unwrap_or(false)
+            });
+        }
+        Ok(nfts)
+    }
+
+    pub fn query_tokens_by_filter_include_expired_nft(
+        &self,
+        deps: Deps,
+        env: Env,
+        filter: TraitFilter,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        include_expired_nft: bool,
+    ) -> StdResult<TokensByFilterResponse> {
+        let mut tokens =
+            self.base_contract
+                .query_tokens_by_filter(deps.storage, filter, start_after, limit)?;
+        // `next_start_after` is the last scanned token, so paging is unaffected by filtering
+        if !include_expired_nft {
+            tokens
+                .tokens
+                .retain(|token_id| self.is_nft_expired(deps, &env, token_id).unwrap_or(false));
+        }
+        Ok(tokens)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn query_tokens_by_trait_include_expired_nft(
+        &self,
+        deps: Deps,
+        env: Env,
+        trait_type: String,
+        value: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        include_expired_nft: bool,
+    ) -> StdResult<TokensResponse> {
+        let mut tokens = self.base_contract.query_tokens_by_trait(
+            deps,
+            &env,
+            trait_type,
+            value,
+            start_after,
+            limit,
+        )?;
+        if !include_expired_nft {
+            tokens
+                .tokens
+                .retain(|token_id| self.is_nft_expired(deps, &env, token_id).unwrap_or(false));
+        }
+        Ok(tokens)
+    }
+
     pub fn query_owner_of_include_expired_nft(
         &self,
         deps: Deps,
@@ -282,6 +530,7 @@ impl DefaultCw721ExpirationContract<'static> {
             .query_approvals(deps, &env, token_id, include_expired_approval)?)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn query_tokens_include_expired_nft(
         &self,
         deps: Deps,
@@ -289,11 +538,19 @@ impl DefaultCw721ExpirationContract<'static> {
         owner: String,
         start_after: Option<String>,
         limit: Option<u32>,
+        end_before: Option<String>,
+        order: Option<OrderBy>,
         include_expired_nft: bool,
     ) -> StdResult<TokensResponse> {
-        let tokens = self
-            .base_contract
-            .query_tokens(deps, &env, owner, start_after, limit)?;
+        let tokens = self.base_contract.query_tokens(
+            deps,
+            &env,
+            owner,
+            start_after,
+            limit,
+            end_before,
+            order,
+        )?;
         if include_expired_nft {
             return Ok(tokens);
         }
@@ -308,17 +565,25 @@ impl DefaultCw721ExpirationContract<'static> {
         Ok(TokensResponse { tokens: filtered })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn query_all_tokens_include_expired_nft(
         &self,
         deps: Deps,
         env: Env,
         start_after: Option<String>,
         limit: Option<u32>,
+        end_before: Option<String>,
+        order: Option<OrderBy>,
         include_expired_nft: bool,
     ) -> Result<TokensResponse, ContractError> {
-        let tokens = self
-            .base_contract
-            .query_all_tokens(deps, &env, start_after, limit)?;
+        let tokens = self.base_contract.query_all_tokens(
+            deps,
+            &env,
+            start_after,
+            limit,
+            end_before,
+            order,
+        )?;
         if include_expired_nft {
             return Ok(tokens);
         }
@@ -333,6 +598,115 @@ impl DefaultCw721ExpirationContract<'static> {
         Ok(TokensResponse { tokens: filtered })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn query_tokens_with_info_include_expired_nft(
+        &self,
+        deps: Deps,
+        env: Env,
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        include_expired_approval: bool,
+        include_expired_nft: bool,
+    ) -> StdResult<TokensWithInfoResponse<TNftExtension>> {
+        let mut tokens = self.base_contract.query_tokens_with_info(
+            deps,
+            &env,
+            owner,
+            start_after,
+            limit,
+            include_expired_approval,
+        )?;
+        if !include_expired_nft {
+            tokens.tokens.retain(|token| {
+                self.is_nft_expired(deps, &env, &token.token_id)
+                    .unwrap_or(false)
+            });
+        }
+        Ok(tokens)
+    }
+
+    pub fn query_all_tokens_with_info_include_expired_nft(
+        &self,
+        deps: Deps,
+        env: Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        include_expired_approval: bool,
+        include_expired_nft: bool,
+    ) -> StdResult<TokensWithInfoResponse<TNftExtension>> {
+        let mut tokens = self.base_contract.query_all_tokens_with_info(
+            deps,
+            &env,
+            start_after,
+            limit,
+            include_expired_approval,
+        )?;
+        if !include_expired_nft {
+            tokens.tokens.retain(|token| {
+                self.is_nft_expired(deps, &env, &token.token_id)
+                    .unwrap_or(false)
+            });
+        }
+        Ok(tokens)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn query_approved_tokens_for_include_expired_nft(
+        &self,
+        deps: Deps,
+        env: Env,
+        spender: String,
+        include_expired_approval: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        include_expired_nft: bool,
+    ) -> StdResult<ApprovedTokensResponse> {
+        let mut tokens = self.base_contract.query_approved_tokens_for(
+            deps,
+            &env,
+            spender,
+            include_expired_approval,
+            start_after,
+            limit,
+        )?;
+        if !include_expired_nft {
+            tokens.tokens.retain(|token| {
+                self.is_nft_expired(deps, &env, &token.token_id)
+                    .unwrap_or(false)
+            });
+        }
+        Ok(tokens)
+    }
+
+    /// throws contract error if nft is expired, since transfers of expired nfts fail
+    pub fn query_can_transfer(
+        &self,
+        deps: Deps,
+        env: Env,
+        sender: String,
+        token_id: String,
+    ) -> Result<AuthorizationResponse, ContractError> {
+        self.assert_nft_expired(deps, &env, token_id.as_str())?;
+        Ok(self
+            .base_contract
+            .query_can_transfer(deps, &env, sender, token_id)?)
+    }
+
+    /// throws contract error if nft is expired, since approvals of expired nfts fail
+    pub fn query_can_approve(
+        &self,
+        deps: Deps,
+        env: Env,
+        sender: String,
+        token_id: String,
+    ) -> Result<AuthorizationResponse, ContractError> {
+        self.assert_nft_expired(deps, &env, token_id.as_str())?;
+        Ok(self
+            .base_contract
+            .query_can_approve(deps, &env, sender, token_id)?)
+    }
+
     pub fn query_all_nft_info_include_expired_nft(
         &self,
         deps: Deps,
@@ -340,7 +714,7 @@ impl DefaultCw721ExpirationContract<'static> {
         token_id: String,
         include_expired_approval: bool,
         include_expired_nft: bool,
-    ) -> Result<AllNftInfoResponse<DefaultOptionalNftExtension>, ContractError> {
+    ) -> Result<AllNftInfoResponse<TNftExtension>, ContractError> {
         if !include_expired_nft {
             self.assert_nft_expired(deps, &env, token_id.as_str())?;
         }
@@ -349,13 +723,148 @@ impl DefaultCw721ExpirationContract<'static> {
             .query_all_nft_info(deps, &env, token_id, include_expired_approval)?)
     }
 
+    pub fn query_nft_expiration(
+        &self,
+        deps: Deps,
+        token_id: String,
+    ) -> StdResult<NftExpirationResponse> {
+        let mint_timestamp = self.nft_mint_timestamp(deps.storage, &token_id)?;
+        let expiration = self.nft_expiration(deps.storage, &token_id)?;
+        Ok(NftExpirationResponse {
+            mint_timestamp,
+            expiration,
+            renewable_until: self.renewable_until(deps.storage, expiration)?,
+        })
+    }
+
+    pub fn query_expiring_between(
+        &self,
+        deps: Deps,
+        start: Timestamp,
+        end: Timestamp,
+        start_after: Option<ExpiringNft>,
+        limit: Option<u32>,
+    ) -> StdResult<ExpiringNftsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        // the cursor carries its expiration, so burned or renewed tokens don't shift the range
+        let min = match start_after {
+            Some(last) if last.expiration >= start => {
+                Bound::exclusive(((EXPIRY_AT_TIME, last.expiration.nanos()), last.token_id))
+            }
+            _ => Bound::inclusive(((EXPIRY_AT_TIME, start.nanos()), String::new())),
+        };
+        let max = Bound::exclusive(((EXPIRY_AT_TIME, end.nanos()), String::new()));
+        let nfts = self
+            .expirations
+            .idx
+            .expiry
+            .range(deps.storage, Some(min), Some(max), Order::Ascending)
+            .take(limit)
+            .filter_map(|item| match item {
+                Ok((token_id, Expiration::AtTime(expiration))) => Some(Ok(ExpiringNft {
+                    token_id,
+                    expiration,
+                })),
+                // only time based expirations are within bounds
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        Ok(ExpiringNftsResponse { nfts })
+    }
+}
+
+impl<
+        TNftExtension,
+        TNftExtensionMsg,
+        TCollectionExtension,
+        TCollectionExtensionMsg,
+        TExtensionMsg,
+        TExtensionQueryMsg,
+        TCustomResponseMsg,
+    >
+    Cw721ExpirationContract<
+        'static,
+        TNftExtension,
+        TNftExtensionMsg,
+        TCollectionExtension,
+        TCollectionExtensionMsg,
+        TExtensionMsg,
+        TExtensionQueryMsg,
+        TCustomResponseMsg,
+    >
+where
+    TNftExtension: Cw721State,
+    TNftExtensionMsg: Cw721CustomMsg,
+    TCollectionExtension: Cw721State,
+    TCollectionExtensionMsg: Cw721CustomMsg,
+{
     // --- helpers ---
+    /// Returns the stored expiration, or mint timestamp + expiration days for tokens minted
+    /// without one, or the legacy expiration for tokens not yet backfilled after migration
+    pub fn nft_expiration(&self, storage: &dyn Storage, token_id: &str) -> StdResult<Expiration> {
+        if let Some(expiration) = self.expirations.may_load(storage, token_id)? {
+            return Ok(expiration);
+        }
+        if let Some(mint_date) = self.mint_timestamps.may_load(storage, token_id)? {
+            let expiration_days = self.expiration_days.load(storage)?;
+            return Ok(Expiration::AtTime(
+                mint_date.plus_days(expiration_days.into()),
+            ));
+        }
+        if let Some(backfill) = self.legacy_backfill(storage, token_id)? {
+            return Ok(backfill.expiration);
+        }
+        Err(StdError::not_found("mint_timestamps"))
+    }
+
+    /// Returns the mint timestamp, or the migration timestamp for tokens not yet backfilled
+    pub fn nft_mint_timestamp(
+        &self,
+        storage: &dyn Storage,
+        token_id: &str,
+    ) -> StdResult<Timestamp> {
+        if let Some(mint_date) = self.mint_timestamps.may_load(storage, token_id)? {
+            return Ok(mint_date);
+        }
+        if let Some(backfill) = self.legacy_backfill(storage, token_id)? {
+            return Ok(backfill.migrated_at);
+        }
+        Err(StdError::not_found("mint_timestamps"))
+    }
+
+    /// Pending legacy backfill, if the token exists and was minted before migration
+    fn legacy_backfill(
+        &self,
+        storage: &dyn Storage,
+        token_id: &str,
+    ) -> StdResult<Option<LegacyBackfill>> {
+        if !self.base_contract.config.nft_info.has(storage, token_id) {
+            return Ok(None);
+        }
+        self.legacy_backfill.may_load(storage)
+    }
+
+    /// Grace period only applies to time based expirations
+    pub fn renewable_until(
+        &self,
+        storage: &dyn Storage,
+        expiration: Expiration,
+    ) -> StdResult<Expiration> {
+        let grace_days = self
+            .renewal_grace_days
+            .may_load(storage)?
+            .unwrap_or_default();
+        Ok(match expiration {
+            Expiration::AtTime(time) => Expiration::AtTime(time.plus_days(grace_days.into())),
+            expiration => expiration,
+        })
+    }
+
     pub fn is_nft_expired(&self, deps: Deps, env: &Env, token_id: &str) -> StdResult<bool> {
         // any non-expired token approval can send
-        let mint_date = self.mint_timestamps.load(deps.storage, token_id)?;
-        let expiration_days = self.expiration_days.load(deps.storage)?;
-        let expiration = mint_date.plus_days(expiration_days.into());
-        if env.block.time >= expiration {
+        let expiration = self.nft_expiration(deps.storage, token_id)?;
+        if expiration.is_expired(&env.block) {
             return Ok(false);
         }
         Ok(true)
@@ -369,10 +878,9 @@ impl DefaultCw721ExpirationContract<'static> {
         token_id: &str,
     ) -> Result<(), ContractError> {
         // any non-expired token approval can send
-        let mint_date = self.mint_timestamps.load(deps.storage, token_id)?;
-        let expiration_days = self.expiration_days.load(deps.storage)?;
-        let expiration = mint_date.plus_days(expiration_days.into());
-        if env.block.time >= expiration {
+        let mint_date = self.nft_mint_timestamp(deps.storage, token_id)?;
+        let expiration = self.nft_expiration(deps.storage, token_id)?;
+        if expiration.is_expired(&env.block) {
             return Err(ContractError::NftExpired {
                 token_id: token_id.to_string(),
                 mint_date,
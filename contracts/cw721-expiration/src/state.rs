@@ -1,7 +1,12 @@
-use cosmwasm_std::Timestamp;
-
-use cw721::extension::Cw721OnchainExtensions;
-use cw_storage_plus::{Item, Map};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Coin, Empty, Timestamp};
+use cw721::{
+    extension::Cw721Extensions,
+    traits::{Cw721CustomMsg, Cw721State},
+    DefaultOptionalCollectionExtension, DefaultOptionalCollectionExtensionMsg,
+    DefaultOptionalNftExtension, DefaultOptionalNftExtensionMsg, Expiration,
+};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 /// Opionated version of generic `Cw721ExpirationContract` with default onchain nft and collection extensions using:
 /// - `DefaultOptionalNftExtension` for NftInfo extension (onchain metadata).
@@ -11,18 +16,138 @@ use cw_storage_plus::{Item, Map};
 /// - `Empty` for custom extension msg for custom contract logic.
 /// - `Empty` for custom query msg for custom contract logic.
 /// - `Empty` for custom response msg for custom contract logic.
-pub struct DefaultCw721ExpirationContract<'a> {
+pub type DefaultCw721ExpirationContract<'a> = Cw721ExpirationContract<
+    'a,
+    DefaultOptionalNftExtension,
+    DefaultOptionalNftExtensionMsg,
+    DefaultOptionalCollectionExtension,
+    DefaultOptionalCollectionExtensionMsg,
+    Empty,
+    Empty,
+    Empty,
+>;
+
+/// Generic `Cw721ExpirationContract`, adding expiration to `Cw721Extensions` with custom onchain nft
+/// and collection extensions. See `Cw721Extensions` for the generics.
+pub struct Cw721ExpirationContract<
+    'a,
+    // NftInfo extension (onchain metadata).
+    TNftExtension,
+    // NftInfo extension msg for onchain metadata.
+    TNftExtensionMsg,
+    // CollectionInfo extension (onchain attributes).
+    TCollectionExtension,
+    // CollectionInfo extension msg for onchain collection attributes.
+    TCollectionExtensionMsg,
+    // Custom extension msg for custom contract logic. Default implementation is a no-op.
+    TExtensionMsg,
+    // Custom query msg for custom contract logic. Default implementation returns an empty binary.
+    TExtensionQueryMsg,
+    // Defines for `CosmosMsg::Custom<T>` in response. Barely used, so `Empty` can be used.
+    TCustomResponseMsg,
+> where
+    TNftExtension: Cw721State,
+    TNftExtensionMsg: Cw721CustomMsg,
+    TCollectionExtension: Cw721State,
+    TCollectionExtensionMsg: Cw721CustomMsg,
+{
     pub expiration_days: Item<u16>, // max 65535 days
     pub mint_timestamps: Map<&'a str, Timestamp>,
-    pub base_contract: Cw721OnchainExtensions<'a>,
+    /// Expiration per token. Tokens minted before per token expirations were stored, expire at
+    /// mint timestamp + expiration days.
+    pub expirations: IndexedMap<&'a str, Expiration, ExpirationIndexes<'a>>,
+    /// Optional price per renewed day
+    pub renewal_price: Item<Coin>,
+    pub renewal_grace_days: Item<u16>,
+    /// Set while tokens minted before migrating into cw721-expiration are backfilled
+    pub legacy_backfill: Item<LegacyBackfill>,
+    pub base_contract: Cw721Extensions<
+        'a,
+        TNftExtension,
+        TNftExtensionMsg,
+        TCollectionExtension,
+        TCollectionExtensionMsg,
+        TExtensionMsg,
+        TExtensionQueryMsg,
+        TCustomResponseMsg,
+    >,
 }
 
-impl Default for DefaultCw721ExpirationContract<'static> {
+impl<
+        TNftExtension,
+        TNftExtensionMsg,
+        TCollectionExtension,
+        TCollectionExtensionMsg,
+        TExtensionMsg,
+        TExtensionQueryMsg,
+        TCustomResponseMsg,
+    > Default
+    for Cw721ExpirationContract<
+        'static,
+        TNftExtension,
+        TNftExtensionMsg,
+        TCollectionExtension,
+        TCollectionExtensionMsg,
+        TExtensionMsg,
+        TExtensionQueryMsg,
+        TCustomResponseMsg,
+    >
+where
+    TNftExtension: Cw721State,
+    TNftExtensionMsg: Cw721CustomMsg,
+    TCollectionExtension: Cw721State,
+    TCollectionExtensionMsg: Cw721CustomMsg,
+{
     fn default() -> Self {
         Self {
             expiration_days: Item::new("expiration_days"),
             mint_timestamps: Map::new("mint_timestamps"),
-            base_contract: Cw721OnchainExtensions::default(),
+            expirations: IndexedMap::new(
+                "expirations",
+                ExpirationIndexes {
+                    expiry: MultiIndex::new(expiry_idx, "expirations", "expirations__expiry"),
+                },
+            ),
+            renewal_price: Item::new("renewal_price"),
+            renewal_grace_days: Item::new("renewal_grace_days"),
+            legacy_backfill: Item::new("legacy_backfill"),
+            base_contract: Cw721Extensions::default(),
         }
     }
 }
+
+#[cw_serde]
+pub struct LegacyBackfill {
+    /// Expiration of tokens minted before migration
+    pub expiration: Expiration,
+    pub migrated_at: Timestamp,
+    /// Last backfilled token, for pagination
+    pub last_token_id: Option<String>,
+}
+
+/// Index kind of height based expirations
+pub const EXPIRY_AT_HEIGHT: u8 = 0;
+/// Index kind of time based expirations
+pub const EXPIRY_AT_TIME: u8 = 1;
+/// Index kind of expirations that never expire
+pub const EXPIRY_NEVER: u8 = 2;
+
+/// Indexes expirations by (kind, height or nanos), so tokens can be listed in expiry order.
+pub fn expiry_idx(_pk: &[u8], d: &Expiration) -> (u8, u64) {
+    match d {
+        Expiration::AtHeight(height) => (EXPIRY_AT_HEIGHT, *height),
+        Expiration::AtTime(time) => (EXPIRY_AT_TIME, time.nanos()),
+        Expiration::Never {} => (EXPIRY_NEVER, 0),
+    }
+}
+
+pub struct ExpirationIndexes<'a> {
+    pub expiry: MultiIndex<'a, (u8, u64), Expiration, String>,
+}
+
+impl IndexList<Expiration> for ExpirationIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Expiration>> + '_> {
+        let v: Vec<&dyn Index<Expiration>> = vec![&self.expiry];
+        Box::new(v.into_iter())
+    }
+}
@@ -1,22 +1,30 @@
 use crate::error::ContractError;
-use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, CONFIG};
+use crate::msg::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, PayeeProceeds, ProceedsResponse,
+    ProvenanceResponse, QueryMsg, SalePhase, SaleStatusResponse, TokenUriResponse,
+};
+use crate::state::{
+    Config, Payee, Reveal, CONFIG, CW20_PROCEEDS, ENTROPY, MINTED_PER_ADDRESS, NATIVE_PROCEEDS,
+    REVEAL, SHUFFLE,
+};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, ReplyOn, Response,
-    StdResult, SubMsg, Uint128, WasmMsg,
+    to_json_binary, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Deps, DepsMut, Empty, Env,
+    MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
-use cw20::Cw20ReceiveMsg;
+use cw20::{Cw20Contract, Cw20ExecuteMsg, Cw20ReceiveMsg};
 use cw721::helpers::DefaultCw721Helper;
 use cw721::msg::{Cw721ExecuteMsg, Cw721InstantiateMsg, NftExtensionMsg};
 use cw721::traits::Cw721Calls;
 use cw721::{
-    DefaultOptionalCollectionExtension, DefaultOptionalCollectionExtensionMsg,
+    Action, DefaultOptionalCollectionExtension, DefaultOptionalCollectionExtensionMsg,
     DefaultOptionalNftExtensionMsg,
 };
-use cw_utils::parse_instantiate_response_data;
+use cw_utils::{must_pay, parse_instantiate_response_data};
+use sha2::{Digest, Sha256};
+use url::Url;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw721-fixed-price";
@@ -24,10 +32,13 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
 
+const DEFAULT_SYNC_LIMIT: u32 = 10;
+const MAX_SYNC_LIMIT: u32 = 50;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg<DefaultOptionalCollectionExtension>,
 ) -> Result<Response, ContractError> {
@@ -41,8 +52,52 @@ pub fn instantiate(
         return Err(ContractError::InvalidMaxTokens {});
     }
 
+    if msg.max_per_address == Some(0) {
+        return Err(ContractError::InvalidMaxPerAddress {});
+    }
+
+    if let (Some(start_time), Some(end_time)) = (msg.start_time, msg.end_time) {
+        if start_time >= end_time {
+            return Err(ContractError::InvalidSaleWindow {});
+        }
+    }
+
+    if let Some(native_price) = &msg.native_price {
+        if native_price.amount.is_zero() {
+            return Err(ContractError::InvalidUnitPrice {});
+        }
+    }
+
+    // all proceeds go to the owner by default
+    let payees = if msg.payees.is_empty() {
+        vec![Payee {
+            address: info.sender.clone(),
+            weight: 1,
+        }]
+    } else {
+        msg.payees
+            .iter()
+            .map(|payee| {
+                if payee.weight == 0 {
+                    return Err(ContractError::InvalidPayees {});
+                }
+                Ok(Payee {
+                    address: deps.api.addr_validate(&payee.address)?,
+                    weight: payee.weight,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    // sell from an existing collection, or instantiate a new one and link it in reply
+    let cw721_address = msg
+        .cw721_address
+        .as_deref()
+        .map(|addr| deps.api.addr_validate(addr))
+        .transpose()?;
+
     let config = Config {
-        cw721_address: None,
+        cw721_address: cw721_address.clone(),
         cw20_address: msg.cw20_address,
         unit_price: msg.unit_price,
         max_tokens: msg.max_tokens,
@@ -52,9 +107,28 @@ pub fn instantiate(
         token_uri: msg.token_uri.clone(),
         extension: msg.extension.clone(),
         unused_token_id: 0,
+        token_id_offset: msg.token_id_offset.unwrap_or_default(),
+        base_uri: None,
+        provenance_hash: msg.provenance_hash,
+        random_assignment: msg.random_assignment,
+        max_per_address: msg.max_per_address,
+        start_time: msg.start_time,
+        end_time: msg.end_time,
+        paused: false,
+        native_price: msg.native_price,
+        payees,
     };
 
     CONFIG.save(deps.storage, &config)?;
+    if config.random_assignment {
+        add_entropy(deps.storage, &instantiate_entropy(&env))?;
+    }
+
+    if let Some(cw721_address) = cw721_address {
+        return Ok(Response::new()
+            .add_attribute("action", "instantiate")
+            .add_attribute("cw721_address", cw721_address));
+    }
 
     let sub_msg: Vec<SubMsg> = vec![SubMsg {
         msg: WasmMsg::Instantiate {
@@ -81,6 +155,14 @@ pub fn instantiate(
     Ok(Response::new().add_submessages(sub_msg))
 }
 
+fn instantiate_entropy(env: &Env) -> Vec<u8> {
+    let mut input = env.block.height.to_be_bytes().to_vec();
+    input.extend(env.block.time.nanos().to_be_bytes());
+    input.extend(env.block.chain_id.as_bytes());
+    input.extend(env.contract.address.as_bytes());
+    input
+}
+
 // Reply callback triggered from cw721 contract instantiation
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
@@ -93,9 +175,15 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
     if msg.id != INSTANTIATE_TOKEN_REPLY_ID {
         return Err(ContractError::InvalidTokenReplyId {});
     }
-    let result = msg.result.into_result().unwrap();
-    let data = result.msg_responses.first().unwrap();
-    let reply = parse_instantiate_response_data(data.value.as_slice()).unwrap();
+    let result = msg
+        .result
+        .into_result()
+        .map_err(ContractError::Cw721InstantiateFailed)?;
+    let data = result
+        .msg_responses
+        .first()
+        .ok_or(ContractError::MissingInstantiateResponse {})?;
+    let reply = parse_instantiate_response_data(data.value.as_slice())?;
     config.cw721_address = Addr::unchecked(reply.contract_address).into();
     CONFIG.save(deps.storage, &config)?;
 
@@ -103,9 +191,13 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetConfig {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::SaleStatus { address } => to_json_binary(&query_sale_status(deps, env, address)?),
+        QueryMsg::Proceeds {} => to_json_binary(&query_proceeds(deps)?),
+        QueryMsg::Provenance {} => to_json_binary(&query_provenance(deps)?),
+        QueryMsg::TokenUri { token_id } => to_json_binary(&query_token_uri(deps, token_id)?),
     }
 }
 
@@ -122,13 +214,108 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         token_uri: config.token_uri,
         extension: config.extension,
         unused_token_id: config.unused_token_id,
+        token_id_offset: config.token_id_offset,
+        base_uri: config.base_uri,
+        provenance_hash: config.provenance_hash,
+        random_assignment: config.random_assignment,
+        max_per_address: config.max_per_address,
+        start_time: config.start_time,
+        end_time: config.end_time,
+        paused: config.paused,
+        native_price: config.native_price,
+        payees: config.payees,
+    })
+}
+
+fn query_provenance(deps: Deps) -> StdResult<ProvenanceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let reveal = REVEAL.may_load(deps.storage)?;
+    Ok(ProvenanceResponse {
+        provenance_hash: config.provenance_hash,
+        base_uri: config.base_uri,
+        revealed_at: reveal.as_ref().map(|r| r.revealed_at),
+        unsynced_tokens: reveal.map_or(0, |r| r.unsynced_count - r.synced_count),
     })
 }
 
+fn query_token_uri(deps: Deps, token_id: String) -> StdResult<TokenUriResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(TokenUriResponse {
+        token_uri: resolve_token_uri(&config, &token_id),
+    })
+}
+
+fn query_proceeds(deps: Deps) -> StdResult<ProceedsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let cw20 = CW20_PROCEEDS.may_load(deps.storage)?.unwrap_or_default();
+    let native = NATIVE_PROCEEDS.may_load(deps.storage)?.unwrap_or_default();
+    let cw20_shares = split_by_weight(&config.payees, cw20);
+    let native_shares = split_by_weight(&config.payees, native);
+    let payees = config
+        .payees
+        .into_iter()
+        .zip(cw20_shares)
+        .zip(native_shares)
+        .map(|((payee, cw20), native)| PayeeProceeds {
+            address: payee.address,
+            weight: payee.weight,
+            cw20,
+            native,
+        })
+        .collect();
+    Ok(ProceedsResponse {
+        cw20,
+        native,
+        native_denom: config.native_price.map(|price| price.denom),
+        payees,
+    })
+}
+
+fn query_sale_status(
+    deps: Deps,
+    env: Env,
+    address: Option<String>,
+) -> StdResult<SaleStatusResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let remaining_supply = config.max_tokens.saturating_sub(config.unused_token_id);
+    let remaining_allocation = match (address, config.max_per_address) {
+        (Some(address), Some(max_per_address)) => {
+            let address = deps.api.addr_validate(&address)?;
+            let minted = MINTED_PER_ADDRESS
+                .may_load(deps.storage, &address)?
+                .unwrap_or_default();
+            Some(max_per_address.saturating_sub(minted).min(remaining_supply))
+        }
+        _ => None,
+    };
+    Ok(SaleStatusResponse {
+        phase: sale_phase(&config, &env.block),
+        remaining_supply,
+        remaining_allocation,
+    })
+}
+
+/// Returns the current phase of the sale. Sold out and ended take precedence over a paused sale.
+pub fn sale_phase(config: &Config, block: &BlockInfo) -> SalePhase {
+    if config.unused_token_id >= config.max_tokens {
+        return SalePhase::SoldOut;
+    }
+    if config.end_time.is_some_and(|end| block.time >= end) {
+        return SalePhase::Ended;
+    }
+    if config.paused {
+        return SalePhase::Paused;
+    }
+    if config.start_time.is_some_and(|start| block.time < start) {
+        return SalePhase::NotStarted;
+    }
+    SalePhase::Active
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
@@ -137,66 +324,443 @@ pub fn execute(
             sender,
             amount,
             msg,
-        }) => execute_receive(deps, info, sender, amount, msg),
+        }) => execute_receive(deps, env, info, sender, amount, msg),
+        ExecuteMsg::Pause {} => execute_set_paused(deps, info, true),
+        ExecuteMsg::Unpause {} => execute_set_paused(deps, info, false),
+        ExecuteMsg::Mint {} => execute_mint(deps, env, info),
+        ExecuteMsg::WithdrawProceeds {} => execute_withdraw_proceeds(deps),
+        ExecuteMsg::Reveal {
+            base_uri,
+            provenance_hash,
+        } => execute_reveal(deps, env, info, base_uri, provenance_hash),
+        ExecuteMsg::SyncTokenUris { limit } => execute_sync_token_uris(deps, limit),
+        ExecuteMsg::UpdateMinterOwnership(action) => {
+            execute_update_minter_ownership(deps, info, action)
+        }
     }
 }
 
+/// Forwards a minter ownership action to the linked collection, e.g. for accepting minter
+/// ownership of an existing collection or handing it over after the sale.
+pub fn execute_update_minter_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+    action: Action,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let cw721 = config
+        .cw721_address
+        .ok_or(ContractError::Uninitialized {})?;
+    let msg = DefaultCw721Helper::new(cw721).call(Cw721ExecuteMsg::<
+        DefaultOptionalNftExtensionMsg,
+        DefaultOptionalCollectionExtensionMsg,
+        Empty,
+    >::UpdateMinterOwnership(action))?;
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "update_minter_ownership")
+        .add_attribute("sender", info.sender))
+}
+
+pub fn execute_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.paused = paused;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", if paused { "pause" } else { "unpause" })
+        .add_attribute("sender", info.sender))
+}
+
 pub fn execute_receive(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     sender: String,
     amount: Uint128,
     _msg: Binary,
 ) -> Result<Response, ContractError> {
-    let mut config = CONFIG.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
     if config.cw20_address != info.sender {
         return Err(ContractError::UnauthorizedTokenContract {});
     }
 
-    if config.cw721_address.is_none() {
-        return Err(ContractError::Uninitialized {});
+    let buyer = deps.api.addr_validate(&sender)?;
+    let cw20 = Cw20Contract(config.cw20_address.clone());
+    let unit_price = config.unit_price;
+    let (mint_msgs, cost, refund) =
+        purchase(deps.storage, &env, config, &buyer, amount, unit_price)?;
+    let proceeds = CW20_PROCEEDS.may_load(deps.storage)?.unwrap_or_default();
+    CW20_PROCEEDS.save(deps.storage, &(proceeds + cost))?;
+
+    let mut response = Response::new().add_messages(mint_msgs);
+    if !refund.is_zero() {
+        response = response.add_message(cw20.call(Cw20ExecuteMsg::Transfer {
+            recipient: buyer.to_string(),
+            amount: refund,
+        })?);
     }
+    Ok(response
+        .add_attribute("action", "purchase")
+        .add_attribute("buyer", buyer)
+        .add_attribute("cost", cost)
+        .add_attribute("refund", refund))
+}
 
-    if config.unused_token_id >= config.max_tokens {
-        return Err(ContractError::SoldOut {});
+/// Purchase paid with native funds, only available if `native_price` is configured.
+pub fn execute_mint(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let native_price = config
+        .native_price
+        .clone()
+        .ok_or(ContractError::NativePaymentDisabled {})?;
+    let amount = must_pay(&info, &native_price.denom)?;
+
+    let (mint_msgs, cost, refund) = purchase(
+        deps.storage,
+        &env,
+        config,
+        &info.sender,
+        amount,
+        native_price.amount,
+    )?;
+    let proceeds = NATIVE_PROCEEDS.may_load(deps.storage)?.unwrap_or_default();
+    NATIVE_PROCEEDS.save(deps.storage, &(proceeds + cost))?;
+
+    let mut response = Response::new().add_messages(mint_msgs);
+    if !refund.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin::new(refund, native_price.denom)],
+        });
+    }
+    Ok(response
+        .add_attribute("action", "purchase")
+        .add_attribute("buyer", info.sender)
+        .add_attribute("cost", cost)
+        .add_attribute("refund", refund))
+}
+
+/// Mints as many tokens as `amount` pays for, bounded by the remaining supply and the buyer's
+/// allocation. Returns the mint messages, the amount kept as proceeds and the amount to refund.
+fn purchase(
+    storage: &mut dyn Storage,
+    env: &Env,
+    mut config: Config,
+    buyer: &Addr,
+    amount: Uint128,
+    unit_price: Uint128,
+) -> Result<(Vec<CosmosMsg>, Uint128, Uint128), ContractError> {
+    let cw721 = config
+        .cw721_address
+        .clone()
+        .ok_or(ContractError::Uninitialized {})?;
+
+    match sale_phase(&config, &env.block) {
+        SalePhase::SoldOut => return Err(ContractError::SoldOut {}),
+        SalePhase::Ended => return Err(ContractError::SaleEnded {}),
+        SalePhase::Paused => return Err(ContractError::SalePaused {}),
+        SalePhase::NotStarted => return Err(ContractError::SaleNotStarted {}),
+        SalePhase::Active => {}
     }
 
-    if amount != config.unit_price {
+    let units = amount.checked_div(unit_price).map_err(StdError::from)?;
+    if units.is_zero() {
         return Err(ContractError::WrongPaymentAmount {});
     }
 
+    let minted = MINTED_PER_ADDRESS
+        .may_load(storage, buyer)?
+        .unwrap_or_default();
+    let remaining_allocation = match config.max_per_address {
+        Some(max_per_address) => max_per_address.saturating_sub(minted),
+        None => u32::MAX,
+    };
+    if remaining_allocation == 0 {
+        return Err(ContractError::MintLimitReached {});
+    }
+    let count = u32::try_from(units.u128())
+        .unwrap_or(u32::MAX)
+        .min(config.max_tokens - config.unused_token_id)
+        .min(remaining_allocation);
+    MINTED_PER_ADDRESS.save(storage, buyer, &(minted + count))?;
+
+    let helper = DefaultCw721Helper::new(cw721);
     let extension: Option<NftExtensionMsg> = config.extension.clone().map(|e| e.into());
-    let mint_msg = Cw721ExecuteMsg::<
-        DefaultOptionalNftExtensionMsg,
-        DefaultOptionalCollectionExtensionMsg,
-        Empty,
-    >::Mint {
-        token_id: config.unused_token_id.to_string(),
-        owner: sender,
-        token_uri: config.token_uri.clone().into(),
-        extension,
+    let mut msgs = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        if config.random_assignment {
+            draw_token_index(storage, env, &config, buyer)?;
+        }
+        let token_id = token_id_at(storage, &config, config.unused_token_id)?;
+        let mint_msg = Cw721ExecuteMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::Mint {
+            token_uri: resolve_token_uri(&config, &token_id).into(),
+            token_id,
+            owner: buyer.to_string(),
+            extension: extension.clone(),
+        };
+        msgs.push(helper.call(mint_msg)?);
+        config.unused_token_id += 1;
+    }
+    CONFIG.save(storage, &config)?;
+
+    let cost = unit_price * Uint128::from(count);
+    Ok((msgs, cost, amount - cost))
+}
+
+/// Returns the token id of the n-th minted token. For random assignment, the n-th drawn index
+/// is kept at position `max_tokens - 1 - n` of the shuffle.
+fn token_id_at(storage: &dyn Storage, config: &Config, mint_index: u32) -> StdResult<String> {
+    let index = if config.random_assignment {
+        let position = config.max_tokens - 1 - mint_index;
+        SHUFFLE.may_load(storage, position)?.unwrap_or(position)
+    } else {
+        mint_index
     };
+    Ok((config.token_id_offset + index).to_string())
+}
+
+/// Draws the next token index with a Fisher–Yates step over the remaining indexes: a random
+/// position in `[0, remaining)` is swapped with the last remaining position, which then holds
+/// the drawn index. Randomness is derived from rolling entropy, hashed with block info,
+/// tx index, buyer and mint count, so it is deterministic for given inputs.
+fn draw_token_index(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    buyer: &Addr,
+) -> StdResult<()> {
+    let entropy = ENTROPY.may_load(storage)?.unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update(entropy.as_slice());
+    hasher.update(env.block.height.to_be_bytes());
+    hasher.update(env.block.time.nanos().to_be_bytes());
+    hasher.update(env.block.chain_id.as_bytes());
+    if let Some(transaction) = &env.transaction {
+        hasher.update(transaction.index.to_be_bytes());
+    }
+    hasher.update(buyer.as_bytes());
+    hasher.update(config.unused_token_id.to_be_bytes());
+    let hash = hasher.finalize();
+    ENTROPY.save(storage, &Binary::from(hash.as_slice()))?;
+
+    let mut random = [0u8; 8];
+    random.copy_from_slice(&hash[..8]);
+    let remaining = config.max_tokens - config.unused_token_id;
+    let position = (u64::from_be_bytes(random) % remaining as u64) as u32;
+    let last = remaining - 1;
+    let drawn = SHUFFLE.may_load(storage, position)?.unwrap_or(position);
+    let last_value = SHUFFLE.may_load(storage, last)?.unwrap_or(last);
+    // only positions not holding their own index are stored
+    if last_value == position {
+        SHUFFLE.remove(storage, position);
+    } else {
+        SHUFFLE.save(storage, position, &last_value)?;
+    }
+    if drawn == last {
+        SHUFFLE.remove(storage, last);
+    } else {
+        SHUFFLE.save(storage, last, &drawn)?;
+    }
+    Ok(())
+}
+
+/// Mixes additional input into the entropy used for random assignment.
+fn add_entropy(storage: &mut dyn Storage, input: &[u8]) -> StdResult<()> {
+    let entropy = ENTROPY.may_load(storage)?.unwrap_or_default();
+    let hash = Sha256::new()
+        .chain_update(entropy.as_slice())
+        .chain_update(input)
+        .finalize();
+    ENTROPY.save(storage, &Binary::from(hash.as_slice()))
+}
+
+/// Returns `{base_uri}/{token_id}.json` once revealed, otherwise the placeholder `token_uri`.
+pub fn resolve_token_uri(config: &Config, token_id: &str) -> String {
+    match &config.base_uri {
+        Some(base_uri) => format!("{}/{}.json", base_uri.trim_end_matches('/'), token_id),
+        None => config.token_uri.clone(),
+    }
+}
+
+/// Owner reveals the collection once. Tokens minted afterwards point to their per token uri,
+/// tokens minted before are updated via `SyncTokenUris`.
+pub fn execute_reveal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    base_uri: String,
+    provenance_hash: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if config.base_uri.is_some() {
+        return Err(ContractError::AlreadyRevealed {});
+    }
+    if let Some(committed) = &config.provenance_hash {
+        if *committed != provenance_hash {
+            return Err(ContractError::ProvenanceMismatch {});
+        }
+    }
+    config.base_uri = Some(base_uri.clone());
+    // validate resulting token uris
+    Url::parse(&resolve_token_uri(
+        &config,
+        &config.token_id_offset.to_string(),
+    ))?;
+    config.provenance_hash = Some(provenance_hash.clone());
+    CONFIG.save(deps.storage, &config)?;
+    if config.random_assignment {
+        // tokens drawn after the reveal also depend on the reveal
+        add_entropy(deps.storage, provenance_hash.as_bytes())?;
+    }
+    REVEAL.save(
+        deps.storage,
+        &Reveal {
+            revealed_at: env.block.time,
+            unsynced_count: config.unused_token_id,
+            synced_count: 0,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "reveal")
+        .add_attribute("base_uri", base_uri)
+        .add_attribute("provenance_hash", provenance_hash))
+}
+
+/// Updates token uris of tokens minted before the reveal, in batches of `limit`. Anyone may call
+/// this. Requires this contract to be the collection's creator, which is the case for
+/// collections instantiated by this contract.
+pub fn execute_sync_token_uris(
+    deps: DepsMut,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut reveal = REVEAL
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NotRevealed {})?;
+    let cw721 = config
+        .cw721_address
+        .clone()
+        .ok_or(ContractError::Uninitialized {})?;
+    let limit = limit.unwrap_or(DEFAULT_SYNC_LIMIT).min(MAX_SYNC_LIMIT);
+    let end = reveal
+        .unsynced_count
+        .min(reveal.synced_count.saturating_add(limit));
+    if reveal.synced_count >= end {
+        return Err(ContractError::NothingToSync {});
+    }
 
-    match config.cw721_address.clone() {
-        Some(cw721) => {
-            let msg = DefaultCw721Helper::new(cw721).call(mint_msg)?;
-            config.unused_token_id += 1;
-            CONFIG.save(deps.storage, &config)?;
+    let helper = DefaultCw721Helper::new(cw721);
+    let extension: Option<NftExtensionMsg> = config.extension.clone().map(|e| e.into());
+    let mut msgs = Vec::with_capacity((end - reveal.synced_count) as usize);
+    for index in reveal.synced_count..end {
+        let token_id = token_id_at(deps.storage, &config, index)?;
+        msgs.push(helper.call(Cw721ExecuteMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::UpdateNftInfo {
+            token_uri: Some(resolve_token_uri(&config, &token_id)),
+            token_id,
+            extension: extension.clone(),
+        })?);
+    }
+    let synced = end - reveal.synced_count;
+    reveal.synced_count = end;
+    REVEAL.save(deps.storage, &reveal)?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "sync_token_uris")
+        .add_attribute("synced", synced.to_string())
+        .add_attribute(
+            "remaining",
+            (reveal.unsynced_count - reveal.synced_count).to_string(),
+        ))
+}
+
+/// Pays out all accumulated proceeds to the configured payees by weight. Anyone may trigger a
+/// withdrawal, since funds can only go to the payees.
+pub fn execute_withdraw_proceeds(deps: DepsMut) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let cw20_proceeds = CW20_PROCEEDS.may_load(deps.storage)?.unwrap_or_default();
+    let native_proceeds = NATIVE_PROCEEDS.may_load(deps.storage)?.unwrap_or_default();
+    if cw20_proceeds.is_zero() && native_proceeds.is_zero() {
+        return Err(ContractError::NoProceeds {});
+    }
 
-            Ok(Response::new().add_message(msg))
+    let cw20 = Cw20Contract(config.cw20_address.clone());
+    let cw20_shares = split_by_weight(&config.payees, cw20_proceeds);
+    let native_shares = split_by_weight(&config.payees, native_proceeds);
+    let mut response = Response::new()
+        .add_attribute("action", "withdraw_proceeds")
+        .add_attribute("cw20_amount", cw20_proceeds)
+        .add_attribute("native_amount", native_proceeds);
+    for ((payee, cw20_share), native_share) in
+        config.payees.iter().zip(cw20_shares).zip(native_shares)
+    {
+        if !cw20_share.is_zero() {
+            response = response.add_message(cw20.call(Cw20ExecuteMsg::Transfer {
+                recipient: payee.address.to_string(),
+                amount: cw20_share,
+            })?);
+        }
+        if let (false, Some(native_price)) = (native_share.is_zero(), &config.native_price) {
+            response = response.add_message(BankMsg::Send {
+                to_address: payee.address.to_string(),
+                amount: vec![Coin::new(native_share, native_price.denom.clone())],
+            });
         }
-        None => Err(ContractError::Cw721NotLinked {}),
     }
+
+    CW20_PROCEEDS.save(deps.storage, &Uint128::zero())?;
+    NATIVE_PROCEEDS.save(deps.storage, &Uint128::zero())?;
+    Ok(response)
+}
+
+/// Splits `amount` between payees proportionally to their weight. Rounding dust goes to the
+/// first payee, so nothing is left behind in the contract.
+fn split_by_weight(payees: &[Payee], amount: Uint128) -> Vec<Uint128> {
+    let total_weight: u64 = payees.iter().map(|p| p.weight as u64).sum();
+    let mut shares: Vec<Uint128> = payees
+        .iter()
+        .map(|p| amount.multiply_ratio(p.weight as u64, total_weight))
+        .collect();
+    let dust = amount - shares.iter().copied().sum::<Uint128>();
+    if let Some(first) = shares.first_mut() {
+        *first += dust;
+    }
+    shares
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::msg::PayeeMsg;
     use cosmwasm_std::testing::{
         message_info, mock_dependencies, mock_env, MockApi, MOCK_CONTRACT_ADDR,
     };
     use cosmwasm_std::{
-        from_json, to_json_binary, CosmosMsg, MsgResponse, SubMsgResponse, SubMsgResult,
+        coins, from_json, to_json_binary, MsgResponse, SubMsgResponse, SubMsgResult,
     };
     use cw721::DefaultOptionalNftExtensionMsg;
     use prost::Message;
@@ -249,6 +813,15 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            cw721_address: None,
+            token_id_offset: None,
+            provenance_hash: None,
+            random_assignment: false,
+            max_per_address: None,
+            start_time: None,
+            end_time: None,
+            native_price: None,
+            payees: vec![],
         };
         let owner = addrs.addr("owner");
         let info = message_info(&owner, &[]);
@@ -323,7 +896,20 @@ mod tests {
                 symbol: msg.symbol,
                 token_uri: msg.token_uri,
                 extension: None,
-                unused_token_id: 0
+                unused_token_id: 0,
+                token_id_offset: 0,
+                base_uri: None,
+                provenance_hash: None,
+                random_assignment: false,
+                max_per_address: None,
+                start_time: None,
+                end_time: None,
+                paused: false,
+                native_price: None,
+                payees: vec![Payee {
+                    address: addrs.addr("owner"),
+                    weight: 1,
+                }],
             }
         );
     }
@@ -343,6 +929,15 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            cw721_address: None,
+            token_id_offset: None,
+            provenance_hash: None,
+            random_assignment: false,
+            max_per_address: None,
+            start_time: None,
+            end_time: None,
+            native_price: None,
+            payees: vec![],
         };
 
         let owner = deps.api.addr_make("owner");
@@ -370,6 +965,15 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            cw721_address: None,
+            token_id_offset: None,
+            provenance_hash: None,
+            random_assignment: false,
+            max_per_address: None,
+            start_time: None,
+            end_time: None,
+            native_price: None,
+            payees: vec![],
         };
 
         let owner = deps.api.addr_make("owner");
@@ -398,6 +1002,15 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            cw721_address: None,
+            token_id_offset: None,
+            provenance_hash: None,
+            random_assignment: false,
+            max_per_address: None,
+            start_time: None,
+            end_time: None,
+            native_price: None,
+            payees: vec![],
         };
 
         let owner = addrs.addr("owner");
@@ -480,6 +1093,15 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            cw721_address: None,
+            token_id_offset: None,
+            provenance_hash: None,
+            random_assignment: false,
+            max_per_address: None,
+            start_time: None,
+            end_time: None,
+            native_price: None,
+            payees: vec![],
         };
 
         let owner = deps.api.addr_make("owner");
@@ -531,6 +1153,15 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            cw721_address: None,
+            token_id_offset: None,
+            provenance_hash: None,
+            random_assignment: false,
+            max_per_address: None,
+            start_time: None,
+            end_time: None,
+            native_price: None,
+            payees: vec![],
         };
 
         let owner = deps.api.addr_make("owner");
@@ -585,6 +1216,15 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            cw721_address: None,
+            token_id_offset: None,
+            provenance_hash: None,
+            random_assignment: false,
+            max_per_address: None,
+            start_time: None,
+            end_time: None,
+            native_price: None,
+            payees: vec![],
         };
 
         let owner = addrs.addr("owner");
@@ -651,6 +1291,15 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            cw721_address: None,
+            token_id_offset: None,
+            provenance_hash: None,
+            random_assignment: false,
+            max_per_address: None,
+            start_time: None,
+            end_time: None,
+            native_price: None,
+            payees: vec![],
         };
 
         let contract = addrs.addr(MOCK_CONTRACT_ADDR);
@@ -687,6 +1336,15 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            cw721_address: None,
+            token_id_offset: None,
+            provenance_hash: None,
+            random_assignment: false,
+            max_per_address: None,
+            start_time: None,
+            end_time: None,
+            native_price: None,
+            payees: vec![],
         };
 
         let owner = deps.api.addr_make("owner");
@@ -743,7 +1401,7 @@ mod tests {
         let msg = InstantiateMsg {
             owner: addrs.addr("owner"),
             max_tokens: 1,
-            unit_price: Uint128::new(1),
+            unit_price: Uint128::new(10),
             name: String::from("SYNTH"),
             symbol: String::from("SYNTH"),
             collection_info_extension: None,
@@ -752,6 +1410,15 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            cw721_address: None,
+            token_id_offset: None,
+            provenance_hash: None,
+            random_assignment: false,
+            max_per_address: None,
+            start_time: None,
+            end_time: None,
+            native_price: None,
+            payees: vec![],
         };
 
         let owner = addrs.addr("owner");
@@ -786,10 +1453,10 @@ mod tests {
         };
         reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
 
-        // Test token transfer from invalid token contract
+        // Test payment below unit price
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: addrs.addr("minter").to_string(),
-            amount: Uint128::new(100),
+            amount: Uint128::new(5),
             msg: [].into(),
         });
         let contract = addrs.addr(MOCK_CONTRACT_ADDR);
@@ -801,4 +1468,905 @@ mod tests {
             e => panic!("unexpected error: {e}"),
         }
     }
+
+    fn sale_instantiate_msg(
+        addrs: &mut MockAddrFactory,
+    ) -> InstantiateMsg<DefaultOptionalCollectionExtension> {
+        InstantiateMsg {
+            owner: addrs.addr("owner"),
+            max_tokens: 3,
+            unit_price: Uint128::new(1),
+            name: String::from("SYNTH"),
+            symbol: String::from("SYNTH"),
+            collection_info_extension: None,
+            token_code_id: 10u64,
+            cw20_address: addrs.addr(MOCK_CONTRACT_ADDR),
+            token_uri: String::from("https://ipfs.io/ipfs/Q"),
+            extension: None,
+            withdraw_address: None,
+            cw721_address: None,
+            token_id_offset: None,
+            provenance_hash: None,
+            random_assignment: false,
+            max_per_address: None,
+            start_time: None,
+            end_time: None,
+            native_price: None,
+            payees: vec![],
+        }
+    }
+
+    #[test]
+    fn instantiate_msg_without_optional_fields() {
+        let mut addrs = MockAddrFactory::new(MockApi::default());
+        // instantiate msg as accepted before sale options were added
+        let json = format!(
+            r#"{{
+                "owner": "{owner}",
+                "max_tokens": 3,
+                "unit_price": "1",
+                "name": "SYNTH",
+                "symbol": "SYNTH",
+                "collection_info_extension": null,
+                "token_code_id": 10,
+                "cw20_address": "{cw20}",
+                "token_uri": "https://ipfs.io/ipfs/Q",
+                "extension": null,
+                "withdraw_address": null
+            }}"#,
+            owner = addrs.addr("owner"),
+            cw20 = addrs.addr(MOCK_CONTRACT_ADDR),
+        );
+        let msg: InstantiateMsg<DefaultOptionalCollectionExtension> =
+            from_json(json.as_bytes()).unwrap();
+        assert_eq!(msg, sale_instantiate_msg(&mut addrs));
+
+        // all proceeds go to the owner
+        let mut deps = mock_dependencies();
+        let owner = addrs.addr("owner");
+        instantiate(deps.as_mut(), mock_env(), message_info(&owner, &[]), msg).unwrap();
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            config.payees,
+            vec![Payee {
+                address: owner,
+                weight: 1
+            }]
+        );
+    }
+
+    /// Instantiates the sale and links the cw721 contract via reply.
+    fn setup_sale(
+        deps: DepsMut,
+        addrs: &mut MockAddrFactory,
+        msg: InstantiateMsg<DefaultOptionalCollectionExtension>,
+    ) {
+        let owner = addrs.addr("owner");
+        let info = message_info(&owner, &[]);
+        let mut deps = deps;
+        instantiate(deps.branch(), mock_env(), info, msg).unwrap();
+        let instantiate_reply = MsgInstantiateContractResponse {
+            contract_address: addrs.addr(NFT_CONTRACT_ADDR).to_string(),
+            data: vec![2u8; 32769],
+        };
+        let mut encoded_instantiate_reply =
+            Vec::<u8>::with_capacity(instantiate_reply.encoded_len());
+        instantiate_reply
+            .encode(&mut encoded_instantiate_reply)
+            .unwrap();
+        let reply_msg = Reply {
+            id: INSTANTIATE_TOKEN_REPLY_ID,
+            payload: Binary::default(),
+            gas_used: 1000,
+            #[allow(deprecated)]
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(encoded_instantiate_reply.clone().into()),
+                msg_responses: vec![MsgResponse {
+                    type_url: "/cosmwasm.wasm.v1.MsgInstantiateContractResponse".to_string(),
+                    value: encoded_instantiate_reply.into(),
+                }],
+            }),
+        };
+        reply(deps, mock_env(), reply_msg).unwrap();
+    }
+
+    fn buy<'a>(
+        deps: DepsMut,
+        env: Env,
+        addrs: &mut MockAddrFactory<'a>,
+        buyer: &'a str,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: addrs.addr(buyer).to_string(),
+            amount: Uint128::new(1),
+            msg: [].into(),
+        });
+        let contract = addrs.addr(MOCK_CONTRACT_ADDR);
+        execute(deps, env, message_info(&contract, &[]), msg)
+    }
+
+    #[test]
+    fn mint_limit_per_address() {
+        let mut deps = mock_dependencies();
+        let mut addrs = MockAddrFactory::new(deps.api);
+        let msg = InstantiateMsg {
+            max_per_address: Some(1),
+            ..sale_instantiate_msg(&mut addrs)
+        };
+        setup_sale(deps.as_mut(), &mut addrs, msg);
+
+        buy(deps.as_mut(), mock_env(), &mut addrs, "buyer").unwrap();
+        let err = buy(deps.as_mut(), mock_env(), &mut addrs, "buyer").unwrap_err();
+        match err {
+            ContractError::MintLimitReached {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+        // other addresses have their own allocation
+        buy(deps.as_mut(), mock_env(), &mut addrs, "other").unwrap();
+
+        // zero limit is rejected
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            max_per_address: Some(0),
+            ..sale_instantiate_msg(&mut addrs)
+        };
+        let owner = addrs.addr("owner");
+        let err =
+            instantiate(deps.as_mut(), mock_env(), message_info(&owner, &[]), msg).unwrap_err();
+        match err {
+            ContractError::InvalidMaxPerAddress {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[test]
+    fn sale_window() {
+        let mut deps = mock_dependencies();
+        let mut addrs = MockAddrFactory::new(deps.api);
+        let now = mock_env().block.time;
+        let msg = InstantiateMsg {
+            start_time: Some(now.plus_seconds(100)),
+            end_time: Some(now.plus_seconds(200)),
+            ..sale_instantiate_msg(&mut addrs)
+        };
+        setup_sale(deps.as_mut(), &mut addrs, msg);
+
+        let err = buy(deps.as_mut(), mock_env(), &mut addrs, "buyer").unwrap_err();
+        match err {
+            ContractError::SaleNotStarted {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+
+        let mut env = mock_env();
+        env.block.time = now.plus_seconds(100);
+        buy(deps.as_mut(), env.clone(), &mut addrs, "buyer").unwrap();
+
+        env.block.time = now.plus_seconds(200);
+        let err = buy(deps.as_mut(), env, &mut addrs, "buyer").unwrap_err();
+        match err {
+            ContractError::SaleEnded {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+
+        // start must be before end
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            start_time: Some(now.plus_seconds(200)),
+            end_time: Some(now.plus_seconds(200)),
+            ..sale_instantiate_msg(&mut addrs)
+        };
+        let owner = addrs.addr("owner");
+        let err =
+            instantiate(deps.as_mut(), mock_env(), message_info(&owner, &[]), msg).unwrap_err();
+        match err {
+            ContractError::InvalidSaleWindow {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[test]
+    fn pause_and_unpause() {
+        let mut deps = mock_dependencies();
+        let mut addrs = MockAddrFactory::new(deps.api);
+        let msg = sale_instantiate_msg(&mut addrs);
+        setup_sale(deps.as_mut(), &mut addrs, msg);
+
+        // only owner can pause
+        let random = addrs.addr("random");
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&random, &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+
+        let owner = addrs.addr("owner");
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+        let err = buy(deps.as_mut(), mock_env(), &mut addrs, "buyer").unwrap_err();
+        match err {
+            ContractError::SalePaused {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+        buy(deps.as_mut(), mock_env(), &mut addrs, "buyer").unwrap();
+    }
+
+    #[test]
+    fn sale_status() {
+        let mut deps = mock_dependencies();
+        let mut addrs = MockAddrFactory::new(deps.api);
+        let now = mock_env().block.time;
+        let msg = InstantiateMsg {
+            max_per_address: Some(2),
+            start_time: Some(now.plus_seconds(100)),
+            ..sale_instantiate_msg(&mut addrs)
+        };
+        setup_sale(deps.as_mut(), &mut addrs, msg);
+        let buyer = addrs.addr("buyer").to_string();
+
+        let query_status = |deps: Deps, env: Env, address: Option<String>| {
+            from_json::<SaleStatusResponse>(
+                query(deps, env, QueryMsg::SaleStatus { address }).unwrap(),
+            )
+            .unwrap()
+        };
+
+        let status = query_status(deps.as_ref(), mock_env(), Some(buyer.clone()));
+        assert_eq!(
+            status,
+            SaleStatusResponse {
+                phase: SalePhase::NotStarted,
+                remaining_supply: 3,
+                remaining_allocation: Some(2),
+            }
+        );
+
+        let mut env = mock_env();
+        env.block.time = now.plus_seconds(100);
+        buy(deps.as_mut(), env.clone(), &mut addrs, "buyer").unwrap();
+        let status = query_status(deps.as_ref(), env.clone(), Some(buyer.clone()));
+        assert_eq!(
+            status,
+            SaleStatusResponse {
+                phase: SalePhase::Active,
+                remaining_supply: 2,
+                remaining_allocation: Some(1),
+            }
+        );
+        let status = query_status(deps.as_ref(), env.clone(), None);
+        assert_eq!(status.remaining_allocation, None);
+
+        buy(deps.as_mut(), env.clone(), &mut addrs, "other").unwrap();
+        buy(deps.as_mut(), env.clone(), &mut addrs, "other").unwrap();
+        let status = query_status(deps.as_ref(), env, Some(buyer));
+        assert_eq!(
+            status,
+            SaleStatusResponse {
+                phase: SalePhase::SoldOut,
+                remaining_supply: 0,
+                remaining_allocation: Some(0),
+            }
+        );
+    }
+
+    #[test]
+    fn multi_unit_purchase_with_refund() {
+        let mut deps = mock_dependencies();
+        let mut addrs = MockAddrFactory::new(deps.api);
+        let msg = InstantiateMsg {
+            max_per_address: Some(2),
+            ..sale_instantiate_msg(&mut addrs)
+        };
+        setup_sale(deps.as_mut(), &mut addrs, msg);
+
+        // pays for 5 tokens, but allocation is capped at 2
+        let buyer = addrs.addr("buyer");
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: buyer.to_string(),
+            amount: Uint128::new(5),
+            msg: [].into(),
+        });
+        let contract = addrs.addr(MOCK_CONTRACT_ADDR);
+        let res = execute(deps.as_mut(), mock_env(), message_info(&contract, &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: buyer.to_string(),
+                    amount: Uint128::new(3),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        let config: Config =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap()).unwrap();
+        assert_eq!(config.unused_token_id, 2);
+
+        // only 1 token left, exact payment for 1 token is not refunded
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: addrs.addr("other").to_string(),
+            amount: Uint128::new(1),
+            msg: [].into(),
+        });
+        let res = execute(deps.as_mut(), mock_env(), message_info(&contract, &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        let proceeds: ProceedsResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::Proceeds {}).unwrap()).unwrap();
+        assert_eq!(proceeds.cw20, Uint128::new(3));
+    }
+
+    #[test]
+    fn withdraw_proceeds() {
+        let mut deps = mock_dependencies();
+        let mut addrs = MockAddrFactory::new(deps.api);
+        let creator = addrs.addr("creator");
+        let platform = addrs.addr("platform");
+        let msg = InstantiateMsg {
+            unit_price: Uint128::new(10),
+            payees: vec![
+                PayeeMsg {
+                    address: creator.to_string(),
+                    weight: 90,
+                },
+                PayeeMsg {
+                    address: platform.to_string(),
+                    weight: 10,
+                },
+            ],
+            ..sale_instantiate_msg(&mut addrs)
+        };
+        setup_sale(deps.as_mut(), &mut addrs, msg);
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: addrs.addr("buyer").to_string(),
+            amount: Uint128::new(25),
+            msg: [].into(),
+        });
+        let contract = addrs.addr(MOCK_CONTRACT_ADDR);
+        execute(deps.as_mut(), mock_env(), message_info(&contract, &[]), msg).unwrap();
+
+        let proceeds: ProceedsResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::Proceeds {}).unwrap()).unwrap();
+        assert_eq!(
+            proceeds,
+            ProceedsResponse {
+                cw20: Uint128::new(20),
+                native: Uint128::zero(),
+                native_denom: None,
+                payees: vec![
+                    PayeeProceeds {
+                        address: creator.clone(),
+                        weight: 90,
+                        cw20: Uint128::new(18),
+                        native: Uint128::zero(),
+                    },
+                    PayeeProceeds {
+                        address: platform.clone(),
+                        weight: 10,
+                        cw20: Uint128::new(2),
+                        native: Uint128::zero(),
+                    },
+                ],
+            }
+        );
+
+        // anyone can trigger the withdrawal
+        let random = addrs.addr("random");
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&random, &[]),
+            ExecuteMsg::WithdrawProceeds {},
+        )
+        .unwrap();
+        let transfer = |recipient: &Addr, amount: u128| {
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: contract.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: Uint128::new(amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        };
+        assert_eq!(
+            res.messages,
+            vec![transfer(&creator, 18), transfer(&platform, 2)]
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&random, &[]),
+            ExecuteMsg::WithdrawProceeds {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NoProceeds {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[test]
+    fn native_mint() {
+        let mut deps = mock_dependencies();
+        let mut addrs = MockAddrFactory::new(deps.api);
+        let buyer = addrs.addr("buyer");
+
+        // native payment is disabled by default
+        let msg = sale_instantiate_msg(&mut addrs);
+        setup_sale(deps.as_mut(), &mut addrs, msg);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&buyer, &coins(10, "ujuno")),
+            ExecuteMsg::Mint {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NativePaymentDisabled {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            native_price: Some(Coin::new(10u128, "ujuno")),
+            ..sale_instantiate_msg(&mut addrs)
+        };
+        setup_sale(deps.as_mut(), &mut addrs, msg);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&buyer, &coins(25, "ujuno")),
+            ExecuteMsg::Mint {},
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: buyer.to_string(),
+                amount: coins(5, "ujuno"),
+            })
+        );
+
+        let owner = addrs.addr("owner");
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            ExecuteMsg::WithdrawProceeds {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: owner.to_string(),
+                amount: coins(20, "ujuno"),
+            })]
+        );
+    }
+
+    #[test]
+    fn existing_collection() {
+        let mut deps = mock_dependencies();
+        let mut addrs = MockAddrFactory::new(deps.api);
+        let collection = addrs.addr("collection");
+        let msg = InstantiateMsg {
+            cw721_address: Some(collection.to_string()),
+            token_id_offset: Some(100),
+            ..sale_instantiate_msg(&mut addrs)
+        };
+        let owner = addrs.addr("owner");
+        let res = instantiate(deps.as_mut(), mock_env(), message_info(&owner, &[]), msg).unwrap();
+        // no new collection is instantiated
+        assert_eq!(res.messages, vec![]);
+
+        let config: Config =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap()).unwrap();
+        assert_eq!(config.cw721_address, Some(collection.clone()));
+
+        // token ids start at the offset
+        let res = buy(deps.as_mut(), mock_env(), &mut addrs, "buyer").unwrap();
+        let mint_msg = Cw721ExecuteMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::Mint {
+            token_id: String::from("100"),
+            owner: addrs.addr("buyer").to_string(),
+            token_uri: Some(String::from("https://ipfs.io/ipfs/Q")),
+            extension: None,
+        };
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: collection.to_string(),
+                msg: to_json_binary(&mint_msg).unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // owner accepts minter ownership of the collection
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            ExecuteMsg::UpdateMinterOwnership(Action::AcceptOwnership),
+        )
+        .unwrap();
+        let accept_msg = Cw721ExecuteMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::UpdateMinterOwnership(Action::AcceptOwnership);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: collection.to_string(),
+                msg: to_json_binary(&accept_msg).unwrap(),
+                funds: vec![],
+            })
+        );
+
+        let random = addrs.addr("random");
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&random, &[]),
+            ExecuteMsg::UpdateMinterOwnership(Action::AcceptOwnership),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+
+        // reply is rejected, since collection is already linked
+        let reply_msg = Reply {
+            id: INSTANTIATE_TOKEN_REPLY_ID,
+            payload: Binary::default(),
+            gas_used: 1000,
+            #[allow(deprecated)]
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+                msg_responses: vec![],
+            }),
+        };
+        let err = reply(deps.as_mut(), mock_env(), reply_msg).unwrap_err();
+        match err {
+            ContractError::Cw721AlreadyLinked {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[test]
+    fn invalid_reply() {
+        let mut deps = mock_dependencies();
+        let mut addrs = MockAddrFactory::new(deps.api);
+        let msg = sale_instantiate_msg(&mut addrs);
+        let owner = addrs.addr("owner");
+        instantiate(deps.as_mut(), mock_env(), message_info(&owner, &[]), msg).unwrap();
+
+        // failed instantiation
+        let reply_msg = Reply {
+            id: INSTANTIATE_TOKEN_REPLY_ID,
+            payload: Binary::default(),
+            gas_used: 1000,
+            result: SubMsgResult::Err("instantiate failed".to_string()),
+        };
+        let err = reply(deps.as_mut(), mock_env(), reply_msg).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            ContractError::Cw721InstantiateFailed("instantiate failed".to_string()).to_string()
+        );
+
+        // missing msg response
+        let reply_msg = Reply {
+            id: INSTANTIATE_TOKEN_REPLY_ID,
+            payload: Binary::default(),
+            gas_used: 1000,
+            #[allow(deprecated)]
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+                msg_responses: vec![],
+            }),
+        };
+        let err = reply(deps.as_mut(), mock_env(), reply_msg).unwrap_err();
+        match err {
+            ContractError::MissingInstantiateResponse {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+
+        // malformed msg response
+        let reply_msg = Reply {
+            id: INSTANTIATE_TOKEN_REPLY_ID,
+            payload: Binary::default(),
+            gas_used: 1000,
+            #[allow(deprecated)]
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+                msg_responses: vec![MsgResponse {
+                    type_url: "/cosmwasm.wasm.v1.MsgInstantiateContractResponse".to_string(),
+                    value: Binary::from(vec![0xff]),
+                }],
+            }),
+        };
+        let err = reply(deps.as_mut(), mock_env(), reply_msg).unwrap_err();
+        match err {
+            ContractError::ParseReply(_) => {}
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[test]
+    fn reveal() {
+        let mut deps = mock_dependencies();
+        let mut addrs = MockAddrFactory::new(deps.api);
+        let msg = InstantiateMsg {
+            provenance_hash: Some("provenance".to_string()),
+            ..sale_instantiate_msg(&mut addrs)
+        };
+        setup_sale(deps.as_mut(), &mut addrs, msg);
+        let nft_contract = addrs.addr(NFT_CONTRACT_ADDR);
+        buy(deps.as_mut(), mock_env(), &mut addrs, "buyer").unwrap();
+        buy(deps.as_mut(), mock_env(), &mut addrs, "buyer").unwrap();
+
+        // placeholder before reveal
+        let res: TokenUriResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TokenUri {
+                    token_id: "0".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.token_uri, "https://ipfs.io/ipfs/Q");
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&addrs.addr("random"), &[]),
+            ExecuteMsg::SyncTokenUris { limit: None },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NotRevealed {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+
+        let reveal_msg = |provenance_hash: &str| ExecuteMsg::Reveal {
+            base_uri: "https://example.com/meta/".to_string(),
+            provenance_hash: provenance_hash.to_string(),
+        };
+        let random = addrs.addr("random");
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&random, &[]),
+            reveal_msg("provenance"),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+        let owner = addrs.addr("owner");
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            reveal_msg("tampered"),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::ProvenanceMismatch {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            reveal_msg("provenance"),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            reveal_msg("provenance"),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::AlreadyRevealed {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+
+        let provenance: ProvenanceResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::Provenance {}).unwrap()).unwrap();
+        assert_eq!(
+            provenance,
+            ProvenanceResponse {
+                provenance_hash: Some("provenance".to_string()),
+                base_uri: Some("https://example.com/meta/".to_string()),
+                revealed_at: Some(mock_env().block.time),
+                unsynced_tokens: 2,
+            }
+        );
+
+        // tokens minted after reveal point to their own uri
+        let res = buy(deps.as_mut(), mock_env(), &mut addrs, "buyer").unwrap();
+        let mint_msg = Cw721ExecuteMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::Mint {
+            token_id: String::from("2"),
+            owner: addrs.addr("buyer").to_string(),
+            token_uri: Some(String::from("https://example.com/meta/2.json")),
+            extension: None,
+        };
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: nft_contract.to_string(),
+                msg: to_json_binary(&mint_msg).unwrap(),
+                funds: vec![],
+            })
+        );
+
+        // tokens minted before reveal are synced in batches
+        let update_msg = |token_id: &str| {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: nft_contract.to_string(),
+                msg: to_json_binary(&Cw721ExecuteMsg::<
+                    DefaultOptionalNftExtensionMsg,
+                    DefaultOptionalCollectionExtensionMsg,
+                    Empty,
+                >::UpdateNftInfo {
+                    token_id: token_id.to_string(),
+                    token_uri: Some(format!("https://example.com/meta/{token_id}.json")),
+                    extension: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        };
+        for token_id in ["0", "1"] {
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                message_info(&random, &[]),
+                ExecuteMsg::SyncTokenUris { limit: Some(1) },
+            )
+            .unwrap();
+            assert_eq!(res.messages.len(), 1);
+            assert_eq!(res.messages[0].msg, update_msg(token_id));
+        }
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&random, &[]),
+            ExecuteMsg::SyncTokenUris { limit: None },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NothingToSync {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    fn minted_token_ids(res: &Response) -> Vec<String> {
+        res.messages
+            .iter()
+            .filter_map(|sub_msg| match &sub_msg.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                    match from_json::<
+                        Cw721ExecuteMsg<
+                            DefaultOptionalNftExtensionMsg,
+                            DefaultOptionalCollectionExtensionMsg,
+                            Empty,
+                        >,
+                    >(msg)
+                    .ok()?
+                    {
+                        Cw721ExecuteMsg::Mint { token_id, .. } => Some(token_id),
+                        Cw721ExecuteMsg::UpdateNftInfo { token_id, .. } => Some(token_id),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn random_assignment() {
+        let random_sale = |addrs: &mut MockAddrFactory| {
+            let mut deps = mock_dependencies();
+            let msg = InstantiateMsg {
+                max_tokens: 5,
+                random_assignment: true,
+                ..sale_instantiate_msg(addrs)
+            };
+            setup_sale(deps.as_mut(), addrs, msg);
+            deps
+        };
+        let mut deps = mock_dependencies();
+        let mut addrs = MockAddrFactory::new(deps.api);
+        let buy_all = |deps: DepsMut, addrs: &mut MockAddrFactory| {
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: addrs.addr("buyer").to_string(),
+                amount: Uint128::new(5),
+                msg: [].into(),
+            });
+            let contract = addrs.addr(MOCK_CONTRACT_ADDR);
+            execute(deps, mock_env(), message_info(&contract, &[]), msg).unwrap()
+        };
+
+        deps = random_sale(&mut addrs);
+        let token_ids = minted_token_ids(&buy_all(deps.as_mut(), &mut addrs));
+        // all ids are assigned exactly once, but not in sequential order
+        let mut sorted = token_ids.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec!["0", "1", "2", "3", "4"]);
+        assert_ne!(token_ids, sorted);
+
+        // deterministic for the same inputs
+        let mut other_deps = random_sale(&mut addrs);
+        let other_token_ids = minted_token_ids(&buy_all(other_deps.as_mut(), &mut addrs));
+        assert_eq!(token_ids, other_token_ids);
+
+        // tokens minted before reveal are synced in mint order
+        let owner = addrs.addr("owner");
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            ExecuteMsg::Reveal {
+                base_uri: "https://example.com/meta".to_string(),
+                provenance_hash: "provenance".to_string(),
+            },
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            ExecuteMsg::SyncTokenUris { limit: None },
+        )
+        .unwrap();
+        assert_eq!(minted_token_ids(&res), token_ids);
+    }
 }
@@ -1,4 +1,5 @@
 use cosmwasm_std::StdError;
+use cw_utils::{ParseReplyError, PaymentError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,6 +7,15 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
+    #[error("{0}")]
+    Url(#[from] url::ParseError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -35,4 +45,49 @@ pub enum ContractError {
 
     #[error("Cw721AlreadyLinked")]
     Cw721AlreadyLinked {},
+
+    #[error("Cw721InstantiateFailed: {0}")]
+    Cw721InstantiateFailed(String),
+
+    #[error("MissingInstantiateResponse")]
+    MissingInstantiateResponse {},
+
+    #[error("InvalidMaxPerAddress")]
+    InvalidMaxPerAddress {},
+
+    #[error("InvalidSaleWindow")]
+    InvalidSaleWindow {},
+
+    #[error("SaleNotStarted")]
+    SaleNotStarted {},
+
+    #[error("SaleEnded")]
+    SaleEnded {},
+
+    #[error("SalePaused")]
+    SalePaused {},
+
+    #[error("MintLimitReached")]
+    MintLimitReached {},
+
+    #[error("InvalidPayees")]
+    InvalidPayees {},
+
+    #[error("NativePaymentDisabled")]
+    NativePaymentDisabled {},
+
+    #[error("NoProceeds")]
+    NoProceeds {},
+
+    #[error("AlreadyRevealed")]
+    AlreadyRevealed {},
+
+    #[error("NotRevealed")]
+    NotRevealed {},
+
+    #[error("ProvenanceMismatch")]
+    ProvenanceMismatch {},
+
+    #[error("NothingToSync")]
+    NothingToSync {},
 }
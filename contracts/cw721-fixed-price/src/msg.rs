@@ -1,7 +1,9 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Coin, Timestamp, Uint128};
 use cw20::Cw20ReceiveMsg;
-use cw721::DefaultOptionalNftExtension;
+use cw721::{Action, DefaultOptionalNftExtension};
+
+use crate::state::Payee;
 
 #[cw_serde]
 pub struct InstantiateMsg<TCollectionExtensionMsg> {
@@ -19,11 +21,62 @@ pub struct InstantiateMsg<TCollectionExtensionMsg> {
     pub token_uri: String,
     pub extension: DefaultOptionalNftExtension,
     pub withdraw_address: Option<String>,
+    /// Existing cw721 collection to sell from. If set, no new collection is instantiated and
+    /// `token_code_id`, `collection_info_extension` and `withdraw_address` are ignored. The
+    /// collection's minter must then add this contract via `AddMinter`, or transfer minter
+    /// ownership to it, which is accepted via `ExecuteMsg::UpdateMinterOwnership`.
+    pub cw721_address: Option<String>,
+    /// First token id minted by this sale, defaults to 0
+    pub token_id_offset: Option<u32>,
+    /// Optional provenance hash committed before the sale, `Reveal` must then provide the same hash
+    pub provenance_hash: Option<String>,
+    /// Assign token ids randomly instead of sequentially, preventing buyers from predicting
+    /// and sniping specific ids. Defaults to false.
+    #[serde(default)]
+    pub random_assignment: bool,
+    /// Max number of tokens a single address may mint, `None` for no limit
+    pub max_per_address: Option<u32>,
+    /// Sale opens at this time, `None` for opening right after instantiation
+    pub start_time: Option<Timestamp>,
+    /// Sale closes at this time, `None` for no end
+    pub end_time: Option<Timestamp>,
+    /// Optional price for buying with native funds via `ExecuteMsg::Mint`
+    pub native_price: Option<Coin>,
+    /// Proceeds are split between payees by weight, e.g. 90 for creator and 10 for platform.
+    /// If empty or unset, all proceeds go to the owner.
+    #[serde(default)]
+    pub payees: Vec<PayeeMsg>,
+}
+
+#[cw_serde]
+pub struct PayeeMsg {
+    pub address: String,
+    pub weight: u32,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
+    /// Owner pauses the sale, no tokens can be minted until unpaused
+    Pause {},
+    /// Owner resumes a paused sale
+    Unpause {},
+    /// Buys with native funds. Mints floor(funds / native_price) tokens, up to the remaining
+    /// supply and allocation, and refunds the remainder.
+    Mint {},
+    /// Pays out accumulated native and cw20 proceeds to the payees by weight
+    WithdrawProceeds {},
+    /// Owner forwards a minter ownership action to the collection
+    UpdateMinterOwnership(Action),
+    /// Owner reveals the collection once. Token uris then resolve to `{base_uri}/{token_id}.json`.
+    Reveal {
+        base_uri: String,
+        provenance_hash: String,
+    },
+    /// Updates the token uris of tokens minted before the reveal, in batches
+    SyncTokenUris {
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -31,6 +84,19 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(ConfigResponse)]
     GetConfig {},
+    /// Returns the current sale phase, the remaining supply and, if an address is given,
+    /// the number of tokens this address may still mint.
+    #[returns(SaleStatusResponse)]
+    SaleStatus { address: Option<String> },
+    /// Returns accumulated proceeds and the share owed to each payee
+    #[returns(ProceedsResponse)]
+    Proceeds {},
+    /// Returns the committed provenance hash and reveal status
+    #[returns(ProvenanceResponse)]
+    Provenance {},
+    /// Returns the token uri the given token resolves to
+    #[returns(TokenUriResponse)]
+    TokenUri { token_id: String },
 }
 
 #[cw_serde]
@@ -45,4 +111,62 @@ pub struct ConfigResponse {
     pub token_uri: String,
     pub extension: DefaultOptionalNftExtension,
     pub unused_token_id: u32,
+    pub token_id_offset: u32,
+    pub base_uri: Option<String>,
+    pub provenance_hash: Option<String>,
+    pub random_assignment: bool,
+    pub max_per_address: Option<u32>,
+    pub start_time: Option<Timestamp>,
+    pub end_time: Option<Timestamp>,
+    pub paused: bool,
+    pub native_price: Option<Coin>,
+    pub payees: Vec<Payee>,
+}
+
+#[cw_serde]
+pub enum SalePhase {
+    NotStarted,
+    Active,
+    Paused,
+    Ended,
+    SoldOut,
+}
+
+#[cw_serde]
+pub struct SaleStatusResponse {
+    pub phase: SalePhase,
+    pub remaining_supply: u32,
+    /// Tokens the queried address may still mint, `None` if no address is given or there is no
+    /// per address limit.
+    pub remaining_allocation: Option<u32>,
+}
+
+#[cw_serde]
+pub struct ProceedsResponse {
+    pub cw20: Uint128,
+    pub native: Uint128,
+    pub native_denom: Option<String>,
+    pub payees: Vec<PayeeProceeds>,
+}
+
+#[cw_serde]
+pub struct PayeeProceeds {
+    pub address: Addr,
+    pub weight: u32,
+    pub cw20: Uint128,
+    pub native: Uint128,
+}
+
+#[cw_serde]
+pub struct ProvenanceResponse {
+    pub provenance_hash: Option<String>,
+    pub base_uri: Option<String>,
+    pub revealed_at: Option<Timestamp>,
+    /// Tokens minted before the reveal whose token uri is not yet updated
+    pub unsynced_tokens: u32,
+}
+
+#[cw_serde]
+pub struct TokenUriResponse {
+    pub token_uri: String,
 }
@@ -1,7 +1,7 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Binary, Coin, Timestamp, Uint128};
 use cw721::DefaultOptionalNftExtension;
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 
 #[cw_serde]
 pub struct Config {
@@ -15,6 +15,56 @@ pub struct Config {
     pub token_uri: String,
     pub extension: DefaultOptionalNftExtension,
     pub unused_token_id: u32,
+    /// Token ids are minted starting at this offset, allowing several sale rounds on one collection
+    pub token_id_offset: u32,
+    /// Set on reveal, tokens then resolve to `{base_uri}/{token_id}.json`. Until then all tokens
+    /// point to the placeholder `token_uri`.
+    pub base_uri: Option<String>,
+    /// Provenance hash, committed on instantiation or reveal
+    pub provenance_hash: Option<String>,
+    /// Assign token ids randomly instead of sequentially
+    pub random_assignment: bool,
+    /// Max number of tokens a single address may mint, `None` for no limit
+    pub max_per_address: Option<u32>,
+    /// Sale opens at this time, `None` for opening right after instantiation
+    pub start_time: Option<Timestamp>,
+    /// Sale closes at this time, `None` for no end
+    pub end_time: Option<Timestamp>,
+    /// Owner may pause and unpause the sale at any time
+    pub paused: bool,
+    /// Optional price for buying with native funds via `ExecuteMsg::Mint`
+    pub native_price: Option<Coin>,
+    /// Proceeds are split between payees by weight
+    pub payees: Vec<Payee>,
+}
+
+#[cw_serde]
+pub struct Payee {
+    pub address: Addr,
+    pub weight: u32,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+/// Number of tokens minted per buyer, used for enforcing `max_per_address`.
+pub const MINTED_PER_ADDRESS: Map<&Addr, u32> = Map::new("minted_per_address");
+/// Accumulated cw20 proceeds not yet withdrawn.
+pub const CW20_PROCEEDS: Item<Uint128> = Item::new("cw20_proceeds");
+/// Accumulated native proceeds (in `native_price` denom) not yet withdrawn.
+pub const NATIVE_PROCEEDS: Item<Uint128> = Item::new("native_proceeds");
+
+#[cw_serde]
+pub struct Reveal {
+    pub revealed_at: Timestamp,
+    /// Number of tokens minted before the reveal, still pointing to the placeholder uri
+    pub unsynced_count: u32,
+    /// Number of tokens minted before the reveal, already updated via `SyncTokenUris`
+    pub synced_count: u32,
+}
+
+pub const REVEAL: Item<Reveal> = Item::new("reveal");
+
+/// Sparse Fisher–Yates shuffle over token indexes for random assignment. Position `p` holds
+/// index `p` unless stored otherwise.
+pub const SHUFFLE: Map<u32, u32> = Map::new("shuffle");
+/// Rolling entropy for random assignment, updated on every draw.
+pub const ENTROPY: Item<Binary> = Item::new("entropy");
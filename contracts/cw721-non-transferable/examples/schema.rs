@@ -3,15 +3,11 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema_with_title, remove_schemas, schema_for};
 
-use cosmwasm_std::Empty;
-use cw721::{
-    DefaultOptionalCollectionExtension, DefaultOptionalCollectionExtensionMsg,
-    DefaultOptionalNftExtensionMsg,
-};
+use cw721::DefaultOptionalCollectionExtension;
 #[allow(deprecated)]
-use cw721_non_transferable::{InstantiateMsg, QueryMsg};
+use cw721_non_transferable::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
-use cw721::msg::{Cw721ExecuteMsg, Cw721MigrateMsg};
+use cw721::msg::Cw721MigrateMsg;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -25,11 +21,7 @@ fn main() {
         &out_dir,
         "InstantiateMsg",
     );
-    export_schema_with_title(
-        &schema_for!(Cw721ExecuteMsg<DefaultOptionalNftExtensionMsg, DefaultOptionalCollectionExtensionMsg, Empty>),
-        &out_dir,
-        "ExecuteMsg",
-    );
+    export_schema_with_title(&schema_for!(ExecuteMsg), &out_dir, "ExecuteMsg");
     export_schema_with_title(&schema_for!(QueryMsg), &out_dir, "QueryMsg");
     export_schema_with_title(&schema_for!(Cw721MigrateMsg), &out_dir, "MigrateMsg");
 }
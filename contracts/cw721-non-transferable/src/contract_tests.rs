@@ -0,0 +1,377 @@
+use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+use cosmwasm_std::{from_json, Addr, DepsMut, Storage};
+use cw721::error::Cw721ContractError;
+use cw721::msg::{AuthorizationReason, AuthorizationResponse};
+
+use crate::entry::{execute, instantiate, migrate, query};
+use crate::error::ContractError;
+use crate::msg::{
+    ConfigResponse, CredentialStatus, ExecuteMsg, InstantiateMsg, IsHolderOfResponse, QueryMsg,
+    VerifyCredentialResponse,
+};
+use crate::state::Revocation;
+
+fn setup_contract(deps: DepsMut<'_>, admin: &Addr, minter: &Addr) {
+    let msg = InstantiateMsg {
+        admin: Some(admin.to_string()),
+        holder_can_burn: None,
+        issuer_can_revoke: None,
+        name: "Credentials".to_string(),
+        symbol: "CRED".to_string(),
+        collection_info_extension: None,
+        minter: Some(minter.to_string()),
+        creator: None,
+        withdraw_address: None,
+    };
+    instantiate(deps, mock_env(), message_info(admin, &[]), msg).unwrap();
+}
+
+fn mint_msg(token_id: &str, owner: &Addr) -> ExecuteMsg {
+    ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: owner.to_string(),
+        token_uri: None,
+        extension: None,
+        credential_type: None,
+        valid_until: None,
+    }
+}
+
+#[test]
+fn test_soulbound_rules() {
+    let mut deps = mock_dependencies();
+    let admin = deps.api.addr_make("admin");
+    let minter = deps.api.addr_make("minter");
+    let holder = deps.api.addr_make("holder");
+    setup_contract(deps.as_mut(), &admin, &minter);
+
+    let config: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            admin: Some(admin.to_string()),
+            holder_can_burn: false,
+            issuer_can_revoke: true,
+        }
+    );
+
+    // only the minter mints, not the admin
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&admin, &[]),
+        mint_msg("1", &holder),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Cw721(Cw721ContractError::NotMinter {}));
+    for token_id in ["1", "2"] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&minter, &[]),
+            mint_msg(token_id, &holder),
+        )
+        .unwrap();
+    }
+
+    // holders can't transfer or approve
+    for msg in [
+        ExecuteMsg::TransferNft {
+            recipient: minter.to_string(),
+            token_id: "1".to_string(),
+        },
+        ExecuteMsg::Approve {
+            spender: minter.to_string(),
+            token_id: "1".to_string(),
+            expires: None,
+        },
+        ExecuteMsg::ApproveAll {
+            operator: minter.to_string(),
+            expires: None,
+        },
+    ] {
+        let err = execute(deps.as_mut(), mock_env(), message_info(&holder, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::NonTransferable {});
+    }
+    // dry-runs report soulbound, even for the holder
+    for msg in [
+        QueryMsg::CanTransfer {
+            sender: holder.to_string(),
+            token_id: "1".to_string(),
+        },
+        QueryMsg::CanApprove {
+            sender: holder.to_string(),
+            token_id: "1".to_string(),
+        },
+    ] {
+        let res: AuthorizationResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(
+            res,
+            AuthorizationResponse {
+                authorized: false,
+                reason: AuthorizationReason::Soulbound {},
+            }
+        );
+    }
+
+    // holders can burn only if enabled
+    let burn_msg = |token_id: &str| ExecuteMsg::Burn {
+        token_id: token_id.to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&holder, &[]),
+        burn_msg("1"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::HolderBurnDisabled {});
+    let update_msg = ExecuteMsg::UpdateConfig {
+        holder_can_burn: Some(true),
+        issuer_can_revoke: Some(false),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&minter, &[]),
+        update_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotAdmin {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&admin, &[]),
+        update_msg,
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&holder, &[]),
+        burn_msg("1"),
+    )
+    .unwrap();
+
+    // issuer can revoke only if enabled, others never
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&minter, &[]),
+        burn_msg("2"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RevokeDisabled {});
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&admin, &[]),
+        ExecuteMsg::UpdateConfig {
+            holder_can_burn: None,
+            issuer_can_revoke: Some(true),
+        },
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&admin, &[]),
+        burn_msg("2"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Cw721(Cw721ContractError::NotMinter {}));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&minter, &[]),
+        burn_msg("2"),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[0].value, "revoke");
+}
+
+#[test]
+fn test_migrate_config_without_soulbound_flags() {
+    let mut deps = mock_dependencies();
+    let admin = deps.api.addr_make("admin");
+    let minter = deps.api.addr_make("minter");
+    setup_contract(deps.as_mut(), &admin, &minter);
+    // config as stored by versions before the soulbound flags
+    deps.storage
+        .set(b"config", format!(r#"{{"admin":"{admin}"}}"#).as_bytes());
+
+    migrate(
+        deps.as_mut(),
+        mock_env(),
+        cw721::msg::Cw721MigrateMsg::WithUpdate {
+            minter: None,
+            creator: None,
+        },
+    )
+    .unwrap();
+
+    // same defaults as on instantiate, stored explicitly
+    let config: ConfigResponse =
+        from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            admin: Some(admin.to_string()),
+            holder_can_burn: false,
+            issuer_can_revoke: true,
+        }
+    );
+    let stored = String::from_utf8(deps.storage.get(b"config").unwrap()).unwrap();
+    assert!(stored.contains(r#""holder_can_burn":false"#));
+    assert!(stored.contains(r#""issuer_can_revoke":true"#));
+}
+
+#[test]
+fn test_credentials() {
+    let mut deps = mock_dependencies();
+    let admin = deps.api.addr_make("admin");
+    let minter = deps.api.addr_make("minter");
+    let holder = deps.api.addr_make("holder");
+    setup_contract(deps.as_mut(), &admin, &minter);
+    let mut env = mock_env();
+    let now = env.block.time;
+
+    let credential_msg = |token_id: &str, valid_until| ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: holder.to_string(),
+        token_uri: None,
+        extension: None,
+        credential_type: Some("kyc".to_string()),
+        valid_until,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&minter, &[]),
+        credential_msg("1", Some(now)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidValidUntil {});
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&minter, &[]),
+        credential_msg("1", Some(now.plus_days(1))),
+    )
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&minter, &[]),
+        credential_msg("2", None),
+    )
+    .unwrap();
+
+    let verify = |deps: cosmwasm_std::Deps, env: cosmwasm_std::Env, token_id: &str| {
+        from_json::<VerifyCredentialResponse>(
+            query(
+                deps,
+                env,
+                QueryMsg::VerifyCredential {
+                    token_id: token_id.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let is_holder_of = |deps: cosmwasm_std::Deps, env: cosmwasm_std::Env, credential_type: &str| {
+        from_json::<IsHolderOfResponse>(
+            query(
+                deps,
+                env,
+                QueryMsg::IsHolderOf {
+                    owner: holder.to_string(),
+                    credential_type: credential_type.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        verify(deps.as_ref(), env.clone(), "1"),
+        VerifyCredentialResponse {
+            token_id: "1".to_string(),
+            owner: holder.to_string(),
+            status: CredentialStatus::Valid,
+            credential_type: Some("kyc".to_string()),
+            issued_at: Some(now),
+            valid_until: Some(now.plus_days(1)),
+            revocation: None,
+        }
+    );
+    assert_eq!(
+        is_holder_of(deps.as_ref(), env.clone(), "kyc").token_id,
+        Some("1".to_string())
+    );
+    assert!(!is_holder_of(deps.as_ref(), env.clone(), "membership").is_holder);
+
+    // expired credentials are skipped
+    env.block.time = now.plus_days(1);
+    assert_eq!(
+        verify(deps.as_ref(), env.clone(), "1").status,
+        CredentialStatus::Expired
+    );
+    assert_eq!(
+        is_holder_of(deps.as_ref(), env.clone(), "kyc").token_id,
+        Some("2".to_string())
+    );
+
+    // only the issuer revokes, with a reason
+    let revoke_msg = ExecuteMsg::RevokeCredential {
+        token_id: "2".to_string(),
+        reason: "fraud".to_string(),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&holder, &[]),
+        revoke_msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Cw721(Cw721ContractError::NotMinter {}));
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&minter, &[]),
+        revoke_msg.clone(),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        message_info(&minter, &[]),
+        revoke_msg,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::AlreadyRevoked {
+            token_id: "2".to_string()
+        }
+    );
+    let verified = verify(deps.as_ref(), env.clone(), "2");
+    assert_eq!(verified.status, CredentialStatus::Revoked);
+    assert_eq!(
+        verified.revocation,
+        Some(Revocation {
+            reason: "fraud".to_string(),
+            revoked_at: env.block.time,
+        })
+    );
+    assert_eq!(
+        is_holder_of(deps.as_ref(), env, "kyc"),
+        IsHolderOfResponse {
+            is_holder: false,
+            token_id: None,
+        }
+    );
+}
@@ -0,0 +1,30 @@
+use cosmwasm_std::StdError;
+use cw721::error::Cw721ContractError;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ContractError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Cw721(#[from] Cw721ContractError),
+
+    #[error("Caller is not the admin")]
+    NotAdmin {},
+
+    #[error("Tokens are non-transferable")]
+    NonTransferable {},
+
+    #[error("Holders are not allowed to burn their tokens")]
+    HolderBurnDisabled {},
+
+    #[error("Issuer is not allowed to revoke tokens")]
+    RevokeDisabled {},
+
+    #[error("Valid until must be in the future")]
+    InvalidValidUntil {},
+
+    #[error("Credential {token_id} is already revoked")]
+    AlreadyRevoked { token_id: String },
+}
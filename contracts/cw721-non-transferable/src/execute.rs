@@ -0,0 +1,227 @@
+use cosmwasm_std::{DepsMut, Empty, Env, MessageInfo, Response, Timestamp};
+use cw721::{
+    execute::assert_minter, msg::Cw721ExecuteMsg, state::Cw721Config, traits::Cw721Execute,
+    EmptyOptionalNftExtension, EmptyOptionalNftExtensionMsg,
+};
+
+use crate::{
+    error::ContractError,
+    msg::ExecuteMsg,
+    state::{Credential, Revocation, CONFIG, CREDENTIALS, HOLDER_CREDENTIALS},
+    Cw721NonTransferableContract,
+};
+
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let cw721_msg = match msg {
+        ExecuteMsg::UpdateConfig {
+            holder_can_burn,
+            issuer_can_revoke,
+        } => return update_config(deps, info, holder_can_burn, issuer_can_revoke),
+        ExecuteMsg::RevokeCredential { token_id, reason } => {
+            return revoke_credential(deps, env, info, token_id, reason)
+        }
+        ExecuteMsg::TransferNft { .. }
+        | ExecuteMsg::SendNft { .. }
+        | ExecuteMsg::Approve { .. }
+        | ExecuteMsg::Revoke { .. }
+        | ExecuteMsg::ApproveAll { .. }
+        | ExecuteMsg::RevokeAll { .. } => return Err(ContractError::NonTransferable {}),
+        ExecuteMsg::Burn { token_id } => return burn(deps, info, token_id),
+        #[allow(deprecated)]
+        ExecuteMsg::UpdateOwnership(action) => Cw721ExecuteMsg::UpdateOwnership(action),
+        ExecuteMsg::UpdateMinterOwnership(action) => Cw721ExecuteMsg::UpdateMinterOwnership(action),
+        ExecuteMsg::UpdateCreatorOwnership(action) => {
+            Cw721ExecuteMsg::UpdateCreatorOwnership(action)
+        }
+        ExecuteMsg::UpdateCollectionInfo { collection_info } => {
+            Cw721ExecuteMsg::UpdateCollectionInfo { collection_info }
+        }
+        ExecuteMsg::Mint {
+            token_id,
+            owner,
+            token_uri,
+            extension,
+            credential_type,
+            valid_until,
+        } => {
+            return mint(
+                deps,
+                env,
+                info,
+                token_id,
+                owner,
+                token_uri,
+                extension,
+                credential_type,
+                valid_until,
+            )
+        }
+        ExecuteMsg::AddMinter { minter } => Cw721ExecuteMsg::AddMinter { minter },
+        ExecuteMsg::RemoveMinter { minter } => Cw721ExecuteMsg::RemoveMinter { minter },
+        ExecuteMsg::UpdateCollectionExtensionAttributes { set, remove } => {
+            Cw721ExecuteMsg::UpdateCollectionExtensionAttributes { set, remove }
+        }
+        ExecuteMsg::BackfillIndexes { limit } => Cw721ExecuteMsg::BackfillIndexes { limit },
+        ExecuteMsg::UpdateNftInfo {
+            token_id,
+            token_uri,
+            extension,
+        } => Cw721ExecuteMsg::UpdateNftInfo {
+            token_id,
+            token_uri,
+            extension,
+        },
+        ExecuteMsg::SetWithdrawAddress { address } => {
+            Cw721ExecuteMsg::SetWithdrawAddress { address }
+        }
+        ExecuteMsg::RemoveWithdrawAddress {} => Cw721ExecuteMsg::RemoveWithdrawAddress {},
+        ExecuteMsg::WithdrawFunds { amount } => Cw721ExecuteMsg::WithdrawFunds { amount },
+    };
+    Ok(Cw721NonTransferableContract::default().execute(deps, &env, &info, cw721_msg)?)
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    holder_can_burn: Option<bool>,
+    issuer_can_revoke: Option<bool>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.admin.as_ref() != Some(&info.sender) {
+        return Err(ContractError::NotAdmin {});
+    }
+    if let Some(holder_can_burn) = holder_can_burn {
+        config.holder_can_burn = holder_can_burn;
+    }
+    if let Some(issuer_can_revoke) = issuer_can_revoke {
+        config.issuer_can_revoke = issuer_can_revoke;
+    }
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("action", "update_config")
+        .add_attribute("holder_can_burn", config.holder_can_burn.to_string())
+        .add_attribute("issuer_can_revoke", config.issuer_can_revoke.to_string()))
+}
+
+/// Mints a token with its credential, issued at the current block time.
+#[allow(clippy::too_many_arguments)]
+pub fn mint(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    owner: String,
+    token_uri: Option<String>,
+    extension: EmptyOptionalNftExtensionMsg,
+    credential_type: Option<String>,
+    valid_until: Option<Timestamp>,
+) -> Result<Response, ContractError> {
+    if valid_until.is_some_and(|valid_until| valid_until <= env.block.time) {
+        return Err(ContractError::InvalidValidUntil {});
+    }
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let mut response = Cw721NonTransferableContract::default().mint(
+        deps.branch(),
+        &env,
+        &info,
+        token_id.clone(),
+        owner,
+        token_uri,
+        extension,
+    )?;
+    let credential = Credential {
+        credential_type,
+        issued_at: Some(env.block.time),
+        valid_until,
+        revocation: None,
+    };
+    if let Some(credential_type) = &credential.credential_type {
+        HOLDER_CREDENTIALS.save(
+            deps.storage,
+            (&owner_addr, credential_type, &token_id),
+            &Empty {},
+        )?;
+        response = response.add_attribute("credential_type", credential_type);
+    }
+    if let Some(valid_until) = valid_until {
+        response = response.add_attribute("valid_until", valid_until.to_string());
+    }
+    CREDENTIALS.save(deps.storage, &token_id, &credential)?;
+    Ok(response)
+}
+
+/// Marks a credential as revoked by the issuer (minter).
+pub fn revoke_credential(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    assert_minter(deps.storage, &info.sender)?;
+    if !CONFIG.load(deps.storage)?.issuer_can_revoke {
+        return Err(ContractError::RevokeDisabled {});
+    }
+    // ensure token exists
+    Cw721Config::<EmptyOptionalNftExtension>::default()
+        .nft_info
+        .load(deps.storage, &token_id)?;
+    let mut credential = CREDENTIALS
+        .may_load(deps.storage, &token_id)?
+        .unwrap_or(Credential {
+            credential_type: None,
+            issued_at: None,
+            valid_until: None,
+            revocation: None,
+        });
+    if credential.revocation.is_some() {
+        return Err(ContractError::AlreadyRevoked { token_id });
+    }
+    credential.revocation = Some(Revocation {
+        reason: reason.clone(),
+        revoked_at: env.block.time,
+    });
+    CREDENTIALS.save(deps.storage, &token_id, &credential)?;
+    Ok(Response::new()
+        .add_attribute("action", "revoke_credential")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id)
+        .add_attribute("reason", reason))
+}
+
+/// Burns a token, either by its holder or revoked by the issuer (minter).
+pub fn burn(deps: DepsMut, info: MessageInfo, token_id: String) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let cw721_config = Cw721Config::<EmptyOptionalNftExtension>::default();
+    let token = cw721_config.nft_info.load(deps.storage, &token_id)?;
+    let action = if token.owner == info.sender {
+        if !config.holder_can_burn {
+            return Err(ContractError::HolderBurnDisabled {});
+        }
+        "burn"
+    } else {
+        assert_minter(deps.storage, &info.sender)?;
+        if !config.issuer_can_revoke {
+            return Err(ContractError::RevokeDisabled {});
+        }
+        "revoke"
+    };
+    cw721_config.remove_token(deps.storage, &token_id, &token)?;
+    if let Some(credential) = CREDENTIALS.may_load(deps.storage, &token_id)? {
+        if let Some(credential_type) = credential.credential_type {
+            HOLDER_CREDENTIALS.remove(deps.storage, (&token.owner, &credential_type, &token_id));
+        }
+        CREDENTIALS.remove(deps.storage, &token_id);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("sender", info.sender)
+        .add_attribute("owner", token.owner)
+        .add_attribute("token_id", token_id))
+}
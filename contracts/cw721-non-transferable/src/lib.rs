@@ -1,12 +1,16 @@
-pub use crate::msg::{InstantiateMsg, QueryMsg};
-use cosmwasm_std::Empty;
+pub use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 use cw721::extension::Cw721EmptyExtensions;
 
+pub mod error;
+pub mod execute;
 #[allow(deprecated)]
 pub mod msg;
 pub mod query;
 pub mod state;
 
+#[cfg(test)]
+mod contract_tests;
+
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw721-non-transferable";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -16,15 +20,16 @@ pub type Cw721NonTransferableContract<'a> = Cw721EmptyExtensions<'a>;
 #[cfg(not(feature = "library"))]
 pub mod entry {
     use super::*;
-    use crate::query::admin;
+    use crate::error::ContractError;
+    use crate::query::{admin, config, is_holder_of, soulbound_authorization, verify_credential};
     use crate::state::{Config, CONFIG};
     use cosmwasm_std::{
         entry_point, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
     };
     use cw721::error::Cw721ContractError;
-    use cw721::msg::{Cw721ExecuteMsg, Cw721InstantiateMsg};
+    use cw721::msg::Cw721InstantiateMsg;
     use cw721::traits::{Cw721Execute, Cw721Query};
-    use cw721::{EmptyOptionalCollectionExtensionMsg, EmptyOptionalNftExtensionMsg};
+    use cw721::EmptyOptionalCollectionExtensionMsg;
 
     #[entry_point]
     pub fn instantiate(
@@ -32,14 +37,18 @@ pub mod entry {
         env: Env,
         info: MessageInfo,
         msg: InstantiateMsg<EmptyOptionalCollectionExtensionMsg>,
-    ) -> Result<Response, Cw721ContractError> {
+    ) -> Result<Response, ContractError> {
         let admin_addr: Option<Addr> = msg
             .admin
             .as_deref()
             .map(|s| deps.api.addr_validate(s))
             .transpose()?;
 
-        let config = Config { admin: admin_addr };
+        let config = Config {
+            admin: admin_addr,
+            holder_can_burn: msg.holder_can_burn.unwrap_or(false),
+            issuer_can_revoke: msg.issuer_can_revoke.unwrap_or(true),
+        };
 
         CONFIG.save(deps.storage, &config)?;
 
@@ -73,42 +82,32 @@ pub mod entry {
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        msg: Cw721ExecuteMsg<
-            EmptyOptionalNftExtensionMsg,
-            EmptyOptionalCollectionExtensionMsg,
-            Empty,
-        >,
-    ) -> Result<Response, Cw721ContractError> {
-        let config = CONFIG.load(deps.storage)?;
-        match config.admin {
-            Some(admin) => {
-                if admin == info.sender {
-                    Cw721EmptyExtensions::default().execute(deps, &env, &info, msg)
-                } else {
-                    Err(Cw721ContractError::Ownership(
-                        cw721::OwnershipError::NotOwner,
-                    ))
-                }
-            }
-            None => match msg {
-                Cw721ExecuteMsg::Mint {
-                    token_id,
-                    owner,
-                    token_uri,
-                    extension,
-                } => Cw721NonTransferableContract::default()
-                    .mint(deps, &env, &info, token_id, owner, token_uri, extension),
-                _ => Err(Cw721ContractError::Ownership(
-                    cw721::OwnershipError::NotOwner,
-                )),
-            },
-        }
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        crate::execute::execute(deps, env, info, msg)
     }
 
     #[entry_point]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, Cw721ContractError> {
         match msg {
             QueryMsg::Admin {} => Ok(to_json_binary(&admin(deps)?)?),
+            QueryMsg::Config {} => Ok(to_json_binary(&config(deps)?)?),
+            QueryMsg::VerifyCredential { token_id } => {
+                Ok(to_json_binary(&verify_credential(deps, &env, token_id)?)?)
+            }
+            QueryMsg::IsHolderOf {
+                owner,
+                credential_type,
+            } => Ok(to_json_binary(&is_holder_of(
+                deps,
+                &env,
+                owner,
+                credential_type,
+            )?)?),
+            QueryMsg::CanTransfer { sender, token_id }
+            | QueryMsg::CanApprove { sender, token_id } => Ok(to_json_binary(
+                &soulbound_authorization(deps, sender, token_id)?,
+            )?),
             _ => Cw721EmptyExtensions::default().query(deps, &env, msg.into()),
         }
     }
@@ -119,6 +118,9 @@ pub mod entry {
         env: Env,
         msg: cw721::msg::Cw721MigrateMsg,
     ) -> Result<Response, Cw721ContractError> {
+        // configs stored by older versions lack the soulbound flags, store them explicitly
+        let config = CONFIG.load(deps.storage)?;
+        CONFIG.save(deps.storage, &config)?;
         let contract = Cw721EmptyExtensions::default();
         contract.migrate(deps, env, msg, CONTRACT_NAME, CONTRACT_VERSION)
     }
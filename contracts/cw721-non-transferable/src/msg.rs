@@ -1,11 +1,21 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::Empty;
-use cw721::{msg::Cw721QueryMsg, EmptyOptionalCollectionExtension, EmptyOptionalNftExtension};
+use crate::state::Revocation;
+use cosmwasm_std::{Binary, Coin, Empty, Timestamp};
+use cw721::{
+    msg::{AttributeMsg, CollectionInfoMsg, Cw721QueryMsg, OrderBy},
+    Action, EmptyOptionalCollectionExtension, EmptyOptionalCollectionExtensionMsg,
+    EmptyOptionalNftExtension, EmptyOptionalNftExtensionMsg, Expiration,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg<TCollectionExtension> {
+    /// Optional admin, eligible to update the soulbound rules. If not set, rules are immutable.
     pub admin: Option<String>,
+    /// Whether holders may burn their own tokens, defaults to false
+    pub holder_can_burn: Option<bool>,
+    /// Whether the issuer (minter) may revoke tokens by burning them, defaults to true
+    pub issuer_can_revoke: Option<bool>,
     /// Name of the NFT contract
     pub name: String,
     /// Symbol of the NFT contract
@@ -17,9 +27,158 @@ pub struct InstantiateMsg<TCollectionExtension> {
     pub withdraw_address: Option<String>,
 }
 
+/// Tokens are soulbound: transfers, sends and approvals are always rejected.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Updates the soulbound rules. Only the admin can call this.
+    UpdateConfig {
+        holder_can_burn: Option<bool>,
+        issuer_can_revoke: Option<bool>,
+    },
+    /// Marks a credential as revoked, the token is kept for verification. Only the issuer (minter)
+    /// can call this, if `issuer_can_revoke` is set.
+    RevokeCredential {
+        token_id: String,
+        reason: String,
+    },
+
+    // -- below copied from Cw721ExecuteMsg
+    #[deprecated(since = "0.19.0", note = "Please use UpdateMinterOwnership instead")]
+    /// Deprecated: use UpdateMinterOwnership instead! Will be removed in next release!
+    UpdateOwnership(Action),
+    UpdateMinterOwnership(Action),
+    UpdateCreatorOwnership(Action),
+
+    /// The creator is the only one eligible to update `CollectionInfo`.
+    UpdateCollectionInfo {
+        collection_info: CollectionInfoMsg<EmptyOptionalCollectionExtensionMsg>,
+    },
+    /// Not supported, tokens are non-transferable
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    /// Not supported, tokens are non-transferable
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    /// Not supported, tokens are non-transferable
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Not supported, tokens are non-transferable
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    /// Not supported, tokens are non-transferable
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// Not supported, tokens are non-transferable
+    RevokeAll {
+        operator: String,
+    },
+
+    /// Mint a new NFT, can only be called by the contract minter
+    Mint {
+        /// Unique ID of the NFT
+        token_id: String,
+        /// The owner of the newly minter NFT
+        owner: String,
+        /// Universal resource identifier for this NFT
+        /// Should point to a JSON file that conforms to the ERC721
+        /// Metadata JSON Schema
+        token_uri: Option<String>,
+        /// Any custom extension used by this contract
+        extension: EmptyOptionalNftExtensionMsg,
+        /// Optional type of the credential, e.g. "kyc" or "membership"
+        credential_type: Option<String>,
+        /// Optional time until the credential is valid (exclusive)
+        valid_until: Option<Timestamp>,
+    },
+
+    /// Burn an NFT. Holders can burn their own NFTs if `holder_can_burn` is set,
+    /// the issuer (minter) can revoke any NFT if `issuer_can_revoke` is set.
+    Burn {
+        token_id: String,
+    },
+
+    /// Add an additional minter. Only the primary minter (manager) can call this.
+    AddMinter {
+        minter: String,
+    },
+    /// Remove an additional minter. Only the primary minter (manager) can call this.
+    RemoveMinter {
+        minter: String,
+    },
+
+    /// The creator is the only one eligible to add, update and remove custom attributes
+    /// of the collection extension. Keys of the default collection extension are reserved
+    /// and can only be changed via `UpdateCollectionInfo`.
+    UpdateCollectionExtensionAttributes {
+        /// Attributes to add, existing attributes with the same key are overwritten.
+        set: Vec<AttributeMsg>,
+        /// Keys of attributes to remove.
+        remove: Vec<String>,
+    },
+    /// Backfills indexes of collections created before they existed, in batches of `limit`
+    /// tokens. Started on migration, anyone can continue until done.
+    BackfillIndexes {
+        limit: Option<u32>,
+    },
+
+    /// The creator is the only one eligible to update NFT's token uri and onchain metadata (`NftInfo.extension`).
+    /// NOTE: approvals and owner are not affected by this call, since they belong to the NFT owner.
+    UpdateNftInfo {
+        token_id: String,
+        /// NOTE: Empty string is handled as None
+        token_uri: Option<String>,
+        extension: EmptyOptionalNftExtensionMsg,
+    },
+
+    /// Sets address to send withdrawn fees to. Only owner can call this.
+    SetWithdrawAddress {
+        address: String,
+    },
+    /// Removes the withdraw address, so fees are sent to the contract. Only owner can call this.
+    RemoveWithdrawAddress {},
+    /// Withdraw from the contract to the given address. Anyone can call this,
+    /// which is okay since withdraw address has been set by owner.
+    WithdrawFunds {
+        amount: Coin,
+    },
+}
+
 #[cw_serde]
 pub enum QueryMsg {
     Admin {},
+    /// Returns the soulbound rules of this contract
+    Config {},
+    /// Returns whether the credential is valid, expired or revoked
+    VerifyCredential {
+        token_id: String,
+    },
+    /// Returns whether the owner holds a valid credential of the given type
+    IsHolderOf {
+        owner: String,
+        credential_type: String,
+    },
+    /// Dry-run of transferring a token, always fails since tokens are soulbound
+    CanTransfer {
+        sender: String,
+        token_id: String,
+    },
+    /// Dry-run of approving a spender for a token, always fails since tokens are soulbound
+    CanApprove {
+        sender: String,
+        token_id: String,
+    },
 
     // -- below copied from Cw721QueryMsg
     OwnerOf {
@@ -42,6 +201,14 @@ pub enum QueryMsg {
         limit: Option<u32>,
     },
     NumTokens {},
+    CollectionStats {},
+    BalanceOf {
+        owner: String,
+    },
+    Holders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     #[deprecated(
         since = "0.19.0",
         note = "Please use GetCollectionInfoAndExtension instead"
@@ -70,10 +237,22 @@ pub enum QueryMsg {
         owner: String,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
     },
     AllTokens {
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
+    },
+
+    GetCollectionExtensionAttribute {
+        key: String,
     },
 
     GetWithdrawAddress {},
@@ -94,6 +273,11 @@ impl From<QueryMsg>
                 include_expired,
             },
             QueryMsg::NumTokens {} => Cw721QueryMsg::NumTokens {},
+            QueryMsg::CollectionStats {} => Cw721QueryMsg::CollectionStats {},
+            QueryMsg::BalanceOf { owner } => Cw721QueryMsg::BalanceOf { owner },
+            QueryMsg::Holders { start_after, limit } => {
+                Cw721QueryMsg::Holders { start_after, limit }
+            }
             #[allow(deprecated)]
             QueryMsg::ContractInfo {} => Cw721QueryMsg::GetCollectionInfoAndExtension {},
             QueryMsg::GetCollectionInfoAndExtension {} => {
@@ -111,23 +295,43 @@ impl From<QueryMsg>
                 owner,
                 start_after,
                 limit,
+                end_before,
+                order,
             } => Cw721QueryMsg::Tokens {
                 owner,
                 start_after,
                 limit,
+                end_before,
+                order,
+            },
+            QueryMsg::AllTokens {
+                start_after,
+                limit,
+                end_before,
+                order,
+            } => Cw721QueryMsg::AllTokens {
+                start_after,
+                limit,
+                end_before,
+                order,
             },
-            QueryMsg::AllTokens { start_after, limit } => {
-                Cw721QueryMsg::AllTokens { start_after, limit }
-            }
             #[allow(deprecated)]
             QueryMsg::Minter {} => Cw721QueryMsg::Minter {},
             QueryMsg::GetMinterOwnership {} => Cw721QueryMsg::GetMinterOwnership {},
             QueryMsg::GetCreatorOwnership {} => Cw721QueryMsg::GetCreatorOwnership {},
+            QueryMsg::GetCollectionExtensionAttribute { key } => {
+                Cw721QueryMsg::GetCollectionExtensionAttribute { key }
+            }
             QueryMsg::GetWithdrawAddress {} => Cw721QueryMsg::GetWithdrawAddress {},
             QueryMsg::AllOperators { .. } => unreachable!("AllOperators is not supported!"),
             QueryMsg::Approval { .. } => unreachable!("Approval is not supported!"),
             QueryMsg::Approvals { .. } => unreachable!("Approvals is not supported!"),
             QueryMsg::Admin { .. } => unreachable!("Approvals is not supported!"),
+            QueryMsg::Config { .. } => unreachable!("Config is not supported!"),
+            QueryMsg::VerifyCredential { .. } => unreachable!("VerifyCredential is not supported!"),
+            QueryMsg::IsHolderOf { .. } => unreachable!("IsHolderOf is not supported!"),
+            QueryMsg::CanTransfer { .. } => unreachable!("CanTransfer is not supported!"),
+            QueryMsg::CanApprove { .. } => unreachable!("CanApprove is not supported!"),
         }
     }
 }
@@ -136,3 +340,36 @@ impl From<QueryMsg>
 pub struct AdminResponse {
     pub admin: Option<String>,
 }
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub admin: Option<String>,
+    pub holder_can_burn: bool,
+    pub issuer_can_revoke: bool,
+}
+
+#[cw_serde]
+pub enum CredentialStatus {
+    Valid,
+    Expired,
+    Revoked,
+}
+
+#[cw_serde]
+pub struct VerifyCredentialResponse {
+    pub token_id: String,
+    pub owner: String,
+    pub status: CredentialStatus,
+    pub credential_type: Option<String>,
+    /// Not set for tokens minted before credentials were supported
+    pub issued_at: Option<Timestamp>,
+    pub valid_until: Option<Timestamp>,
+    pub revocation: Option<Revocation>,
+}
+
+#[cw_serde]
+pub struct IsHolderOfResponse {
+    pub is_holder: bool,
+    /// First valid credential of the given type
+    pub token_id: Option<String>,
+}
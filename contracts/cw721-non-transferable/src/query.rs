@@ -1,5 +1,16 @@
-use crate::{msg::AdminResponse, state::CONFIG};
-use cosmwasm_std::{Deps, StdResult};
+use crate::{
+    msg::{
+        AdminResponse, ConfigResponse, CredentialStatus, IsHolderOfResponse,
+        VerifyCredentialResponse,
+    },
+    state::{Credential, CONFIG, CREDENTIALS, HOLDER_CREDENTIALS},
+};
+use cosmwasm_std::{Deps, Env, Order, StdResult};
+use cw721::{
+    msg::{AuthorizationReason, AuthorizationResponse},
+    state::Cw721Config,
+    EmptyOptionalNftExtension,
+};
 
 pub fn admin(deps: Deps) -> StdResult<AdminResponse> {
     let config = CONFIG.load(deps.storage)?;
@@ -7,3 +18,93 @@ pub fn admin(deps: Deps) -> StdResult<AdminResponse> {
         admin: config.admin.map(|admin| admin.to_string()),
     })
 }
+
+pub fn config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        admin: config.admin.map(|admin| admin.to_string()),
+        holder_can_burn: config.holder_can_burn,
+        issuer_can_revoke: config.issuer_can_revoke,
+    })
+}
+
+pub fn verify_credential(
+    deps: Deps,
+    env: &Env,
+    token_id: String,
+) -> StdResult<VerifyCredentialResponse> {
+    let token = Cw721Config::<EmptyOptionalNftExtension>::default()
+        .nft_info
+        .load(deps.storage, &token_id)?;
+    let credential = CREDENTIALS.may_load(deps.storage, &token_id)?;
+    let status = credential
+        .as_ref()
+        .map_or(CredentialStatus::Valid, |credential| {
+            credential_status(env, credential)
+        });
+    Ok(VerifyCredentialResponse {
+        token_id,
+        owner: token.owner.to_string(),
+        status,
+        credential_type: credential
+            .as_ref()
+            .and_then(|credential| credential.credential_type.clone()),
+        issued_at: credential
+            .as_ref()
+            .and_then(|credential| credential.issued_at),
+        valid_until: credential
+            .as_ref()
+            .and_then(|credential| credential.valid_until),
+        revocation: credential.and_then(|credential| credential.revocation),
+    })
+}
+
+/// Transfers and approvals are rejected for all tokens, see `ContractError::NonTransferable`.
+pub fn soulbound_authorization(
+    deps: Deps,
+    sender: String,
+    token_id: String,
+) -> StdResult<AuthorizationResponse> {
+    deps.api.addr_validate(&sender)?;
+    // ensure token exists
+    Cw721Config::<EmptyOptionalNftExtension>::default()
+        .nft_info
+        .load(deps.storage, &token_id)?;
+    Ok(AuthorizationReason::Soulbound {}.into())
+}
+
+pub fn is_holder_of(
+    deps: Deps,
+    env: &Env,
+    owner: String,
+    credential_type: String,
+) -> StdResult<IsHolderOfResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    for token_id in HOLDER_CREDENTIALS
+        .prefix((&owner, credential_type.as_str()))
+        .keys(deps.storage, None, None, Order::Ascending)
+    {
+        let token_id = token_id?;
+        let credential = CREDENTIALS.load(deps.storage, &token_id)?;
+        if credential_status(env, &credential) == CredentialStatus::Valid {
+            return Ok(IsHolderOfResponse {
+                is_holder: true,
+                token_id: Some(token_id),
+            });
+        }
+    }
+    Ok(IsHolderOfResponse {
+        is_holder: false,
+        token_id: None,
+    })
+}
+
+pub fn credential_status(env: &Env, credential: &Credential) -> CredentialStatus {
+    if credential.revocation.is_some() {
+        return CredentialStatus::Revoked;
+    }
+    match credential.valid_until {
+        Some(valid_until) if env.block.time >= valid_until => CredentialStatus::Expired,
+        _ => CredentialStatus::Valid,
+    }
+}
@@ -1,10 +1,41 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Empty, Timestamp};
+use cw_storage_plus::{Item, Map};
 
 #[cw_serde]
 pub struct Config {
     pub admin: Option<Addr>,
+    /// Holders may burn their own tokens. Defaults to false, same as on instantiate, for configs
+    /// stored by older versions.
+    #[serde(default)]
+    pub holder_can_burn: bool,
+    /// Issuer (minter) may revoke tokens. Defaults to true, same as on instantiate, for configs
+    /// stored by older versions.
+    #[serde(default = "default_issuer_can_revoke")]
+    pub issuer_can_revoke: bool,
+}
+
+fn default_issuer_can_revoke() -> bool {
+    true
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+pub const CREDENTIALS: Map<&str, Credential> = Map::new("credentials");
+/// Credentials by holder, credential type and token id
+pub const HOLDER_CREDENTIALS: Map<(&Addr, &str, &str), Empty> = Map::new("holder_credentials");
+
+#[cw_serde]
+pub struct Credential {
+    pub credential_type: Option<String>,
+    /// Not set for tokens minted before credentials were supported
+    pub issued_at: Option<Timestamp>,
+    /// Credential is expired at and after this time
+    pub valid_until: Option<Timestamp>,
+    pub revocation: Option<Revocation>,
+}
+
+#[cw_serde]
+pub struct Revocation {
+    pub reason: String,
+    pub revoked_at: Timestamp,
+}
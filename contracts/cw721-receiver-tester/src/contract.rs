@@ -1,54 +1,155 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{from_json, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    from_json, to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+    WasmMsg,
+};
+use cw721::helpers::EmptyCw721Helper;
+use cw721::msg::Cw721ExecuteMsg;
+use cw721::receiver::Cw721ReceiveMsg;
+use cw721::traits::Cw721Calls;
+use cw_storage_plus::Bound;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InnerMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{
+    ExecuteMsg, InnerMsg, InstantiateMsg, QueryMsg, ReceivedNft, ReceivedNftsResponse,
+};
+use crate::state::{BEHAVIOR, GAS_SINK, RECEIVED, RECEIVED_COUNT};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    if let Some(behavior) = msg.behavior {
+        BEHAVIOR.save(deps.storage, &behavior)?;
+    }
     Ok(Response::default())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::ReceiveNft(receive_msg) => {
-            let inner: InnerMsg = from_json(&receive_msg.msg)?;
-            match inner {
-                InnerMsg::Succeed => Ok(Response::new()
-                    .add_attributes([
-                        ("action", "receive_nft"),
-                        ("token_id", receive_msg.token_id.as_str()),
-                        ("sender", receive_msg.sender.as_str()),
-                        ("msg", receive_msg.msg.to_base64().as_str()),
-                    ])
-                    .set_data(
-                        [
-                            receive_msg.token_id,
-                            receive_msg.sender,
-                            receive_msg.msg.to_base64(),
-                        ]
-                        .concat()
-                        .as_bytes(),
-                    )),
-                InnerMsg::Fail => Err(ContractError::Failed {}),
+        ExecuteMsg::ReceiveNft(receive_msg) => receive_nft(deps, info, receive_msg),
+        ExecuteMsg::SetBehavior { behavior } => {
+            match behavior {
+                Some(behavior) => BEHAVIOR.save(deps.storage, &behavior)?,
+                None => BEHAVIOR.remove(deps.storage),
             }
+            Ok(Response::new().add_attribute("action", "set_behavior"))
+        }
+    }
+}
+
+fn receive_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    receive_msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let inner = match BEHAVIOR.may_load(deps.storage)? {
+        Some(behavior) => behavior,
+        None => from_json(&receive_msg.msg)?,
+    };
+
+    // record received NFT
+    let index = RECEIVED_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    RECEIVED.save(
+        deps.storage,
+        index,
+        &ReceivedNft {
+            index,
+            contract: info.sender.clone(),
+            sender: receive_msg.sender.clone(),
+            token_id: receive_msg.token_id.clone(),
+            msg: receive_msg.msg.clone(),
+        },
+    )?;
+    RECEIVED_COUNT.save(deps.storage, &(index + 1))?;
+
+    let response = Response::new()
+        .add_attributes([
+            ("action", "receive_nft"),
+            ("token_id", receive_msg.token_id.as_str()),
+            ("sender", receive_msg.sender.as_str()),
+            ("msg", receive_msg.msg.to_base64().as_str()),
+        ])
+        .set_data(
+            [
+                receive_msg.token_id.clone(),
+                receive_msg.sender,
+                receive_msg.msg.to_base64(),
+            ]
+            .concat()
+            .as_bytes(),
+        );
+    let nft_contract = EmptyCw721Helper::new(info.sender);
+    match inner {
+        InnerMsg::Succeed => Ok(response),
+        InnerMsg::Fail => Err(ContractError::Failed {}),
+        InnerMsg::Forward { recipient, msg } => {
+            let forward_msg = match msg {
+                Some(msg) => Cw721ExecuteMsg::SendNft {
+                    contract: recipient.clone(),
+                    token_id: receive_msg.token_id,
+                    msg,
+                },
+                None => Cw721ExecuteMsg::TransferNft {
+                    recipient: recipient.clone(),
+                    token_id: receive_msg.token_id,
+                },
+            };
+            Ok(response
+                .add_message(nft_contract.call(forward_msg)?)
+                .add_attribute("forward_to", recipient))
+        }
+        InnerMsg::CallSender { msg } => Ok(response
+            .add_message(WasmMsg::Execute {
+                contract_addr: nft_contract.addr().to_string(),
+                msg,
+                funds: vec![],
+            })
+            .add_attribute("call_sender", nft_contract.addr())),
+        InnerMsg::ConsumeGas { loops } => {
+            for i in 0..u64::from(loops) {
+                let value = GAS_SINK.may_load(deps.storage)?.unwrap_or_default();
+                GAS_SINK.save(deps.storage, &value.wrapping_add(i))?;
+            }
+            Ok(response.add_attribute("gas_loops", loops.to_string()))
         }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<Binary> {
-    unimplemented!()
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::ReceivedNfts { start_after, limit } => {
+            to_json_binary(&query_received_nfts(deps, start_after, limit)?)
+        }
+        QueryMsg::Behavior {} => to_json_binary(&BEHAVIOR.may_load(deps.storage)?),
+    }
+}
+
+fn query_received_nfts(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ReceivedNftsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let nfts = RECEIVED
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, nft)| nft))
+        .collect::<StdResult<_>>()?;
+    Ok(ReceivedNftsResponse { nfts })
 }
@@ -1,23 +1,71 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Binary};
 use cw721::receiver::Cw721ReceiveMsg;
 
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    /// Behavior applied to every received NFT. If not set, the `msg` of the received NFT is
+    /// parsed as `InnerMsg`.
+    pub behavior: Option<InnerMsg>,
+}
 
 #[cw_serde]
 pub enum ExecuteMsg {
     ReceiveNft(Cw721ReceiveMsg),
+    /// Sets or unsets the behavior applied to every received NFT
+    SetBehavior {
+        behavior: Option<InnerMsg>,
+    },
 }
 
 #[cw_serde]
 pub enum InnerMsg {
     Succeed,
     Fail,
+    /// Forwards the received NFT. If `msg` is set, it is sent to the `recipient` contract with
+    /// `SendNft`, otherwise transferred with `TransferNft`.
+    Forward {
+        recipient: String,
+        msg: Option<Binary>,
+    },
+    /// Executes `msg` on the NFT contract which sent the NFT, e.g. for re-entrant calls
+    CallSender {
+        msg: Binary,
+    },
+    /// Consumes gas by reading and writing storage the given number of times
+    ConsumeGas {
+        loops: u32,
+    },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
-pub enum QueryMsg {}
+pub enum QueryMsg {
+    /// Returns all successfully received NFTs, in the order they were received
+    #[returns(ReceivedNftsResponse)]
+    ReceivedNfts {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(Option<InnerMsg>)]
+    Behavior {},
+}
+
+#[cw_serde]
+pub struct ReceivedNft {
+    /// Index of the received NFT, starting at 0
+    pub index: u64,
+    /// The NFT contract
+    pub contract: Addr,
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+#[cw_serde]
+pub struct ReceivedNftsResponse {
+    pub nfts: Vec<ReceivedNft>,
+}
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,9 @@
+use cw_storage_plus::{Item, Map};
+
+use crate::msg::{InnerMsg, ReceivedNft};
+
+pub const BEHAVIOR: Item<InnerMsg> = Item::new("behavior");
+pub const RECEIVED: Map<u64, ReceivedNft> = Map::new("received");
+pub const RECEIVED_COUNT: Item<u64> = Item::new("received_count");
+/// Scratch space for `InnerMsg::ConsumeGas`
+pub const GAS_SINK: Item<u64> = Item::new("gas_sink");
@@ -1,4 +1,5 @@
 use cosmwasm_std::{to_json_binary, Addr, Attribute, Binary};
+use cw721::msg::OwnerOfResponse;
 use cw_multi_test::{App, ContractWrapper, Executor};
 
 #[test]
@@ -98,6 +99,237 @@ fn test_cw721_base_receive_fail() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_cw721_base_receive_records_nfts() {
+    use cw721_base::msg::*;
+    use cw721_receiver_tester::msg::{InnerMsg, QueryMsg, ReceivedNft, ReceivedNftsResponse};
+
+    let mut app = App::default();
+    let admin = app.api().addr_make("admin");
+
+    let Contracts {
+        nft_contract,
+        receiver_contract,
+    } = setup_contracts(&mut app, admin.clone());
+
+    let msg = to_json_binary(&InnerMsg::Succeed).unwrap();
+    app.execute_contract(
+        admin.clone(),
+        nft_contract.clone(),
+        &ExecuteMsg::SendNft {
+            contract: receiver_contract.to_string(),
+            token_id: "test".to_string(),
+            msg: msg.clone(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let received: ReceivedNftsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &receiver_contract,
+            &QueryMsg::ReceivedNfts {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        received.nfts,
+        vec![ReceivedNft {
+            index: 0,
+            contract: nft_contract,
+            sender: admin.to_string(),
+            token_id: "test".to_string(),
+            msg,
+        }]
+    );
+    let received: ReceivedNftsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &receiver_contract,
+            &QueryMsg::ReceivedNfts {
+                start_after: Some(0),
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(received.nfts, vec![]);
+}
+
+#[test]
+fn test_cw721_base_receive_forward() {
+    use cw721_base::msg::*;
+    use cw721_receiver_tester::msg::{InnerMsg, ReceivedNftsResponse};
+
+    let mut app = App::default();
+    let admin = app.api().addr_make("admin");
+
+    let Contracts {
+        nft_contract,
+        receiver_contract,
+    } = setup_contracts(&mut app, admin.clone());
+    let other_receiver = instantiate_receiver(&mut app, admin.clone(), None);
+
+    // forward to another receiver contract, which succeeds
+    app.execute_contract(
+        admin.clone(),
+        nft_contract.clone(),
+        &ExecuteMsg::SendNft {
+            contract: receiver_contract.to_string(),
+            token_id: "test".to_string(),
+            msg: to_json_binary(&InnerMsg::Forward {
+                recipient: other_receiver.to_string(),
+                msg: Some(to_json_binary(&InnerMsg::Succeed).unwrap()),
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    let owner: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &nft_contract,
+            &QueryMsg::OwnerOf {
+                token_id: "test".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(owner.owner, other_receiver.to_string());
+    let received: ReceivedNftsResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &other_receiver,
+            &cw721_receiver_tester::msg::QueryMsg::ReceivedNfts {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(received.nfts[0].sender, receiver_contract.to_string());
+
+    // forwarding to a failing receiver reverts the whole send
+    let failing_receiver = instantiate_receiver(&mut app, admin.clone(), Some(InnerMsg::Fail));
+    app.execute_contract(
+        other_receiver.clone(),
+        nft_contract,
+        &ExecuteMsg::SendNft {
+            contract: receiver_contract.to_string(),
+            token_id: "test".to_string(),
+            msg: to_json_binary(&InnerMsg::Forward {
+                recipient: failing_receiver.to_string(),
+                msg: Some(Binary::default()),
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn test_cw721_base_receive_reentrant_call() {
+    use cw721_base::msg::*;
+    use cw721_receiver_tester::msg::InnerMsg;
+
+    let mut app = App::default();
+    let admin = app.api().addr_make("admin");
+
+    let Contracts {
+        nft_contract,
+        receiver_contract,
+    } = setup_contracts(&mut app, admin.clone());
+
+    // receiver transfers the NFT back while being notified about it
+    let transfer_back = ExecuteMsg::TransferNft {
+        recipient: admin.to_string(),
+        token_id: "test".to_string(),
+    };
+    app.execute_contract(
+        admin.clone(),
+        nft_contract.clone(),
+        &ExecuteMsg::SendNft {
+            contract: receiver_contract.to_string(),
+            token_id: "test".to_string(),
+            msg: to_json_binary(&InnerMsg::CallSender {
+                msg: to_json_binary(&transfer_back).unwrap(),
+            })
+            .unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    let owner: OwnerOfResponse = app
+        .wrap()
+        .query_wasm_smart(
+            &nft_contract,
+            &QueryMsg::OwnerOf {
+                token_id: "test".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(owner.owner, admin.to_string());
+}
+
+#[test]
+fn test_cw721_base_receive_configured_behavior() {
+    use cw721_base::msg::*;
+    use cw721_receiver_tester::msg::InnerMsg;
+
+    let mut app = App::default();
+    let admin = app.api().addr_make("admin");
+
+    let Contracts {
+        nft_contract,
+        receiver_contract,
+    } = setup_contracts(&mut app, admin.clone());
+
+    // configured behavior ignores the msg, e.g. a marketplace specific payload
+    app.execute_contract(
+        admin.clone(),
+        receiver_contract.clone(),
+        &cw721_receiver_tester::msg::ExecuteMsg::SetBehavior {
+            behavior: Some(InnerMsg::ConsumeGas { loops: 10 }),
+        },
+        &[],
+    )
+    .unwrap();
+    let behavior: Option<InnerMsg> = app
+        .wrap()
+        .query_wasm_smart(
+            &receiver_contract,
+            &cw721_receiver_tester::msg::QueryMsg::Behavior {},
+        )
+        .unwrap();
+    assert_eq!(behavior, Some(InnerMsg::ConsumeGas { loops: 10 }));
+    let response = app
+        .execute_contract(
+            admin,
+            nft_contract,
+            &ExecuteMsg::SendNft {
+                contract: receiver_contract.to_string(),
+                token_id: "test".to_string(),
+                msg: Binary::from(br#"{"list": {"price": "100"}}"#),
+            },
+            &[],
+        )
+        .unwrap();
+    let receive_event = response
+        .events
+        .iter()
+        .filter(|e| e.ty == "wasm")
+        .nth(1)
+        .unwrap();
+    assert_eq!(
+        get_attribute(&receive_event.attributes, "gas_loops"),
+        Some("10")
+    );
+}
+
 struct Contracts {
     nft_contract: Addr,
     receiver_contract: Addr,
@@ -140,7 +372,7 @@ fn setup_contracts(app: &mut App, admin: Addr) -> Contracts {
         .instantiate_contract(
             code_id,
             admin.clone(),
-            &InstantiateMsg {},
+            &InstantiateMsg { behavior: None },
             &[],
             "receiver".to_string(),
             None,
@@ -167,6 +399,27 @@ fn setup_contracts(app: &mut App, admin: Addr) -> Contracts {
     }
 }
 
+/// Setup another cw721-receiver contract
+fn instantiate_receiver(
+    app: &mut App,
+    admin: Addr,
+    behavior: Option<cw721_receiver_tester::msg::InnerMsg>,
+) -> Addr {
+    use cw721_receiver_tester::contract::*;
+    use cw721_receiver_tester::msg::*;
+
+    let code_id = app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+    app.instantiate_contract(
+        code_id,
+        admin,
+        &InstantiateMsg { behavior },
+        &[],
+        "receiver".to_string(),
+        None,
+    )
+    .unwrap()
+}
+
 fn get_attribute<'a>(attributes: &'a [Attribute], key: &str) -> Option<&'a str> {
     attributes
         .iter()
@@ -16,8 +16,11 @@ pub enum Cw721ContractError {
     #[error(transparent)]
     ParseBoolError(#[from] ParseBoolError),
 
-    #[error("Unsupoorted custom attribute type: {key}={value}")]
-    UnsupportedCustomAttributeType { key: String, value: String },
+    #[error("Invalid JSON value for custom attribute: {key}")]
+    InvalidCustomAttributeValue { key: String },
+
+    #[error("Invalid JSON object for attribute: {key}")]
+    InvalidJsonObjectAttributeValue { key: String },
 
     #[error("Missing {0} attribute")]
     AttributeMissing(String),
@@ -88,6 +91,24 @@ pub enum Cw721ContractError {
     #[error("Address is not an additional minter")]
     MinterNotFound {},
 
+    #[error("Collection attribute key must not be empty")]
+    CollectionAttributeKeyEmpty {},
+
+    #[error("Collection attribute key too long. Max length is {max_length} characters.")]
+    CollectionAttributeKeyTooLong { max_length: u32 },
+
+    #[error("Collection attribute key is reserved: {key}")]
+    CollectionAttributeKeyReserved { key: String },
+
+    #[error("Collection attribute value too large: {key}. Max size is {max_size} bytes.")]
+    CollectionAttributeValueTooLarge { key: String, max_size: u32 },
+
+    #[error("Maximum number of custom collection attributes ({max}) reached")]
+    MaxCollectionAttributesExceeded { max: u32 },
+
+    #[error("No indexes left to backfill")]
+    NoBackfillPending {},
+
     #[error("Internal error. Missing argument: Info")]
     NoInfo,
 }
@@ -3,7 +3,7 @@ use cosmwasm_std::{
     Response, StdResult, Storage,
 };
 use cw_ownable::{Action, Ownership, OwnershipError};
-use cw_storage_plus::Item;
+use cw_storage_plus::{Bound, Item};
 use cw_utils::Expiration;
 
 use crate::{
@@ -13,12 +13,17 @@ use crate::{
     },
     helpers::value_or_empty,
     legacy::ContractInfoResponse as V16ContractInfoResponse,
-    msg::{CollectionInfoMsg, Cw721InstantiateMsg, Cw721MigrateMsg, NftInfoMsg},
+    msg::{
+        AttributeMsg, AuthorizationReason, CollectionInfoMsg, Cw721InstantiateMsg, Cw721MigrateMsg,
+        NftInfoMsg,
+    },
     query::query_collection_info_and_extension,
     receiver::Cw721ReceiveMsg,
     state::{
-        CollectionInfo, Cw721Config, NftInfo, ADDITIONAL_MINTERS, CREATOR, MAX_ADDITIONAL_MINTERS,
-        MINTER,
+        CollectionInfo, CollectionStats, Cw721Config, IndexBackfill, NftInfo, ADDITIONAL_MINTERS,
+        CREATOR, MAX_ADDITIONAL_MINTERS, MAX_COLLECTION_ATTRIBUTE_KEY_LENGTH,
+        MAX_COLLECTION_ATTRIBUTE_VALUE_SIZE, MAX_COLLECTION_CUSTOM_ATTRIBUTES, MINTER,
+        RESERVED_COLLECTION_ATTRIBUTE_KEYS,
     },
     traits::{
         Cw721CustomMsg, Cw721Execute, Cw721State, FromAttributesState, StateFactory,
@@ -29,6 +34,9 @@ use crate::{
     EmptyOptionalCollectionExtensionMsg, EmptyOptionalNftExtension, EmptyOptionalNftExtensionMsg,
 };
 
+const DEFAULT_BACKFILL_LIMIT: u32 = 100;
+const MAX_BACKFILL_LIMIT: u32 = 1000;
+
 // ------- instantiate -------
 pub fn instantiate_with_version<TCollectionExtension, TCollectionExtensionMsg, TCustomResponseMsg>(
     deps: DepsMut,
@@ -133,7 +141,13 @@ where
     // ensure we have permissions
     check_can_send(deps.as_ref(), env, info.sender.as_str(), &token)?;
     // set owner and remove existing approvals
-    token.owner = deps.api.addr_validate(recipient)?;
+    let recipient = deps.api.addr_validate(recipient)?;
+    if config.is_backfilled(deps.storage, token_id)? {
+        config.decrement_balance(deps.storage, &token.owner)?;
+        config.increment_balance(deps.storage, &recipient)?;
+    }
+    config.unindex_approvals(deps.storage, token_id, &token.approvals);
+    token.owner = recipient;
     token.approvals = vec![];
     config.nft_info.save(deps.storage, token_id, &token)?;
     Ok(token)
@@ -212,6 +226,9 @@ where
     // update the approval list (remove any for the same spender before adding)
     let spender_addr = deps.api.addr_validate(spender)?;
     token.approvals.retain(|apr| apr.spender != spender_addr);
+    config
+        .spender_approvals
+        .remove(deps.storage, (&spender_addr, token_id));
 
     // only difference between approve and revoke
     if add {
@@ -224,6 +241,7 @@ where
             spender: spender_addr,
             expires,
         };
+        config.index_approvals(deps.storage, token_id, &[approval.clone()])?;
         token.approvals.push(approval);
     }
 
@@ -267,11 +285,9 @@ pub fn approve_all<TCustomResponseMsg>(
     // set the operator for us
     let operator_addr = deps.api.addr_validate(&operator)?;
     let config = Cw721Config::<Option<Empty>>::default();
-    config
-        .operators
-        // stores info.sender as key (=granter, NFT owner) and operator as value (operator only(!) has control over NFTs of granter)
-        // check is done in `check_can_send()`
-        .save(deps.storage, (&info.sender, &operator_addr), &expires)?;
+    // stores info.sender as key (=granter, NFT owner) and operator as value (operator only(!) has control over NFTs of granter)
+    // check is done in `check_can_send()`
+    config.save_operator(deps.storage, &info.sender, &operator_addr, &expires)?;
 
     Ok(Response::new()
         .add_attribute("action", "approve_all")
@@ -287,9 +303,7 @@ pub fn revoke_all<TCustomResponseMsg>(
 ) -> Result<Response<TCustomResponseMsg>, Cw721ContractError> {
     let operator_addr = deps.api.addr_validate(&operator)?;
     let config = Cw721Config::<Option<Empty>>::default();
-    config
-        .operators
-        .remove(deps.storage, (&info.sender, &operator_addr));
+    config.remove_operator(deps.storage, &info.sender, &operator_addr);
 
     Ok(Response::new()
         .add_attribute("action", "revoke_all")
@@ -307,8 +321,7 @@ pub fn burn_nft<TCustomResponseMsg>(
     let token = config.nft_info.load(deps.storage, &token_id)?;
     check_can_send(deps.as_ref(), env, info.sender.as_str(), &token)?;
 
-    config.nft_info.remove(deps.storage, &token_id)?;
-    config.decrement_tokens(deps.storage)?;
+    config.remove_token(deps.storage, &token_id, &token)?;
 
     Ok(Response::new()
         .add_attribute("action", "burn")
@@ -349,6 +362,75 @@ where
     }
 }
 
+pub fn update_collection_extension_attributes<TCustomResponseMsg>(
+    deps: DepsMut,
+    info: &MessageInfo,
+    set: Vec<AttributeMsg>,
+    remove: Vec<String>,
+) -> Result<Response<TCustomResponseMsg>, Cw721ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+    let config = Cw721Config::<Option<Empty>>::default();
+    for key in &remove {
+        assert_custom_attribute_key(key)?;
+        if !config.collection_extension.has(deps.storage, key.clone()) {
+            return Err(Cw721ContractError::AttributeMissing(key.clone()));
+        }
+        config
+            .collection_extension
+            .remove(deps.storage, key.clone());
+    }
+    for attr in &set {
+        assert_custom_attribute_key(&attr.key)?;
+        let attribute = attr.from(deps.api)?;
+        if attribute.value.len() > MAX_COLLECTION_ATTRIBUTE_VALUE_SIZE as usize {
+            return Err(Cw721ContractError::CollectionAttributeValueTooLarge {
+                key: attr.key.clone(),
+                max_size: MAX_COLLECTION_ATTRIBUTE_VALUE_SIZE,
+            });
+        }
+        config
+            .collection_extension
+            .save(deps.storage, attribute.key.clone(), &attribute)?;
+    }
+    let count = config
+        .collection_extension
+        .keys(deps.storage, None, None, Order::Ascending)
+        .filter(|key| {
+            key.as_ref().map_or(true, |key| {
+                !RESERVED_COLLECTION_ATTRIBUTE_KEYS.contains(&key.as_str())
+            })
+        })
+        .count();
+    if count > MAX_COLLECTION_CUSTOM_ATTRIBUTES as usize {
+        return Err(Cw721ContractError::MaxCollectionAttributesExceeded {
+            max: MAX_COLLECTION_CUSTOM_ATTRIBUTES,
+        });
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_collection_extension_attributes")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("set", set.len().to_string())
+        .add_attribute("removed", remove.len().to_string()))
+}
+
+fn assert_custom_attribute_key(key: &str) -> Result<(), Cw721ContractError> {
+    if key.is_empty() {
+        return Err(Cw721ContractError::CollectionAttributeKeyEmpty {});
+    }
+    if key.len() > MAX_COLLECTION_ATTRIBUTE_KEY_LENGTH as usize {
+        return Err(Cw721ContractError::CollectionAttributeKeyTooLong {
+            max_length: MAX_COLLECTION_ATTRIBUTE_KEY_LENGTH,
+        });
+    }
+    if RESERVED_COLLECTION_ATTRIBUTE_KEYS.contains(&key) {
+        return Err(Cw721ContractError::CollectionAttributeKeyReserved {
+            key: key.to_string(),
+        });
+    }
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn mint<TNftExtension, TNftExtensionMsg, TCustomResponseMsg>(
     deps: DepsMut,
@@ -372,6 +454,8 @@ where
         extension,
     };
     let token = token_msg.create(deps.as_ref(), env, info.into(), None)?;
+    let traits = token.extension.indexed_traits();
+    let token_owner = token.owner.clone();
     let config = Cw721Config::<TNftExtension>::default();
     config
         .nft_info
@@ -379,8 +463,16 @@ where
             Some(_) => Err(Cw721ContractError::Claimed {}),
             None => Ok(token),
         })?;
+    config.index_traits(deps.storage, &token_id, traits)?;
 
     config.increment_tokens(deps.storage)?;
+    if config.is_backfilled(deps.storage, &token_id)? {
+        config.increment_balance(deps.storage, &token_owner)?;
+    }
+    config.update_stats(deps.storage, |stats| {
+        stats.first_mint_at.get_or_insert(env.block.time);
+        stats.last_mint_at = Some(env.block.time);
+    })?;
 
     let mut res = Response::new()
         .add_attribute("action", "mint")
@@ -454,6 +546,7 @@ where
     };
     let updated = nft_info_msg.create(deps.as_ref(), env, info, Some(&current_nft_info))?;
     contract.nft_info.save(deps.storage, &token_id, &updated)?;
+    contract.index_traits(deps.storage, &token_id, updated.extension.indexed_traits())?;
     Ok(Response::new()
         .add_attribute("action", "update_nft_info")
         .add_attribute("token_id", token_id)
@@ -526,25 +619,26 @@ where
     TNftExtension: Cw721State,
 {
     let sender = deps.api.addr_validate(sender)?;
+    if approve_authorization(deps, env, &sender, token)?.is_authorized() {
+        Ok(())
+    } else {
+        Err(Cw721ContractError::Ownership(OwnershipError::NotOwner))
+    }
+}
+
+/// Returns why the sender may or may not approve spenders for the token.
+pub fn approve_authorization<TNftExtension>(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    token: &NftInfo<TNftExtension>,
+) -> StdResult<AuthorizationReason> {
     // owner can approve
     if token.owner == sender {
-        return Ok(());
+        return Ok(AuthorizationReason::Owner {});
     }
     // operator can approve
-    let config = Cw721Config::<TNftExtension>::default();
-    let op = config
-        .operators
-        .may_load(deps.storage, (&token.owner, &sender))?;
-    match op {
-        Some(ex) => {
-            if ex.is_expired(&env.block) {
-                Err(Cw721ContractError::Ownership(OwnershipError::NotOwner))
-            } else {
-                Ok(())
-            }
-        }
-        None => Err(Cw721ContractError::Ownership(OwnershipError::NotOwner)),
-    }
+    operator_authorization(deps, env, sender, token)
 }
 
 /// returns true if the sender can transfer ownership of the token
@@ -555,37 +649,66 @@ pub fn check_can_send<TNftExtension>(
     token: &NftInfo<TNftExtension>,
 ) -> Result<(), Cw721ContractError> {
     let sender = deps.api.addr_validate(sender)?;
+    if transfer_authorization(deps, env, &sender, token)?.is_authorized() {
+        Ok(())
+    } else {
+        Err(Cw721ContractError::Ownership(OwnershipError::NotOwner))
+    }
+}
+
+/// Returns why the sender may or may not transfer (send or burn) the token.
+pub fn transfer_authorization<TNftExtension>(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    token: &NftInfo<TNftExtension>,
+) -> StdResult<AuthorizationReason> {
     // owner can send
     if token.owner == sender {
-        return Ok(());
+        return Ok(AuthorizationReason::Owner {});
     }
 
     // any non-expired token approval can send
-    if token
-        .approvals
-        .iter()
-        .any(|apr| apr.spender == sender && !apr.is_expired(&env.block))
-    {
-        return Ok(());
+    let approval = token.approvals.iter().find(|apr| apr.spender == sender);
+    if let Some(approval) = approval {
+        if !approval.is_expired(&env.block) {
+            return Ok(AuthorizationReason::Approval {
+                expires: approval.expires,
+            });
+        }
     }
 
     // operator can send
+    match operator_authorization(deps, env, sender, token)? {
+        AuthorizationReason::NotAuthorized {} => Ok(approval.map_or(
+            AuthorizationReason::NotAuthorized {},
+            |approval| AuthorizationReason::ExpiredApproval {
+                expires: approval.expires,
+            },
+        )),
+        reason => Ok(reason),
+    }
+}
+
+fn operator_authorization<TNftExtension>(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    token: &NftInfo<TNftExtension>,
+) -> StdResult<AuthorizationReason> {
     let config = Cw721Config::<Option<Empty>>::default();
     let op = config
         .operators
         // has token owner approved/gave grant to sender for full control over owner's NFTs?
-        .may_load(deps.storage, (&token.owner, &sender))?;
-
-    match op {
-        Some(ex) => {
-            if ex.is_expired(&env.block) {
-                Err(Cw721ContractError::Ownership(OwnershipError::NotOwner))
-            } else {
-                Ok(())
-            }
+        .may_load(deps.storage, (&token.owner, sender))?;
+
+    Ok(match op {
+        Some(expires) if expires.is_expired(&env.block) => {
+            AuthorizationReason::ExpiredOperator { expires }
         }
-        None => Err(Cw721ContractError::Ownership(OwnershipError::NotOwner)),
-    }
+        Some(expires) => AuthorizationReason::Operator { expires },
+        None => AuthorizationReason::NotAuthorized {},
+    })
 }
 
 /// Checks that the sender is the primary minter (manager). Used for admin operations
@@ -658,17 +781,21 @@ pub fn assert_creator(storage: &dyn Storage, sender: &Addr) -> Result<(), Cw721C
 }
 
 // ------- migrate -------
-pub fn migrate(
+pub fn migrate<TNftExtension>(
     deps: DepsMut,
     env: Env,
     msg: Cw721MigrateMsg,
     contract_name: &str,
     contract_version: &str,
-) -> Result<Response, Cw721ContractError> {
+) -> Result<Response, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+{
     let response = Response::<Empty>::default();
     // first migrate legacy data ...
     let response = migrate_legacy_minter_and_creator(deps.storage, deps.api, &env, &msg, response)?;
     let response = migrate_legacy_collection_info(deps.storage, &env, &msg, response)?;
+    let response = migrate_indexes::<TNftExtension>(deps.storage, response)?;
     // ... then migrate
     let response = migrate_version(deps.storage, contract_name, contract_version, response)?;
     // ... and update creator and minter AFTER legacy migration
@@ -677,6 +804,124 @@ pub fn migrate(
     Ok(response)
 }
 
+/// Starts backfilling balances, holder count, approval and trait indexes for collections
+/// created before they existed, and backfills a first batch. Remaining tokens and operators are
+/// backfilled via `Cw721ExecuteMsg::BackfillIndexes`.
+/// Collection stats history is unknown, so `minted` starts at the current supply and `burned`
+/// at zero. Skipped if any balance is stored, since indexes are kept up to date from then on.
+pub fn migrate_indexes<TNftExtension>(
+    storage: &mut dyn Storage,
+    response: Response,
+) -> Result<Response, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+{
+    let config = Cw721Config::<TNftExtension>::default();
+    if config.index_backfill.exists(storage) || !config.balances.is_empty(storage) {
+        return Ok(response);
+    }
+    config.index_backfill.save(
+        storage,
+        &IndexBackfill {
+            last_token_id: None,
+            tokens_done: false,
+            last_operator: None,
+        },
+    )?;
+    if !config.stats.exists(storage) {
+        let stats = CollectionStats {
+            minted: config.token_count(storage)?,
+            ..CollectionStats::default()
+        };
+        config.stats.save(storage, &stats)?;
+    }
+    let (count, done) = backfill_indexes::<TNftExtension>(storage, None)?;
+    Ok(response
+        .add_attribute("backfilled", count.to_string())
+        .add_attribute("backfill_done", done.to_string()))
+}
+
+pub fn execute_backfill_indexes<TNftExtension, TCustomResponseMsg>(
+    deps: DepsMut,
+    limit: Option<u32>,
+) -> Result<Response<TCustomResponseMsg>, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+{
+    let (count, done) = backfill_indexes::<TNftExtension>(deps.storage, limit)?;
+    Ok(Response::new()
+        .add_attribute("action", "backfill_indexes")
+        .add_attribute("backfilled", count.to_string())
+        .add_attribute("backfill_done", done.to_string()))
+}
+
+/// Backfills balances, the `spender_approvals` and trait indexes for up to `limit` tokens, then the
+/// `operator_grants` index for up to `limit` operators. Returns the number of backfilled
+/// entries and whether all of them are backfilled.
+pub fn backfill_indexes<TNftExtension>(
+    storage: &mut dyn Storage,
+    limit: Option<u32>,
+) -> Result<(usize, bool), Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+{
+    let config = Cw721Config::<TNftExtension>::default();
+    let mut backfill = config
+        .index_backfill
+        .may_load(storage)?
+        .ok_or(Cw721ContractError::NoBackfillPending {})?;
+    let limit = limit
+        .unwrap_or(DEFAULT_BACKFILL_LIMIT)
+        .min(MAX_BACKFILL_LIMIT) as usize;
+    let mut count = 0;
+    if !backfill.tokens_done {
+        let start = backfill.last_token_id.as_deref().map(Bound::exclusive);
+        let tokens = config
+            .nft_info
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (token_id, token) in &tokens {
+            config.increment_balance(storage, &token.owner)?;
+            config.index_approvals(storage, token_id, &token.approvals)?;
+            config.index_traits(storage, token_id, token.extension.indexed_traits())?;
+        }
+        count = tokens.len();
+        backfill.tokens_done = count < limit;
+        if let Some((token_id, _)) = tokens.last() {
+            backfill.last_token_id = Some(token_id.clone());
+        }
+    }
+    let mut done = false;
+    if backfill.tokens_done {
+        let start = backfill
+            .last_operator
+            .as_ref()
+            .map(|(granter, operator)| Bound::exclusive((granter, operator)));
+        let operators = config
+            .operators
+            .range(storage, start, None, Order::Ascending)
+            .take(limit - count)
+            .collect::<StdResult<Vec<_>>>()?;
+        for ((granter, operator), expires) in &operators {
+            config
+                .operator_grants
+                .save(storage, (operator, granter), expires)?;
+        }
+        done = operators.len() < limit - count;
+        count += operators.len();
+        if let Some((key, _)) = operators.last() {
+            backfill.last_operator = Some(key.clone());
+        }
+    }
+    if done {
+        config.index_backfill.remove(storage);
+    } else {
+        config.index_backfill.save(storage, &backfill)?;
+    }
+    Ok((count, done))
+}
+
 pub fn migrate_version(
     storage: &mut dyn Storage,
     contradct_name: &str,
@@ -1,13 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, Coin, ContractInfoResponse, Decimal, Deps, Env, MessageInfo,
-    Timestamp,
+    from_json, to_json_binary, Addr, Api, Binary, Coin, ContractInfoResponse, Decimal, Deps, Env,
+    MessageInfo, Order, SignedDecimal, StdError, Timestamp, Uint128,
 };
 use cw_ownable::{Action, Ownership};
 use cw_utils::Expiration;
-use serde::Serialize;
+use serde::{de::IgnoredAny, Serialize};
 use url::Url;
 
 use crate::error::Cw721ContractError;
@@ -105,6 +106,22 @@ pub enum Cw721ExecuteMsg<
         minter: String,
     },
 
+    /// The creator is the only one eligible to add, update and remove custom attributes
+    /// of the collection extension. Keys of the default collection extension are reserved
+    /// and can only be changed via `UpdateCollectionInfo`.
+    UpdateCollectionExtensionAttributes {
+        /// Attributes to add, existing attributes with the same key are overwritten.
+        set: Vec<AttributeMsg>,
+        /// Keys of attributes to remove.
+        remove: Vec<String>,
+    },
+
+    /// Backfills indexes of collections created before they existed, in batches of `limit`
+    /// tokens. Started on migration, anyone can continue until done.
+    BackfillIndexes {
+        limit: Option<u32>,
+    },
+
     /// Custom msg execution. This is a no-op in default implementation.
     UpdateExtension {
         msg: TExtensionMsg,
@@ -197,10 +214,56 @@ pub enum Cw721QueryMsg<
         include_expired: Option<bool>,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
     },
+    /// Lists tokens `spender` is approved to transfer, ordered by token_id.
+    /// NOTE: incomplete while `BackfillIndexes` is pending after migration.
+    #[returns(ApprovedTokensResponse)]
+    ApprovedTokensFor {
+        spender: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        /// token_id
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Lists owners who granted `operator` control over all their tokens, ordered by owner.
+    /// NOTE: incomplete while `BackfillIndexes` is pending after migration.
+    #[returns(OperatorGrantsResponse)]
+    OperatorGrantsFor {
+        operator: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        /// owner
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Dry-run of transferring (or sending, burning) a token: returns whether `sender`
+    /// is authorized and why.
+    #[returns(AuthorizationResponse)]
+    CanTransfer { sender: String, token_id: String },
+    /// Dry-run of approving a spender for a token: returns whether `sender` is authorized and why.
+    #[returns(AuthorizationResponse)]
+    CanApprove { sender: String, token_id: String },
     /// Total number of tokens issued
     #[returns(NumTokensResponse)]
     NumTokens {},
+    /// Minted and burned totals, current supply, holders and mint timestamps
+    /// NOTE: `holders` is incomplete while `BackfillIndexes` is pending after migration.
+    #[returns(CollectionStatsResponse)]
+    CollectionStats {},
+    /// Number of tokens held by the given owner
+    #[returns(BalanceOfResponse)]
+    BalanceOf { owner: String },
+    /// Lists distinct owners and their number of tokens, ordered by owner address
+    #[returns(HoldersResponse)]
+    Holders {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     #[deprecated(
         since = "0.19.0",
@@ -248,6 +311,10 @@ pub enum Cw721QueryMsg<
     GetAdditionalMinters {
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
     },
 
     /// With MetaData Extension.
@@ -262,6 +329,32 @@ pub enum Cw721QueryMsg<
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Like `GetNftByExtension`, but matches include `token_id` and `owner`.
+    /// `limit` bounds the number of scanned tokens, use `next_start_after` for paging.
+    #[returns(NftsByExtensionResponse<TNftExtension>)]
+    NftsByExtension {
+        extension: TNftExtension,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Lists token_ids whose traits match `filter`.
+    /// `limit` bounds the number of scanned tokens, use `next_start_after` for paging.
+    #[returns(TokensByFilterResponse)]
+    TokensByFilter {
+        filter: TraitFilter,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Lists token_ids having the given trait, using the trait index.
+    /// Only extensions providing `Cw721State::indexed_traits` (e.g. `NftExtension`) are indexed.
+    /// NOTE: incomplete while `BackfillIndexes` is pending after migration.
+    #[returns(TokensResponse)]
+    TokensByTrait {
+        trait_type: String,
+        value: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     /// With MetaData Extension.
     /// Returns the result of both `NftInfo` and `OwnerOf` as one query as an optimization
@@ -280,6 +373,10 @@ pub enum Cw721QueryMsg<
         owner: String,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
     },
     /// With Enumerable extension.
     /// Requires pagination. Lists all token_ids controlled by the contract.
@@ -287,6 +384,29 @@ pub enum Cw721QueryMsg<
     AllTokens {
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Exclusive bound, the counterpart of `start_after`
+        end_before: Option<String>,
+        /// Ascending by default
+        order: Option<OrderBy>,
+    },
+    /// With Enumerable extension.
+    /// Returns all tokens owned by the given address including their details, [] if unset.
+    #[returns(TokensWithInfoResponse<TNftExtension>)]
+    TokensWithInfo {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+    },
+    /// With Enumerable extension.
+    /// Requires pagination. Lists all tokens controlled by the contract including their details.
+    #[returns(TokensWithInfoResponse<TNftExtension>)]
+    AllTokensWithInfo {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
     },
 
     /// Custom msg query. Default implementation returns an empty binary.
@@ -296,6 +416,10 @@ pub enum Cw721QueryMsg<
     #[returns(())]
     GetCollectionExtension { msg: TCollectionExtension },
 
+    /// Returns a single attribute of the collection extension, None if unset.
+    #[returns(Option<Attribute>)]
+    GetCollectionExtensionAttribute { key: String },
+
     #[returns(Option<String>)]
     GetWithdrawAddress {},
 }
@@ -345,26 +469,64 @@ impl AttributeMsg {
         Ok(Timestamp::from_nanos(nanos))
     }
 
-    pub fn addr_value(&self) -> Result<Addr, Cw721ContractError> {
-        Ok(Addr::unchecked(self.string_value()?))
+    pub fn addr_value(&self, api: &dyn Api) -> Result<Addr, Cw721ContractError> {
+        Ok(api.addr_validate(&self.value)?)
+    }
+
+    pub fn uint128_value(&self) -> Result<Uint128, Cw721ContractError> {
+        Ok(self.value.parse::<Uint128>()?)
+    }
+
+    /// Coin in its string representation, e.g. `100ujuno`.
+    pub fn coin_value(&self) -> Result<Coin, Cw721ContractError> {
+        Ok(Coin::from_str(&self.value).map_err(StdError::from)?)
+    }
+
+    /// JSON array of strings, e.g. `["a","b"]`.
+    pub fn vec_string_value(&self) -> Result<Vec<String>, Cw721ContractError> {
+        Ok(from_json(self.value.as_bytes())?)
+    }
+
+    pub fn url_value(&self) -> Result<Url, Cw721ContractError> {
+        Ok(Url::parse(&self.value)?)
+    }
+
+    /// Raw JSON object, stored as is once it is validated.
+    pub fn json_value(&self) -> Result<Binary, Cw721ContractError> {
+        from_json::<BTreeMap<String, IgnoredAny>>(self.value.as_bytes()).map_err(|_| {
+            Cw721ContractError::InvalidJsonObjectAttributeValue {
+                key: self.key.clone(),
+            }
+        })?;
+        Ok(Binary::from(self.value.as_bytes()))
+    }
+
+    /// Raw JSON value, stored as is once it is validated.
+    pub fn custom_value(&self) -> Result<Binary, Cw721ContractError> {
+        from_json::<IgnoredAny>(self.value.as_bytes()).map_err(|_| {
+            Cw721ContractError::InvalidCustomAttributeValue {
+                key: self.key.clone(),
+            }
+        })?;
+        Ok(Binary::from(self.value.as_bytes()))
     }
 }
 
 impl AttributeMsg {
-    pub fn from(&self) -> Result<Attribute, Cw721ContractError> {
+    pub fn from(&self, api: &dyn Api) -> Result<Attribute, Cw721ContractError> {
         let value = match self.attr_type {
             AttributeType::String => to_json_binary(&self.string_value()?)?,
             AttributeType::U64 => to_json_binary(&self.u64_value()?)?,
             AttributeType::Boolean => to_json_binary(&self.bool_value()?)?,
             AttributeType::Decimal => to_json_binary(&self.decimal_value()?)?,
             AttributeType::Timestamp => to_json_binary(&self.timestamp_value()?)?,
-            AttributeType::Addr => to_json_binary(&self.addr_value()?)?,
-            AttributeType::Custom => {
-                return Err(Cw721ContractError::UnsupportedCustomAttributeType {
-                    key: self.key.clone(),
-                    value: self.value.clone(),
-                });
-            }
+            AttributeType::Addr => to_json_binary(&self.addr_value(api)?)?,
+            AttributeType::Uint128 => to_json_binary(&self.uint128_value()?)?,
+            AttributeType::Coin => to_json_binary(&self.coin_value()?)?,
+            AttributeType::VecString => to_json_binary(&self.vec_string_value()?)?,
+            AttributeType::Url => to_json_binary(&self.url_value()?)?,
+            AttributeType::Json => self.json_value()?,
+            AttributeType::Custom => self.custom_value()?,
         };
         let attribute = Attribute {
             key: self.key.clone(),
@@ -383,6 +545,14 @@ pub enum AttributeType {
     Addr,
     Decimal,
     Custom,
+    Uint128,
+    /// Coin in its string representation, e.g. `100ujuno`.
+    Coin,
+    /// JSON array of strings.
+    VecString,
+    Url,
+    /// JSON object.
+    Json,
 }
 
 #[cw_serde]
@@ -859,16 +1029,199 @@ pub struct OperatorResponse {
     pub approval: Approval,
 }
 
+/// Iteration order of paginated queries. With `Desc`, `start_after` is the upper and
+/// `end_before` the lower bound.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum OrderBy {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl From<OrderBy> for Order {
+    fn from(order: OrderBy) -> Self {
+        match order {
+            OrderBy::Asc => Order::Ascending,
+            OrderBy::Desc => Order::Descending,
+        }
+    }
+}
+
+/// Structured filter over NFT traits (see `Cw721State::traits`).
+/// `trait_type` and `value` comparisons are case-sensitive.
+#[cw_serde]
+pub enum TraitFilter {
+    /// A trait of `trait_type` has the given value.
+    Eq { trait_type: String, value: String },
+    /// No trait of `trait_type` has the given value, negation of `Eq`.
+    Ne { trait_type: String, value: String },
+    /// A trait of `trait_type` has a value starting with `prefix`.
+    Prefix { trait_type: String, prefix: String },
+    /// A trait of `trait_type` with `display_type: "number"` has a value within
+    /// the inclusive bounds. Unset bounds are open.
+    Range {
+        trait_type: String,
+        min: Option<SignedDecimal>,
+        max: Option<SignedDecimal>,
+    },
+    /// A trait of `trait_type` has one of the given values.
+    In {
+        trait_type: String,
+        values: Vec<String>,
+    },
+    /// All filters match. Matches if empty.
+    And(Vec<TraitFilter>),
+    /// At least one filter matches. Never matches if empty.
+    Or(Vec<TraitFilter>),
+}
+
+/// `display_type` of traits evaluated by `TraitFilter::Range`.
+pub const DISPLAY_TYPE_NUMBER: &str = "number";
+
+impl TraitFilter {
+    pub fn matches(&self, traits: &[Trait]) -> bool {
+        let any = |trait_type: &str, pred: &dyn Fn(&Trait) -> bool| {
+            traits.iter().any(|t| t.trait_type == trait_type && pred(t))
+        };
+        match self {
+            TraitFilter::Eq { trait_type, value } => any(trait_type, &|t| &t.value == value),
+            TraitFilter::Ne { trait_type, value } => !any(trait_type, &|t| &t.value == value),
+            TraitFilter::Prefix { trait_type, prefix } => {
+                any(trait_type, &|t| t.value.starts_with(prefix.as_str()))
+            }
+            TraitFilter::Range {
+                trait_type,
+                min,
+                max,
+            } => any(trait_type, &|t| {
+                if t.display_type.as_deref() != Some(DISPLAY_TYPE_NUMBER) {
+                    return false;
+                }
+                match SignedDecimal::from_str(&t.value) {
+                    Ok(number) => {
+                        min.is_none_or(|min| number >= min) && max.is_none_or(|max| number <= max)
+                    }
+                    Err(_) => false,
+                }
+            }),
+            TraitFilter::In { trait_type, values } => {
+                any(trait_type, &|t| values.contains(&t.value))
+            }
+            TraitFilter::And(filters) => filters.iter().all(|f| f.matches(traits)),
+            TraitFilter::Or(filters) => filters.iter().any(|f| f.matches(traits)),
+        }
+    }
+}
+
 #[cw_serde]
 pub struct OperatorsResponse {
     pub operators: Vec<Approval>,
 }
 
+#[cw_serde]
+pub struct ApprovedToken {
+    pub token_id: String,
+    pub owner: String,
+    pub expires: Expiration,
+}
+
+#[cw_serde]
+pub struct ApprovedTokensResponse {
+    pub tokens: Vec<ApprovedToken>,
+}
+
+#[cw_serde]
+pub struct OperatorGrant {
+    pub owner: String,
+    pub expires: Expiration,
+}
+
+#[cw_serde]
+pub struct OperatorGrantsResponse {
+    pub grants: Vec<OperatorGrant>,
+}
+
+/// Why a sender is authorized, or not, to transfer or approve a token.
+#[cw_serde]
+pub enum AuthorizationReason {
+    /// Sender owns the token.
+    Owner {},
+    /// Sender has a token approval (transfer only).
+    Approval { expires: Expiration },
+    /// Sender is an operator of the token owner.
+    Operator { expires: Expiration },
+    /// Sender's token approval has expired.
+    ExpiredApproval { expires: Expiration },
+    /// Sender's operator grant has expired.
+    ExpiredOperator { expires: Expiration },
+    /// Sender is neither owner, approved spender nor operator.
+    NotAuthorized {},
+    /// The token can't be transferred or approved at all, e.g. soulbound tokens.
+    Soulbound {},
+}
+
+impl AuthorizationReason {
+    pub fn is_authorized(&self) -> bool {
+        matches!(
+            self,
+            AuthorizationReason::Owner {}
+                | AuthorizationReason::Approval { .. }
+                | AuthorizationReason::Operator { .. }
+        )
+    }
+}
+
+#[cw_serde]
+pub struct AuthorizationResponse {
+    pub authorized: bool,
+    pub reason: AuthorizationReason,
+}
+
+impl From<AuthorizationReason> for AuthorizationResponse {
+    fn from(reason: AuthorizationReason) -> Self {
+        AuthorizationResponse {
+            authorized: reason.is_authorized(),
+            reason,
+        }
+    }
+}
+
 #[cw_serde]
 pub struct NumTokensResponse {
     pub count: u64,
 }
 
+#[cw_serde]
+pub struct CollectionStatsResponse {
+    /// Total number of tokens ever minted
+    pub minted: u64,
+    /// Total number of tokens ever burned
+    pub burned: u64,
+    /// Current number of tokens, same as `NumTokens`
+    pub supply: u64,
+    /// Number of distinct owners holding at least one token
+    pub holders: u64,
+    pub first_mint_at: Option<Timestamp>,
+    pub last_mint_at: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct BalanceOfResponse {
+    pub balance: u64,
+}
+
+#[cw_serde]
+pub struct Holder {
+    pub owner: String,
+    pub balance: u64,
+}
+
+#[cw_serde]
+pub struct HoldersResponse {
+    pub holders: Vec<Holder>,
+}
+
 #[cw_serde]
 pub struct NftInfoResponse<TNftExtension> {
     /// Universal resource identifier for this NFT
@@ -895,6 +1248,49 @@ pub struct TokensResponse {
     pub tokens: Vec<String>,
 }
 
+#[cw_serde]
+pub struct TokenWithInfo<TNftExtension> {
+    pub token_id: String,
+    pub owner: String,
+    pub token_uri: Option<String>,
+    pub extension: TNftExtension,
+    pub approvals: Vec<Approval>,
+}
+
+#[cw_serde]
+pub struct TokensWithInfoResponse<TNftExtension> {
+    /// Contains all tokens in lexicographical ordering of their token_id
+    /// If there are more than `limit`, use the last `token_id` as `start_after` in future queries
+    /// to achieve pagination.
+    pub tokens: Vec<TokenWithInfo<TNftExtension>>,
+}
+
+#[cw_serde]
+pub struct TokensByFilterResponse {
+    /// Matching token_ids in lexicographical ordering
+    pub tokens: Vec<String>,
+    /// Last scanned token_id, to be used as `start_after` in the next query.
+    /// None once all tokens have been scanned.
+    pub next_start_after: Option<String>,
+}
+
+#[cw_serde]
+pub struct NftByExtension<TNftExtension> {
+    pub token_id: String,
+    pub owner: String,
+    pub token_uri: Option<String>,
+    pub extension: TNftExtension,
+}
+
+#[cw_serde]
+pub struct NftsByExtensionResponse<TNftExtension> {
+    /// Matching NFTs in lexicographical ordering of their token_id
+    pub nfts: Vec<NftByExtension<TNftExtension>>,
+    /// Last scanned token_id, to be used as `start_after` in the next query.
+    /// None once all tokens have been scanned.
+    pub next_start_after: Option<String>,
+}
+
 /// Deprecated: use Cw721QueryMsg::GetMinterOwnership instead!
 /// Shows who can mint these tokens.
 #[cw_serde]
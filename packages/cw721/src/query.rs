@@ -2,22 +2,26 @@ use cosmwasm_std::{
     Addr, BlockInfo, CustomMsg, Deps, Empty, Env, Order, StdError, StdResult, Storage,
 };
 use cw_ownable::Ownership;
-use cw_storage_plus::Bound;
+use cw_storage_plus::{Bound, PrimaryKey};
 use cw_utils::{maybe_addr, Expiration};
 
 use crate::{
     error::Cw721ContractError,
+    execute::{approve_authorization, transfer_authorization},
     extension::{
         Cw721BaseExtensions, Cw721EmptyExtensions, Cw721Extensions, Cw721OnchainExtensions,
     },
     msg::{
         AdditionalMintersResponse, AllInfoResponse, AllNftInfoResponse, ApprovalResponse,
-        ApprovalsResponse, CollectionInfoAndExtensionResponse, ConfigResponse, MinterResponse,
-        NftInfoResponse, NumTokensResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse,
-        TokensResponse,
+        ApprovalsResponse, ApprovedToken, ApprovedTokensResponse, AuthorizationResponse,
+        BalanceOfResponse, CollectionInfoAndExtensionResponse, CollectionStatsResponse,
+        ConfigResponse, Holder, HoldersResponse, MinterResponse, NftByExtension, NftInfoResponse,
+        NftsByExtensionResponse, NumTokensResponse, OperatorGrant, OperatorGrantsResponse,
+        OperatorResponse, OperatorsResponse, OrderBy, OwnerOfResponse, TokenWithInfo,
+        TokensByFilterResponse, TokensResponse, TokensWithInfoResponse, TraitFilter,
     },
     state::{
-        Approval, CollectionExtensionAttributes, CollectionInfo, Cw721Config, NftInfo,
+        Approval, Attribute, CollectionExtensionAttributes, CollectionInfo, Cw721Config, NftInfo,
         ADDITIONAL_MINTERS, CREATOR, MINTER,
     },
     traits::{Contains, Cw721CustomMsg, Cw721Query, Cw721State, FromAttributesState},
@@ -32,6 +36,23 @@ pub fn parse_approval(item: StdResult<(Addr, Expiration)>) -> StdResult<Approval
     item.map(|(spender, expires)| Approval { spender, expires })
 }
 
+/// Returns min and max range bounds and the iteration order, for exclusive `start_after` and
+/// `end_before` bounds given in iteration order.
+pub fn ordered_bounds<'a, K>(
+    start_after: Option<Bound<'a, K>>,
+    end_before: Option<Bound<'a, K>>,
+    order: Option<OrderBy>,
+) -> (Option<Bound<'a, K>>, Option<Bound<'a, K>>, Order)
+where
+    K: PrimaryKey<'a>,
+{
+    let order = order.unwrap_or_default();
+    match order {
+        OrderBy::Asc => (start_after, end_before, order.into()),
+        OrderBy::Desc => (end_before, start_after, order.into()),
+    }
+}
+
 pub fn humanize_approvals<TNftExtension>(
     block: &BlockInfo,
     nft_info: &NftInfo<TNftExtension>,
@@ -79,12 +100,19 @@ pub fn query_additional_minters(
     deps: Deps,
     start_after: Option<String>,
     limit: Option<u32>,
+    end_before: Option<String>,
+    order: Option<OrderBy>,
 ) -> StdResult<AdditionalMintersResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start_addr = maybe_addr(deps.api, start_after)?;
-    let start = start_addr.as_ref().map(Bound::exclusive);
+    let end_addr = maybe_addr(deps.api, end_before)?;
+    let (min, max, order) = ordered_bounds(
+        start_addr.as_ref().map(Bound::exclusive),
+        end_addr.as_ref().map(Bound::exclusive),
+        order,
+    );
     let minters = ADDITIONAL_MINTERS
-        .keys(deps.storage, start, None, Order::Ascending)
+        .keys(deps.storage, min, max, order)
         .take(limit)
         .map(|item| item.map(|addr| addr.to_string()))
         .collect::<StdResult<Vec<_>>>()?;
@@ -107,6 +135,14 @@ pub fn query_collection_extension_attributes(
         .collect()
 }
 
+pub fn query_collection_extension_attribute(
+    deps: Deps,
+    key: String,
+) -> StdResult<Option<Attribute>> {
+    let config = Cw721Config::<Option<Empty>>::default();
+    config.collection_extension.may_load(deps.storage, key)
+}
+
 pub fn query_config<TCollectionExtension>(
     deps: Deps,
     contract_addr: impl Into<String>,
@@ -169,6 +205,64 @@ pub fn query_num_tokens(storage: &dyn Storage) -> StdResult<NumTokensResponse> {
     Ok(NumTokensResponse { count })
 }
 
+pub fn query_collection_stats(storage: &dyn Storage) -> StdResult<CollectionStatsResponse> {
+    let config = Cw721Config::<Option<Empty>>::default();
+    let stats = config.collection_stats(storage)?;
+    Ok(CollectionStatsResponse {
+        minted: stats.minted,
+        burned: stats.burned,
+        supply: config.token_count(storage)?,
+        holders: stats.holders,
+        first_mint_at: stats.first_mint_at,
+        last_mint_at: stats.last_mint_at,
+    })
+}
+
+pub fn query_balance_of(deps: Deps, owner: String) -> StdResult<BalanceOfResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let config = Cw721Config::<Option<Empty>>::default();
+    let mut balance = config.balance(deps.storage, &owner)?;
+    // tokens not backfilled yet after migration are counted via the owner index
+    if let Some(backfill) = config
+        .index_backfill
+        .may_load(deps.storage)?
+        .filter(|backfill| !backfill.tokens_done)
+    {
+        let start = backfill.last_token_id.map(Bound::exclusive);
+        balance += config
+            .nft_info
+            .idx
+            .owner
+            .prefix(owner)
+            .keys(deps.storage, start, None, Order::Ascending)
+            .count() as u64;
+    }
+    Ok(BalanceOfResponse { balance })
+}
+
+pub fn query_holders(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<HoldersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let holders = Cw721Config::<Option<Empty>>::default()
+        .balances
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(owner, balance)| Holder {
+                owner: owner.to_string(),
+                balance,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(HoldersResponse { holders })
+}
+
 pub fn query_nft_info<TNftExtension>(
     storage: &dyn Storage,
     token_id: String,
@@ -226,6 +320,79 @@ where
     }
 }
 
+pub fn query_nfts_by_extension<TNftExtension>(
+    storage: &dyn Storage,
+    extension: TNftExtension,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<NftsByExtensionResponse<TNftExtension>>
+where
+    TNftExtension: Cw721State + Contains,
+{
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let scanned = Cw721Config::<TNftExtension>::default()
+        .nft_info
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let next_start_after = if scanned.len() < limit {
+        None
+    } else {
+        scanned.last().map(|(token_id, _)| token_id.clone())
+    };
+    let nfts = scanned
+        .into_iter()
+        .filter(|(_, nft)| nft.extension.contains(&extension))
+        .map(|(token_id, nft)| NftByExtension {
+            token_id,
+            owner: nft.owner.to_string(),
+            token_uri: nft.token_uri,
+            extension: nft.extension,
+        })
+        .collect();
+
+    Ok(NftsByExtensionResponse {
+        nfts,
+        next_start_after,
+    })
+}
+
+pub fn query_tokens_by_filter<TNftExtension>(
+    storage: &dyn Storage,
+    filter: TraitFilter,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensByFilterResponse>
+where
+    TNftExtension: Cw721State,
+{
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let scanned = Cw721Config::<TNftExtension>::default()
+        .nft_info
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let next_start_after = if scanned.len() < limit {
+        None
+    } else {
+        scanned.last().map(|(token_id, _)| token_id.clone())
+    };
+    let tokens = scanned
+        .into_iter()
+        .filter(|(_, nft)| filter.matches(&nft.extension.traits()))
+        .map(|(token_id, _)| token_id)
+        .collect();
+
+    Ok(TokensByFilterResponse {
+        tokens,
+        next_start_after,
+    })
+}
+
 pub fn query_owner_of(
     deps: Deps,
     env: &Env,
@@ -273,6 +440,7 @@ pub fn query_operator(
 }
 
 /// operators returns all operators owner given access to
+#[allow(clippy::too_many_arguments)]
 pub fn query_operators(
     deps: Deps,
     env: &Env,
@@ -280,16 +448,23 @@ pub fn query_operators(
     include_expired_approval: bool,
     start_after: Option<String>,
     limit: Option<u32>,
+    end_before: Option<String>,
+    order: Option<OrderBy>,
 ) -> StdResult<OperatorsResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start_addr = maybe_addr(deps.api, start_after)?;
-    let start = start_addr.as_ref().map(Bound::exclusive);
+    let end_addr = maybe_addr(deps.api, end_before)?;
+    let (min, max, order) = ordered_bounds(
+        start_addr.as_ref().map(Bound::exclusive),
+        end_addr.as_ref().map(Bound::exclusive),
+        order,
+    );
 
     let owner_addr = deps.api.addr_validate(&owner)?;
     let res: StdResult<Vec<_>> = Cw721Config::<Option<Empty>>::default()
         .operators
         .prefix(&owner_addr)
-        .range(deps.storage, start, None, Order::Ascending)
+        .range(deps.storage, min, max, order)
         .filter(|r| {
             include_expired_approval || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block)
         })
@@ -299,6 +474,99 @@ pub fn query_operators(
     Ok(OperatorsResponse { operators: res? })
 }
 
+/// approved_tokens_for returns all tokens spender is approved to transfer
+pub fn query_approved_tokens_for(
+    deps: Deps,
+    env: &Env,
+    spender: String,
+    include_expired_approval: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ApprovedTokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    let config = Cw721Config::<Option<Empty>>::default();
+    let tokens = config
+        .spender_approvals
+        .prefix(&spender_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|r| {
+            include_expired_approval || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block)
+        })
+        .take(limit)
+        .map(|item| {
+            let (token_id, expires) = item?;
+            let owner = config.nft_info.load(deps.storage, &token_id)?.owner;
+            Ok(ApprovedToken {
+                token_id,
+                owner: owner.to_string(),
+                expires,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ApprovedTokensResponse { tokens })
+}
+
+/// operator_grants_for returns all owners who gave operator access to their tokens
+pub fn query_operator_grants_for(
+    deps: Deps,
+    env: &Env,
+    operator: String,
+    include_expired_approval: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OperatorGrantsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_addr = maybe_addr(deps.api, start_after)?;
+    let start = start_addr.as_ref().map(Bound::exclusive);
+
+    let operator_addr = deps.api.addr_validate(&operator)?;
+    let grants = Cw721Config::<Option<Empty>>::default()
+        .operator_grants
+        .prefix(&operator_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|r| {
+            include_expired_approval || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block)
+        })
+        .take(limit)
+        .map(|item| {
+            item.map(|(owner, expires)| OperatorGrant {
+                owner: owner.to_string(),
+                expires,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(OperatorGrantsResponse { grants })
+}
+
+pub fn query_can_transfer(
+    deps: Deps,
+    env: &Env,
+    sender: String,
+    token_id: String,
+) -> StdResult<AuthorizationResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+    let token = Cw721Config::<Option<Empty>>::default()
+        .nft_info
+        .load(deps.storage, &token_id)?;
+    Ok(transfer_authorization(deps, env, &sender, &token)?.into())
+}
+
+pub fn query_can_approve(
+    deps: Deps,
+    env: &Env,
+    sender: String,
+    token_id: String,
+) -> StdResult<AuthorizationResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+    let token = Cw721Config::<Option<Empty>>::default()
+        .nft_info
+        .load(deps.storage, &token_id)?;
+    Ok(approve_authorization(deps, env, &sender, &token)?.into())
+}
+
 pub fn query_approval(
     deps: Deps,
     env: &Env,
@@ -368,9 +636,15 @@ pub fn query_tokens(
     owner: String,
     start_after: Option<String>,
     limit: Option<u32>,
+    end_before: Option<String>,
+    order: Option<OrderBy>,
 ) -> StdResult<TokensResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+    let (min, max, order) = ordered_bounds(
+        start_after.map(|s| Bound::ExclusiveRaw(s.into())),
+        end_before.map(|s| Bound::ExclusiveRaw(s.into())),
+        order,
+    );
 
     let owner_addr = deps.api.addr_validate(&owner)?;
     let tokens: Vec<String> = Cw721Config::<Option<Empty>>::default()
@@ -378,7 +652,7 @@ pub fn query_tokens(
         .idx
         .owner
         .prefix(owner_addr)
-        .keys(deps.storage, start, None, Order::Ascending)
+        .keys(deps.storage, min, max, order)
         .take(limit)
         .collect::<StdResult<Vec<_>>>()?;
 
@@ -390,13 +664,19 @@ pub fn query_all_tokens(
     _env: &Env,
     start_after: Option<String>,
     limit: Option<u32>,
+    end_before: Option<String>,
+    order: Option<OrderBy>,
 ) -> StdResult<TokensResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+    let (min, max, order) = ordered_bounds(
+        start_after.map(|s| Bound::ExclusiveRaw(s.into())),
+        end_before.map(|s| Bound::ExclusiveRaw(s.into())),
+        order,
+    );
 
     let tokens = Cw721Config::<Option<Empty>>::default()
         .nft_info
-        .range(deps.storage, start, None, Order::Ascending)
+        .range(deps.storage, min, max, order)
         .take(limit)
         .map(|item| item.map(|(k, _)| k))
         .collect::<StdResult<Vec<_>>>()?;
@@ -404,6 +684,104 @@ pub fn query_all_tokens(
     Ok(TokensResponse { tokens })
 }
 
+pub fn query_tokens_with_info<TNftExtension>(
+    deps: Deps,
+    env: &Env,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    include_expired_approval: bool,
+) -> StdResult<TokensWithInfoResponse<TNftExtension>>
+where
+    TNftExtension: Cw721State,
+{
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let tokens = Cw721Config::<TNftExtension>::default()
+        .nft_info
+        .idx
+        .owner
+        .prefix(owner_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(token_id, nft_info)| {
+                token_with_info(&env.block, token_id, nft_info, include_expired_approval)
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(TokensWithInfoResponse { tokens })
+}
+
+pub fn query_tokens_by_trait(
+    deps: Deps,
+    _env: &Env,
+    trait_type: String,
+    value: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let tokens = Cw721Config::<Option<Empty>>::default()
+        .nft_traits
+        .prefix((&trait_type, &value))
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(TokensResponse { tokens })
+}
+
+pub fn query_all_tokens_with_info<TNftExtension>(
+    deps: Deps,
+    env: &Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    include_expired_approval: bool,
+) -> StdResult<TokensWithInfoResponse<TNftExtension>>
+where
+    TNftExtension: Cw721State,
+{
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let tokens = Cw721Config::<TNftExtension>::default()
+        .nft_info
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(token_id, nft_info)| {
+                token_with_info(&env.block, token_id, nft_info, include_expired_approval)
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(TokensWithInfoResponse { tokens })
+}
+
+fn token_with_info<TNftExtension>(
+    block: &BlockInfo,
+    token_id: String,
+    nft_info: NftInfo<TNftExtension>,
+    include_expired_approval: bool,
+) -> TokenWithInfo<TNftExtension>
+where
+    TNftExtension: Cw721State,
+{
+    TokenWithInfo {
+        token_id,
+        owner: nft_info.owner.to_string(),
+        approvals: humanize_approvals(block, &nft_info, include_expired_approval),
+        token_uri: nft_info.token_uri,
+        extension: nft_info.extension,
+    }
+}
+
 pub fn query_all_nft_info<TNftExtension>(
     deps: Deps,
     env: &Env,
@@ -35,6 +35,12 @@ pub const MAX_ADDITIONAL_MINTERS: u32 = 10;
 
 /// Maximum length of the description field in the collection info.
 pub const MAX_COLLECTION_DESCRIPTION_LENGTH: u32 = 512;
+/// Maximum number of custom attributes in the collection extension.
+pub const MAX_COLLECTION_CUSTOM_ATTRIBUTES: u32 = 32;
+/// Maximum length of a custom collection attribute key.
+pub const MAX_COLLECTION_ATTRIBUTE_KEY_LENGTH: u32 = 64;
+/// Maximum size in bytes of a custom collection attribute value (JSON encoded).
+pub const MAX_COLLECTION_ATTRIBUTE_VALUE_SIZE: u32 = 1024;
 /// Max increase/decrease of royalty share percentage.
 pub const MAX_ROYALTY_SHARE_DELTA_PCT: u64 = 2;
 /// Max royalty share percentage.
@@ -47,6 +53,16 @@ pub const ATTRIBUTE_BANNER_URL: &str = "banner_url";
 pub const ATTRIBUTE_EXPLICIT_CONTENT: &str = "explicit_content";
 pub const ATTRIBUTE_START_TRADING_TIME: &str = "start_trading_time";
 pub const ATTRIBUTE_ROYALTY_INFO: &str = "royalty_info";
+/// Keys of the default collection extension, these can't be used for custom attributes.
+pub const RESERVED_COLLECTION_ATTRIBUTE_KEYS: [&str; 7] = [
+    ATTRIBUTE_DESCRIPTION,
+    ATTRIBUTE_IMAGE,
+    ATTRIBUTE_EXTERNAL_LINK,
+    ATTRIBUTE_BANNER_URL,
+    ATTRIBUTE_EXPLICIT_CONTENT,
+    ATTRIBUTE_START_TRADING_TIME,
+    ATTRIBUTE_ROYALTY_INFO,
+];
 // ----------------------
 
 pub struct Cw721Config<
@@ -60,11 +76,26 @@ pub struct Cw721Config<
     pub collection_info: Item<CollectionInfo>,
     pub collection_extension: Map<String, Attribute>,
     pub num_tokens: Item<u64>,
+    /// Counters maintained on mint, burn and transfer, see `CollectionStats`.
+    pub stats: Item<CollectionStats>,
     /// Stored as (granter, operator) giving operator full control over granter's account.
     /// NOTE: granter is the owner, so operator has only control for NFTs owned by granter!
     pub operators: Map<(&'a Addr, &'a Addr), Expiration>,
+    /// Reverse index of `operators`, stored as (operator, granter).
+    pub operator_grants: Map<(&'a Addr, &'a Addr), Expiration>,
     pub nft_info: IndexedMap<&'a str, NftInfo<TNftExtension>, TokenIndexes<'a, TNftExtension>>,
+    /// Trait index stored as (trait_type, value, token_id), see `Cw721State::indexed_traits`.
+    pub nft_traits: Map<(&'a str, &'a str, &'a str), Empty>,
+    /// Reverse lookup of `nft_traits` by token_id, so entries can be removed without
+    /// knowing the NFT extension type (e.g. on burn).
+    pub nft_trait_keys: Map<&'a str, Vec<(String, String)>>,
+    /// Number of tokens held by an owner. Owners without tokens have no entry.
+    pub balances: Map<&'a Addr, u64>,
+    /// Reverse index of `NftInfo.approvals`, stored as (spender, token_id).
+    pub spender_approvals: Map<(&'a Addr, &'a str), Expiration>,
     pub withdraw_address: Item<String>,
+    /// Set while indexes of collections created before they existed are backfilled.
+    pub index_backfill: Item<IndexBackfill>,
 }
 
 impl<TNftExtension> Default for Cw721Config<'static, TNftExtension>
@@ -77,10 +108,17 @@ where
             "cw721_collection_info", // replaces deprecated/legacy key "nft_info"
             "cw721_collection_info_extension",
             "num_tokens",
+            "collection_stats",
             "operators",
+            "operators__operator",
             "tokens",
             "tokens__owner",
+            "tokens__traits",
+            "tokens__trait_keys",
+            "owner_balances",
+            "tokens__spender",
             "withdraw_address",
+            "index_backfill",
         )
     }
 }
@@ -89,14 +127,22 @@ impl<TNftExtension> Cw721Config<'_, TNftExtension>
 where
     TNftExtension: Cw721State,
 {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         collection_info_key: &'static str,
         collection_info_extension_key: &'static str,
         num_tokens_key: &'static str,
+        stats_key: &'static str,
         operator_key: &'static str,
+        operator_grants_key: &'static str,
         nft_info_key: &'static str,
         nft_info_owner_key: &'static str,
+        nft_traits_key: &'static str,
+        nft_trait_keys_key: &'static str,
+        balances_key: &'static str,
+        spender_approvals_key: &'static str,
         withdraw_address_key: &'static str,
+        index_backfill_key: &'static str,
     ) -> Self {
         let indexes = TokenIndexes {
             owner: MultiIndex::new(token_owner_idx, nft_info_key, nft_info_owner_key),
@@ -104,9 +150,16 @@ where
         Self {
             collection_info: Item::new(collection_info_key),
             num_tokens: Item::new(num_tokens_key),
+            stats: Item::new(stats_key),
             operators: Map::new(operator_key),
+            operator_grants: Map::new(operator_grants_key),
             nft_info: IndexedMap::new(nft_info_key, indexes),
+            nft_traits: Map::new(nft_traits_key),
+            nft_trait_keys: Map::new(nft_trait_keys_key),
+            balances: Map::new(balances_key),
+            spender_approvals: Map::new(spender_approvals_key),
             withdraw_address: Item::new(withdraw_address_key),
+            index_backfill: Item::new(index_backfill_key),
             collection_extension: Map::new(collection_info_extension_key),
         }
     }
@@ -118,14 +171,173 @@ where
     pub fn increment_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
         let val = self.token_count(storage)? + 1;
         self.num_tokens.save(storage, &val)?;
+        self.update_stats(storage, |stats| stats.minted += 1)?;
         Ok(val)
     }
 
     pub fn decrement_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
         let val = self.token_count(storage)? - 1;
         self.num_tokens.save(storage, &val)?;
+        self.update_stats(storage, |stats| stats.burned += 1)?;
         Ok(val)
     }
+
+    pub fn collection_stats(&self, storage: &dyn Storage) -> StdResult<CollectionStats> {
+        Ok(self.stats.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn update_stats(
+        &self,
+        storage: &mut dyn Storage,
+        action: impl FnOnce(&mut CollectionStats),
+    ) -> StdResult<()> {
+        let mut stats = self.collection_stats(storage)?;
+        action(&mut stats);
+        self.stats.save(storage, &stats)
+    }
+
+    /// Whether a token is covered by balances. While a backfill is pending, tokens after its
+    /// cursor are left to the backfill, so they aren't counted twice.
+    pub fn is_backfilled(&self, storage: &dyn Storage, token_id: &str) -> StdResult<bool> {
+        Ok(match self.index_backfill.may_load(storage)? {
+            None => true,
+            Some(backfill) => {
+                backfill.tokens_done
+                    || backfill
+                        .last_token_id
+                        .is_some_and(|last_token_id| token_id <= last_token_id.as_str())
+            }
+        })
+    }
+
+    pub fn balance(&self, storage: &dyn Storage, owner: &Addr) -> StdResult<u64> {
+        Ok(self.balances.may_load(storage, owner)?.unwrap_or_default())
+    }
+
+    pub fn increment_balance(&self, storage: &mut dyn Storage, owner: &Addr) -> StdResult<u64> {
+        let val = self.balance(storage, owner)? + 1;
+        self.balances.save(storage, owner, &val)?;
+        if val == 1 {
+            self.update_stats(storage, |stats| stats.holders += 1)?;
+        }
+        Ok(val)
+    }
+
+    pub fn decrement_balance(&self, storage: &mut dyn Storage, owner: &Addr) -> StdResult<u64> {
+        let current = self.balance(storage, owner)?;
+        let val = current.saturating_sub(1);
+        if current == 1 {
+            // saturating, stats of upgraded contracts may not be backfilled yet
+            self.update_stats(storage, |stats| {
+                stats.holders = stats.holders.saturating_sub(1)
+            })?;
+        }
+        if val == 0 {
+            self.balances.remove(storage, owner);
+        } else {
+            self.balances.save(storage, owner, &val)?;
+        }
+        Ok(val)
+    }
+
+    /// Grants `operator` control over all NFTs of `granter`, see `operators`.
+    pub fn save_operator(
+        &self,
+        storage: &mut dyn Storage,
+        granter: &Addr,
+        operator: &Addr,
+        expires: &Expiration,
+    ) -> StdResult<()> {
+        self.operators.save(storage, (granter, operator), expires)?;
+        self.operator_grants
+            .save(storage, (operator, granter), expires)
+    }
+
+    pub fn remove_operator(&self, storage: &mut dyn Storage, granter: &Addr, operator: &Addr) {
+        self.operators.remove(storage, (granter, operator));
+        self.operator_grants.remove(storage, (operator, granter));
+    }
+
+    /// Adds `approvals` of a token to the `spender_approvals` index.
+    pub fn index_approvals(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        approvals: &[Approval],
+    ) -> StdResult<()> {
+        for approval in approvals {
+            self.spender_approvals.save(
+                storage,
+                (&approval.spender, token_id),
+                &approval.expires,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Removes `approvals` of a token from the `spender_approvals` index.
+    pub fn unindex_approvals(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        approvals: &[Approval],
+    ) {
+        for approval in approvals {
+            self.spender_approvals
+                .remove(storage, (&approval.spender, token_id));
+        }
+    }
+
+    /// Replaces the trait index entries of a token with the given `(trait_type, value)` pairs.
+    pub fn index_traits(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        mut traits: Vec<(String, String)>,
+    ) -> StdResult<()> {
+        self.unindex_traits(storage, token_id)?;
+        traits.sort();
+        traits.dedup();
+        if traits.is_empty() {
+            return Ok(());
+        }
+        for (trait_type, value) in &traits {
+            self.nft_traits
+                .save(storage, (trait_type, value, token_id), &Empty {})?;
+        }
+        self.nft_trait_keys.save(storage, token_id, &traits)
+    }
+
+    /// Removes all trait index entries of a token.
+    pub fn unindex_traits(&self, storage: &mut dyn Storage, token_id: &str) -> StdResult<()> {
+        let traits = self
+            .nft_trait_keys
+            .may_load(storage, token_id)?
+            .unwrap_or_default();
+        for (trait_type, value) in &traits {
+            self.nft_traits
+                .remove(storage, (trait_type, value, token_id));
+        }
+        self.nft_trait_keys.remove(storage, token_id);
+        Ok(())
+    }
+
+    /// Removes a burned token, including its index entries, and updates balance and counters.
+    pub fn remove_token(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        token: &NftInfo<TNftExtension>,
+    ) -> StdResult<()> {
+        self.nft_info.remove(storage, token_id)?;
+        self.unindex_traits(storage, token_id)?;
+        self.unindex_approvals(storage, token_id, &token.approvals);
+        if self.is_backfilled(storage, token_id)? {
+            self.decrement_balance(storage, &token.owner)?;
+        }
+        self.decrement_tokens(storage)?;
+        Ok(())
+    }
 }
 
 pub fn token_owner_idx<TNftExtension>(_pk: &[u8], d: &NftInfo<TNftExtension>) -> Addr {
@@ -181,6 +393,31 @@ where
     }
 }
 
+/// Progress of backfilling indexes for collections created before they existed.
+#[cw_serde]
+pub struct IndexBackfill {
+    /// Last backfilled token, for pagination
+    pub last_token_id: Option<String>,
+    /// Tokens are backfilled first, then operators
+    pub tokens_done: bool,
+    /// Last backfilled (granter, operator), for pagination
+    pub last_operator: Option<(Addr, Addr)>,
+}
+
+/// Collection counters, current supply is `Cw721Config::num_tokens`.
+#[cw_serde]
+#[derive(Default)]
+pub struct CollectionStats {
+    /// Total number of tokens ever minted
+    pub minted: u64,
+    /// Total number of tokens ever burned
+    pub burned: u64,
+    /// Number of distinct owners holding at least one token
+    pub holders: u64,
+    pub first_mint_at: Option<Timestamp>,
+    pub last_mint_at: Option<Timestamp>,
+}
+
 #[cw_serde]
 pub struct CollectionInfo {
     pub name: String,
@@ -277,7 +514,11 @@ pub struct NftExtension {
     pub youtube_url: Option<String>,
 }
 
-impl Cw721State for NftExtension {}
+impl Cw721State for NftExtension {
+    fn traits(&self) -> Vec<Trait> {
+        self.attributes.clone().unwrap_or_default()
+    }
+}
 
 impl From<NftExtensionMsg> for NftExtension {
     fn from(msg: NftExtensionMsg) -> Self {
@@ -336,10 +577,9 @@ impl Contains for NftExtension {
             return false;
         }
         if let (Some(a), Some(b)) = (&self.attributes, &other.attributes) {
-            for (i, b) in b.iter().enumerate() {
-                if !a[i].eq(b) {
-                    return false;
-                }
+            // order independent, each trait of `other` must be present
+            if !b.iter().all(|b| a.contains(b)) {
+                return false;
             }
         }
         true
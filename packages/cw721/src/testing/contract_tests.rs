@@ -1,21 +1,28 @@
+use std::str::FromStr;
+
 use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
 
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Coin, CosmosMsg, DepsMut, Empty, MessageInfo, Response,
-    StdError, Timestamp, WasmMsg,
+    from_json, to_json_binary, Addr, Coin, CosmosMsg, Deps, DepsMut, Empty, MessageInfo, Response,
+    SignedDecimal, StdError, Timestamp, Uint128, WasmMsg,
 };
 
 use crate::error::Cw721ContractError;
 use crate::extension::Cw721OnchainExtensions;
 use crate::msg::{
-    ApprovalResponse, CollectionExtensionMsg, NftExtensionMsg, NftInfoResponse, OperatorResponse,
-    OperatorsResponse, OwnerOfResponse, RoyaltyInfoResponse,
+    ApprovalResponse, ApprovedToken, AttributeMsg, AttributeType, AuthorizationReason,
+    AuthorizationResponse, CollectionExtensionMsg, CollectionStatsResponse, Holder,
+    NftExtensionMsg, NftInfoResponse, OperatorGrant, OperatorResponse, OperatorsResponse, OrderBy,
+    OwnerOfResponse, RoyaltyInfoResponse, TokenWithInfo, TraitFilter,
 };
 use crate::msg::{CollectionInfoMsg, Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721QueryMsg};
 use crate::receiver::Cw721ReceiveMsg;
-use crate::state::{NftExtension, Trait, CREATOR, MINTER};
+use crate::state::{
+    Cw721Config, NftExtension, Trait, CREATOR, MAX_COLLECTION_ATTRIBUTE_VALUE_SIZE,
+    MAX_COLLECTION_CUSTOM_ATTRIBUTES, MINTER,
+};
 use crate::{
-    traits::{Cw721Execute, Cw721Query},
+    traits::{Contains, Cw721Execute, Cw721Query},
     Approval, DefaultOptionalCollectionExtensionMsg, DefaultOptionalNftExtension,
     DefaultOptionalNftExtensionMsg, Expiration,
 };
@@ -140,7 +147,7 @@ fn test_instantiate() {
 
     // list the token_ids
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), &env, None, None)
+        .query_all_tokens(deps.as_ref(), &env, None, None, None, None)
         .unwrap();
     assert_eq!(0, tokens.tokens.len());
 }
@@ -231,7 +238,7 @@ fn test_instantiate_with_collection_info_and_extension() {
 
     // list the token_ids
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), &env, None, None)
+        .query_all_tokens(deps.as_ref(), &env, None, None, None, None)
         .unwrap();
     assert_eq!(0, tokens.tokens.len());
 }
@@ -399,7 +406,7 @@ fn test_mint() {
 
     // list the token_ids
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), &env, None, None)
+        .query_all_tokens(deps.as_ref(), &env, None, None, None, None)
         .unwrap();
     assert_eq!(1, tokens.tokens.len());
     assert_eq!(vec![token_id1.clone()], tokens.tokens);
@@ -451,7 +458,7 @@ fn test_mint() {
 
     // list the token_ids
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), &env, None, None)
+        .query_all_tokens(deps.as_ref(), &env, None, None, None, None)
         .unwrap();
     assert_eq!(2, tokens.tokens.len());
     assert_eq!(vec![token_id2.clone(), token_id1.clone()], tokens.tokens);
@@ -503,7 +510,7 @@ fn test_mint() {
 
     // list the token_ids
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), &env, None, None)
+        .query_all_tokens(deps.as_ref(), &env, None, None, None, None)
         .unwrap();
     assert_eq!(3, tokens.tokens.len());
     assert_eq!(vec![token_id2, token_id3, token_id1], tokens.tokens);
@@ -1217,7 +1224,7 @@ fn test_burn() {
 
     // list the token_ids
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), &env, None, None)
+        .query_all_tokens(deps.as_ref(), &env, None, None, None, None)
         .unwrap();
     assert!(tokens.tokens.is_empty());
 }
@@ -1541,12 +1548,19 @@ fn test_approve_all_revoke_all() {
 
     // paginate the token_ids
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), &env, None, Some(1))
+        .query_all_tokens(deps.as_ref(), &env, None, Some(1), None, None)
         .unwrap();
     assert_eq!(1, tokens.tokens.len());
     assert_eq!(vec![token_id1.clone()], tokens.tokens);
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), &env, Some(token_id1.clone()), Some(3))
+        .query_all_tokens(
+            deps.as_ref(),
+            &env,
+            Some(token_id1.clone()),
+            Some(3),
+            None,
+            None,
+        )
         .unwrap();
     assert_eq!(1, tokens.tokens.len());
     assert_eq!(vec![token_id2.clone()], tokens.tokens);
@@ -1647,6 +1661,8 @@ fn test_approve_all_revoke_all() {
             true,
             None,
             None,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -1679,6 +1695,8 @@ fn test_approve_all_revoke_all() {
             true,
             None,
             Some(1),
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -1698,6 +1716,8 @@ fn test_approve_all_revoke_all() {
             true,
             Some(addrs.addr("operator").to_string()),
             Some(2),
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -1709,6 +1729,34 @@ fn test_approve_all_revoke_all() {
             }]
         }
     );
+    // reverse order, the first result is the other end of the range
+    let res = contract
+        .query_operators(
+            deps.as_ref(),
+            &mock_env(),
+            addrs.addr("person").to_string(),
+            true,
+            None,
+            Some(1),
+            None,
+            Some(OrderBy::Desc),
+        )
+        .unwrap();
+    assert_eq!(res.operators[0].spender, addrs.addr("buddy"));
+    let res = contract
+        .query_operators(
+            deps.as_ref(),
+            &mock_env(),
+            addrs.addr("person").to_string(),
+            true,
+            None,
+            None,
+            Some(addrs.addr("operator").to_string()),
+            Some(OrderBy::Desc),
+        )
+        .unwrap();
+    assert_eq!(res.operators.len(), 1);
+    assert_eq!(res.operators[0].spender, addrs.addr("buddy"));
 
     let revoke_all_msg = Cw721ExecuteMsg::RevokeAll {
         operator: addrs.addr("operator").to_string(),
@@ -1739,6 +1787,8 @@ fn test_approve_all_revoke_all() {
             false,
             None,
             None,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
@@ -1762,6 +1812,8 @@ fn test_approve_all_revoke_all() {
             false,
             None,
             None,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(0, res.operators.len());
@@ -1925,16 +1977,23 @@ fn query_tokens_by_owner() {
     // get all tokens in order:
     let expected = vec![token_id1.clone(), token_id2.clone(), token_id3.clone()];
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), &env, None, None)
+        .query_all_tokens(deps.as_ref(), &env, None, None, None, None)
         .unwrap();
     assert_eq!(&expected, &tokens.tokens);
     // paginate
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), &env, None, Some(2))
+        .query_all_tokens(deps.as_ref(), &env, None, Some(2), None, None)
         .unwrap();
     assert_eq!(&expected[..2], &tokens.tokens[..]);
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), &env, Some(expected[1].clone()), None)
+        .query_all_tokens(
+            deps.as_ref(),
+            &env,
+            Some(expected[1].clone()),
+            None,
+            None,
+            None,
+        )
         .unwrap();
     assert_eq!(&expected[2..], &tokens.tokens[..]);
 
@@ -1943,11 +2002,27 @@ fn query_tokens_by_owner() {
     let by_demeter = vec![token_id1, token_id3];
     // all tokens by owner
     let tokens = contract
-        .query_tokens(deps.as_ref(), &env, demeter.clone().to_string(), None, None)
+        .query_tokens(
+            deps.as_ref(),
+            &env,
+            demeter.clone().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
         .unwrap();
     assert_eq!(&by_demeter, &tokens.tokens);
     let tokens = contract
-        .query_tokens(deps.as_ref(), &env, ceres.clone().to_string(), None, None)
+        .query_tokens(
+            deps.as_ref(),
+            &env,
+            ceres.clone().to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
         .unwrap();
     assert_eq!(&by_ceres, &tokens.tokens);
 
@@ -1959,6 +2034,8 @@ fn query_tokens_by_owner() {
             demeter.clone().to_string(),
             None,
             Some(1),
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(&by_demeter[..1], &tokens.tokens[..]);
@@ -1969,7 +2046,1587 @@ fn query_tokens_by_owner() {
             demeter.to_string(),
             Some(by_demeter[0].clone()),
             Some(3),
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(&by_demeter[1..], &tokens.tokens[..]);
+
+    // descending order, start_after is the upper bound
+    let tokens = contract
+        .query_all_tokens(deps.as_ref(), &env, None, None, None, Some(OrderBy::Desc))
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["sing", "grow2", "grow1"]);
+    let tokens = contract
+        .query_all_tokens(
+            deps.as_ref(),
+            &env,
+            Some("sing".to_string()),
+            Some(1),
+            None,
+            Some(OrderBy::Desc),
+        )
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["grow2"]);
+    let tokens = contract
+        .query_tokens(
+            deps.as_ref(),
+            &env,
+            demeter.to_string(),
+            None,
+            None,
+            None,
+            Some(OrderBy::Desc),
+        )
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["sing", "grow1"]);
+
+    // end_before is exclusive in both orders
+    let tokens = contract
+        .query_all_tokens(
+            deps.as_ref(),
+            &env,
+            None,
+            None,
+            Some("sing".to_string()),
+            None,
+        )
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["grow1", "grow2"]);
+    let tokens = contract
+        .query_all_tokens(
+            deps.as_ref(),
+            &env,
+            None,
+            None,
+            Some("grow1".to_string()),
+            Some(OrderBy::Desc),
+        )
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["sing", "grow2"]);
+    let tokens = contract
+        .query_tokens(
+            deps.as_ref(),
+            &env,
+            demeter.to_string(),
+            Some("sing".to_string()),
+            None,
+            Some("grow1".to_string()),
+            Some(OrderBy::Desc),
+        )
+        .unwrap();
+    assert!(tokens.tokens.is_empty());
+}
+
+#[test]
+fn query_tokens_with_info() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let minter_info = addrs.info("minter");
+    let demeter = addrs.addr("demeter");
+    let ceres = addrs.addr("ceres");
+    let env = mock_env();
+
+    for (token_id, owner) in [("grow1", &demeter), ("grow2", &ceres), ("sing", &demeter)] {
+        let mint_msg = Cw721ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: Some(format!("https://example.com/{token_id}")),
+            extension: None,
+        };
+        contract
+            .execute(deps.as_mut(), &env, &minter_info, mint_msg)
+            .unwrap();
+    }
+    let approve_msg = Cw721ExecuteMsg::Approve {
+        spender: ceres.to_string(),
+        token_id: "sing".to_string(),
+        expires: Some(Expiration::AtHeight(env.block.height)),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), approve_msg)
+        .unwrap_err();
+    let approve_msg = Cw721ExecuteMsg::Approve {
+        spender: ceres.to_string(),
+        token_id: "sing".to_string(),
+        expires: Some(Expiration::AtHeight(env.block.height + 1)),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), approve_msg)
+        .unwrap();
+
+    let tokens = contract
+        .query_tokens_with_info(deps.as_ref(), &env, demeter.to_string(), None, None, false)
+        .unwrap();
+    assert_eq!(
+        tokens.tokens,
+        vec![
+            TokenWithInfo {
+                token_id: "grow1".to_string(),
+                owner: demeter.to_string(),
+                token_uri: Some("https://example.com/grow1".to_string()),
+                extension: None,
+                approvals: vec![],
+            },
+            TokenWithInfo {
+                token_id: "sing".to_string(),
+                owner: demeter.to_string(),
+                token_uri: Some("https://example.com/sing".to_string()),
+                extension: None,
+                approvals: vec![Approval {
+                    spender: ceres.clone(),
+                    expires: Expiration::AtHeight(env.block.height + 1),
+                }],
+            },
+        ]
+    );
+    // paginate
+    let tokens = contract
+        .query_tokens_with_info(
+            deps.as_ref(),
+            &env,
+            demeter.to_string(),
+            Some("grow1".to_string()),
+            Some(1),
+            false,
+        )
+        .unwrap();
+    assert_eq!(tokens.tokens.len(), 1);
+    assert_eq!(tokens.tokens[0].token_id, "sing");
+
+    // expired approvals are filtered out, unless included
+    let mut later = env.clone();
+    later.block.height += 1;
+    let tokens = contract
+        .query_all_tokens_with_info(
+            deps.as_ref(),
+            &later,
+            Some("grow2".to_string()),
+            None,
+            false,
+        )
+        .unwrap();
+    assert_eq!(tokens.tokens.len(), 1);
+    assert_eq!(tokens.tokens[0].approvals, vec![]);
+    let tokens = contract
+        .query_all_tokens_with_info(deps.as_ref(), &later, None, None, true)
+        .unwrap();
+    let token_ids: Vec<_> = tokens.tokens.iter().map(|t| t.token_id.as_str()).collect();
+    assert_eq!(token_ids, vec!["grow1", "grow2", "sing"]);
+    assert_eq!(tokens.tokens[2].approvals.len(), 1);
+}
+
+#[test]
+fn query_tokens_by_trait() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let minter_info = addrs.info("minter");
+    let owner = addrs.addr("owner");
+    let env = mock_env();
+
+    let traits = |values: &[(&str, &str)]| {
+        Some(NftExtensionMsg {
+            attributes: Some(
+                values
+                    .iter()
+                    .map(|(trait_type, value)| Trait {
+                        display_type: None,
+                        trait_type: trait_type.to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect(),
+            ),
+            ..NftExtensionMsg::default()
+        })
+    };
+    for (token_id, extension) in [
+        ("1", traits(&[("color", "red"), ("size", "xl")])),
+        ("2", traits(&[("color", "blue")])),
+        ("3", traits(&[("color", "red")])),
+        ("4", None),
+        ("5", traits(&[("color", "red")])),
+    ] {
+        let mint_msg = Cw721ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension,
+        };
+        contract
+            .execute(deps.as_mut(), &env, &minter_info, mint_msg)
+            .unwrap();
+    }
+    let tokens_by_trait = |deps: &cosmwasm_std::OwnedDeps<_, _, _>,
+                           trait_type: &str,
+                           value: &str,
+                           start_after: Option<&str>,
+                           limit: Option<u32>| {
+        contract
+            .query_tokens_by_trait(
+                deps.as_ref(),
+                &env,
+                trait_type.to_string(),
+                value.to_string(),
+                start_after.map(String::from),
+                limit,
+            )
+            .unwrap()
+            .tokens
+    };
+
+    assert_eq!(
+        tokens_by_trait(&deps, "color", "red", None, None),
+        vec!["1", "3", "5"]
+    );
+    assert_eq!(tokens_by_trait(&deps, "size", "xl", None, None), vec!["1"]);
+    assert!(tokens_by_trait(&deps, "color", "green", None, None).is_empty());
+    // limit counts matches, not scanned tokens
+    assert_eq!(
+        tokens_by_trait(&deps, "color", "red", None, Some(2)),
+        vec!["1", "3"]
+    );
+    assert_eq!(
+        tokens_by_trait(&deps, "color", "red", Some("3"), Some(2)),
+        vec!["5"]
+    );
+
+    // update replaces index entries
+    let update_msg = Cw721ExecuteMsg::UpdateNftInfo {
+        token_id: "1".to_string(),
+        token_uri: None,
+        extension: traits(&[("color", "green")]),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("creator"), update_msg)
+        .unwrap();
+    assert_eq!(
+        tokens_by_trait(&deps, "color", "red", None, None),
+        vec!["3", "5"]
+    );
+    assert!(tokens_by_trait(&deps, "size", "xl", None, None).is_empty());
+    assert_eq!(
+        tokens_by_trait(&deps, "color", "green", None, None),
+        vec!["1"]
+    );
+
+    // burn removes index entries
+    let burn_msg = Cw721ExecuteMsg::Burn {
+        token_id: "3".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("owner"), burn_msg)
+        .unwrap();
+    assert_eq!(
+        tokens_by_trait(&deps, "color", "red", None, None),
+        vec!["5"]
+    );
+
+    // migration backfills the index of collections created before it existed
+    contract.config.nft_traits.clear(deps.as_mut().storage);
+    contract.config.nft_trait_keys.clear(deps.as_mut().storage);
+    contract.config.balances.clear(deps.as_mut().storage);
+    assert!(tokens_by_trait(&deps, "color", "red", None, None).is_empty());
+    contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            crate::msg::Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+            },
+            CONTRACT_NAME,
+            "2.0.0",
+        )
+        .unwrap();
+    assert_eq!(
+        tokens_by_trait(&deps, "color", "red", None, None),
+        vec!["5"]
+    );
+    assert_eq!(
+        tokens_by_trait(&deps, "color", "green", None, None),
+        vec!["1"]
+    );
+}
+
+#[test]
+fn query_tokens_by_filter() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let minter_info = addrs.info("minter");
+    let owner = addrs.addr("owner");
+    let env = mock_env();
+
+    let traits = |values: &[(Option<&str>, &str, &str)]| {
+        Some(NftExtensionMsg {
+            attributes: Some(
+                values
+                    .iter()
+                    .map(|(display_type, trait_type, value)| Trait {
+                        display_type: display_type.map(String::from),
+                        trait_type: trait_type.to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect(),
+            ),
+            ..NftExtensionMsg::default()
+        })
+    };
+    let number = Some("number");
+    for (token_id, extension) in [
+        (
+            "1",
+            traits(&[(None, "color", "red"), (number, "level", "5")]),
+        ),
+        (
+            "2",
+            traits(&[(None, "color", "dark blue"), (number, "level", "-2.5")]),
+        ),
+        (
+            "3",
+            traits(&[(None, "color", "dark red"), (None, "level", "7")]),
+        ),
+        ("4", None),
+    ] {
+        let mint_msg = Cw721ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension,
+        };
+        contract
+            .execute(deps.as_mut(), &env, &minter_info, mint_msg)
+            .unwrap();
+    }
+    let tokens_by_filter = |filter: TraitFilter| {
+        contract
+            .query_tokens_by_filter(deps.as_ref().storage, filter, None, None)
+            .unwrap()
+            .tokens
+    };
+    let eq = |trait_type: &str, value: &str| TraitFilter::Eq {
+        trait_type: trait_type.to_string(),
+        value: value.to_string(),
+    };
+
+    assert_eq!(tokens_by_filter(eq("color", "red")), vec!["1"]);
+    assert_eq!(
+        tokens_by_filter(TraitFilter::Ne {
+            trait_type: "color".to_string(),
+            value: "red".to_string(),
+        }),
+        vec!["2", "3", "4"]
+    );
+    assert_eq!(
+        tokens_by_filter(TraitFilter::Prefix {
+            trait_type: "color".to_string(),
+            prefix: "dark".to_string(),
+        }),
+        vec!["2", "3"]
+    );
+    assert_eq!(
+        tokens_by_filter(TraitFilter::In {
+            trait_type: "color".to_string(),
+            values: vec!["red".to_string(), "dark red".to_string()],
+        }),
+        vec!["1", "3"]
+    );
+    // only traits with display_type "number" are compared numerically
+    assert_eq!(
+        tokens_by_filter(TraitFilter::Range {
+            trait_type: "level".to_string(),
+            min: Some(SignedDecimal::from_str("-3").unwrap()),
+            max: Some(SignedDecimal::from_str("10").unwrap()),
+        }),
+        vec!["1", "2"]
+    );
+    assert_eq!(
+        tokens_by_filter(TraitFilter::Range {
+            trait_type: "level".to_string(),
+            min: Some(SignedDecimal::from_str("5").unwrap()),
+            max: None,
+        }),
+        vec!["1"]
+    );
+    assert_eq!(
+        tokens_by_filter(TraitFilter::Or(vec![
+            eq("color", "red"),
+            TraitFilter::And(vec![
+                eq("color", "dark blue"),
+                TraitFilter::Range {
+                    trait_type: "level".to_string(),
+                    min: None,
+                    max: Some(SignedDecimal::zero()),
+                },
+            ]),
+        ])),
+        vec!["1", "2"]
+    );
+    assert!(tokens_by_filter(TraitFilter::Or(vec![])).is_empty());
+    assert_eq!(tokens_by_filter(TraitFilter::And(vec![])).len(), 4);
+
+    // limit bounds scanned tokens, paging continues with the cursor
+    let res = contract
+        .query_tokens_by_filter(
+            deps.as_ref().storage,
+            eq("color", "dark red"),
+            None,
+            Some(2),
+        )
+        .unwrap();
+    assert!(res.tokens.is_empty());
+    assert_eq!(res.next_start_after, Some("2".to_string()));
+    let res = contract
+        .query_tokens_by_filter(
+            deps.as_ref().storage,
+            eq("color", "dark red"),
+            res.next_start_after,
+            Some(2),
+        )
+        .unwrap();
+    assert_eq!(res.tokens, vec!["3"]);
+    assert_eq!(res.next_start_after, Some("4".to_string()));
+}
+
+#[test]
+fn nft_extension_contains_attributes_in_any_order() {
+    let t = |trait_type: &str| Trait {
+        display_type: None,
+        trait_type: trait_type.to_string(),
+        value: "value".to_string(),
+    };
+    let nft = NftExtension {
+        attributes: Some(vec![t("a"), t("b")]),
+        ..NftExtension::default()
+    };
+    let filter = |attributes: Vec<Trait>| NftExtension {
+        attributes: Some(attributes),
+        ..NftExtension::default()
+    };
+    assert!(nft.contains(&filter(vec![t("b")])));
+    assert!(nft.contains(&filter(vec![t("b"), t("a")])));
+    assert!(!nft.contains(&filter(vec![t("a"), t("b"), t("c")])));
+}
+
+#[test]
+fn query_balance_of() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let minter_info = addrs.info("minter");
+    let demeter = addrs.addr("demeter");
+    let ceres = addrs.addr("ceres");
+    let receiver = addrs.addr("receiver");
+    let env = mock_env();
+
+    for (token_id, owner) in [("grow1", &demeter), ("grow2", &demeter), ("sing", &ceres)] {
+        let mint_msg = Cw721ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        contract
+            .execute(deps.as_mut(), &env, &minter_info, mint_msg)
+            .unwrap();
+    }
+    let balance_of = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, owner: &Addr| {
+        contract
+            .query_balance_of(deps.as_ref(), owner.to_string())
+            .unwrap()
+            .balance
+    };
+    assert_eq!(balance_of(&deps, &demeter), 2);
+    assert_eq!(balance_of(&deps, &ceres), 1);
+    assert_eq!(balance_of(&deps, &receiver), 0);
+
+    // transfer and send move balances
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: ceres.to_string(),
+        token_id: "grow1".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), transfer_msg)
+        .unwrap();
+    let send_msg = Cw721ExecuteMsg::SendNft {
+        contract: receiver.to_string(),
+        token_id: "grow2".to_string(),
+        msg: to_json_binary("yes").unwrap(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), send_msg)
+        .unwrap();
+    assert_eq!(balance_of(&deps, &demeter), 0);
+    assert_eq!(balance_of(&deps, &ceres), 2);
+    assert_eq!(balance_of(&deps, &receiver), 1);
+
+    // burn decrements
+    let burn_msg = Cw721ExecuteMsg::Burn {
+        token_id: "sing".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("ceres"), burn_msg)
+        .unwrap();
+    assert_eq!(balance_of(&deps, &ceres), 1);
+
+    // migration backfills balances of collections created before balances were tracked
+    contract.config.balances.clear(deps.as_mut().storage);
+    contract.config.stats.remove(deps.as_mut().storage);
+    assert_eq!(balance_of(&deps, &ceres), 0);
+    let res = contract
+        .migrate(
+            deps.as_mut(),
+            env,
+            crate::msg::Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+            },
+            CONTRACT_NAME,
+            "2.0.0",
+        )
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "backfilled" && attr.value == "2"));
+    assert_eq!(balance_of(&deps, &ceres), 1);
+    assert_eq!(balance_of(&deps, &receiver), 1);
+    assert_eq!(balance_of(&deps, &demeter), 0);
+}
+
+#[test]
+fn query_approved_tokens_and_operator_grants_for() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let minter_info = addrs.info("minter");
+    let demeter = addrs.addr("demeter");
+    let ceres = addrs.addr("ceres");
+    let market = addrs.addr("market");
+    let env = mock_env();
+
+    for (token_id, owner) in [("grow1", &demeter), ("grow2", &ceres), ("sing", &demeter)] {
+        let mint_msg = Cw721ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        contract
+            .execute(deps.as_mut(), &env, &minter_info, mint_msg)
+            .unwrap();
+    }
+    for (token_id, owner, expires) in [
+        ("grow1", "demeter", Expiration::Never {}),
+        ("grow2", "ceres", Expiration::AtHeight(env.block.height + 1)),
+        ("sing", "demeter", Expiration::Never {}),
+    ] {
+        let approve_msg = Cw721ExecuteMsg::Approve {
+            spender: market.to_string(),
+            token_id: token_id.to_string(),
+            expires: Some(expires),
+        };
+        contract
+            .execute(deps.as_mut(), &env, &addrs.info(owner), approve_msg)
+            .unwrap();
+    }
+    for owner in ["demeter", "ceres"] {
+        let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
+            operator: market.to_string(),
+            expires: None,
+        };
+        contract
+            .execute(deps.as_mut(), &env, &addrs.info(owner), approve_all_msg)
+            .unwrap();
+    }
+    let approved_tokens = |deps: &cosmwasm_std::OwnedDeps<_, _, _>,
+                           env: &cosmwasm_std::Env,
+                           include_expired: bool,
+                           start_after: Option<&str>| {
+        contract
+            .query_approved_tokens_for(
+                deps.as_ref(),
+                env,
+                market.to_string(),
+                include_expired,
+                start_after.map(String::from),
+                None,
+            )
+            .unwrap()
+            .tokens
+            .into_iter()
+            .map(|t| t.token_id)
+            .collect::<Vec<_>>()
+    };
+
+    let res = contract
+        .query_approved_tokens_for(deps.as_ref(), &env, market.to_string(), false, None, None)
+        .unwrap();
+    assert_eq!(
+        res.tokens[1],
+        ApprovedToken {
+            token_id: "grow2".to_string(),
+            owner: ceres.to_string(),
+            expires: Expiration::AtHeight(env.block.height + 1),
+        }
+    );
+    assert_eq!(
+        approved_tokens(&deps, &env, false, None),
+        vec!["grow1", "grow2", "sing"]
+    );
+    assert_eq!(
+        approved_tokens(&deps, &env, false, Some("grow1")),
+        vec!["grow2", "sing"]
+    );
+    // expired approvals are filtered out, unless included
+    let mut later = env.clone();
+    later.block.height += 1;
+    assert_eq!(
+        approved_tokens(&deps, &later, false, None),
+        vec!["grow1", "sing"]
+    );
+    assert_eq!(approved_tokens(&deps, &later, true, None).len(), 3);
+
+    let res = contract
+        .query_operator_grants_for(deps.as_ref(), &env, market.to_string(), false, None, None)
+        .unwrap();
+    let mut owners = [ceres.to_string(), demeter.to_string()];
+    owners.sort();
+    assert_eq!(
+        res.grants,
+        owners
+            .iter()
+            .map(|owner| OperatorGrant {
+                owner: owner.clone(),
+                expires: Expiration::Never {},
+            })
+            .collect::<Vec<_>>()
+    );
+    let res = contract
+        .query_operator_grants_for(
+            deps.as_ref(),
+            &env,
+            market.to_string(),
+            false,
+            Some(owners[0].clone()),
+            None,
+        )
+        .unwrap();
+    assert_eq!(res.grants.len(), 1);
+    assert_eq!(res.grants[0].owner, owners[1]);
+
+    // revoke, transfer and burn remove approvals, revoke_all removes grants
+    let revoke_msg = Cw721ExecuteMsg::Revoke {
+        spender: market.to_string(),
+        token_id: "grow1".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), revoke_msg)
+        .unwrap();
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: ceres.to_string(),
+        token_id: "sing".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), transfer_msg)
+        .unwrap();
+    assert_eq!(approved_tokens(&deps, &env, true, None), vec!["grow2"]);
+    let burn_msg = Cw721ExecuteMsg::Burn {
+        token_id: "grow2".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("ceres"), burn_msg)
+        .unwrap();
+    assert!(approved_tokens(&deps, &env, true, None).is_empty());
+
+    let revoke_all_msg = Cw721ExecuteMsg::RevokeAll {
+        operator: market.to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("ceres"), revoke_all_msg)
+        .unwrap();
+    let res = contract
+        .query_operator_grants_for(deps.as_ref(), &env, market.to_string(), true, None, None)
+        .unwrap();
+    assert_eq!(res.grants.len(), 1);
+    assert_eq!(res.grants[0].owner, demeter.to_string());
+}
+
+#[test]
+fn migrate_backfills_approval_indexes() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let demeter = addrs.addr("demeter");
+    let market = addrs.addr("market");
+    let env = mock_env();
+
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: "grow".to_string(),
+        owner: demeter.to_string(),
+        token_uri: None,
+        extension: None,
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("minter"), mint_msg)
+        .unwrap();
+    let approve_msg = Cw721ExecuteMsg::Approve {
+        spender: market.to_string(),
+        token_id: "grow".to_string(),
+        expires: None,
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), approve_msg)
+        .unwrap();
+    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
+        operator: market.to_string(),
+        expires: None,
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), approve_all_msg)
+        .unwrap();
+
+    // collections created before the indexes existed
+    contract
+        .config
+        .spender_approvals
+        .clear(deps.as_mut().storage);
+    contract.config.operator_grants.clear(deps.as_mut().storage);
+    contract.config.balances.clear(deps.as_mut().storage);
+    contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            crate::msg::Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+            },
+            CONTRACT_NAME,
+            "2.0.0",
+        )
+        .unwrap();
+
+    let res = contract
+        .query_approved_tokens_for(deps.as_ref(), &env, market.to_string(), false, None, None)
+        .unwrap();
+    assert_eq!(res.tokens.len(), 1);
+    assert_eq!(res.tokens[0].token_id, "grow");
+    let res = contract
+        .query_operator_grants_for(deps.as_ref(), &env, market.to_string(), false, None, None)
+        .unwrap();
+    assert_eq!(res.grants.len(), 1);
+    assert_eq!(res.grants[0].owner, demeter.to_string());
+}
+
+#[test]
+fn query_can_transfer_and_can_approve() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let demeter = addrs.addr("demeter");
+    let spender = addrs.addr("spender");
+    let operator = addrs.addr("operator");
+    let random = addrs.addr("random");
+    let env = mock_env();
+
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: "grow".to_string(),
+        owner: demeter.to_string(),
+        token_uri: None,
+        extension: None,
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("minter"), mint_msg)
+        .unwrap();
+    let expires = Expiration::AtHeight(env.block.height + 1);
+    let approve_msg = Cw721ExecuteMsg::Approve {
+        spender: spender.to_string(),
+        token_id: "grow".to_string(),
+        expires: Some(expires),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), approve_msg)
+        .unwrap();
+    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
+        operator: operator.to_string(),
+        expires: Some(expires),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), approve_all_msg)
+        .unwrap();
+
+    let can_transfer = |env: &cosmwasm_std::Env, sender: &Addr| {
+        contract
+            .query_can_transfer(deps.as_ref(), env, sender.to_string(), "grow".to_string())
+            .unwrap()
+    };
+    let can_approve = |env: &cosmwasm_std::Env, sender: &Addr| {
+        contract
+            .query_can_approve(deps.as_ref(), env, sender.to_string(), "grow".to_string())
+            .unwrap()
+    };
+
+    assert_eq!(
+        can_transfer(&env, &demeter),
+        AuthorizationResponse {
+            authorized: true,
+            reason: AuthorizationReason::Owner {},
+        }
+    );
+    assert_eq!(
+        can_transfer(&env, &spender),
+        AuthorizationResponse {
+            authorized: true,
+            reason: AuthorizationReason::Approval { expires },
+        }
+    );
+    assert_eq!(
+        can_transfer(&env, &operator),
+        AuthorizationResponse {
+            authorized: true,
+            reason: AuthorizationReason::Operator { expires },
+        }
+    );
+    assert_eq!(
+        can_transfer(&env, &random),
+        AuthorizationResponse {
+            authorized: false,
+            reason: AuthorizationReason::NotAuthorized {},
+        }
+    );
+    // token approvals don't allow approving others
+    assert_eq!(
+        can_approve(&env, &demeter).reason,
+        AuthorizationReason::Owner {}
+    );
+    assert_eq!(
+        can_approve(&env, &spender).reason,
+        AuthorizationReason::NotAuthorized {}
+    );
+    assert_eq!(
+        can_approve(&env, &operator).reason,
+        AuthorizationReason::Operator { expires }
+    );
+
+    // expired approvals and grants are reported
+    let mut later = env.clone();
+    later.block.height += 1;
+    assert_eq!(
+        can_transfer(&later, &spender),
+        AuthorizationResponse {
+            authorized: false,
+            reason: AuthorizationReason::ExpiredApproval { expires },
+        }
+    );
+    assert_eq!(
+        can_transfer(&later, &operator),
+        AuthorizationResponse {
+            authorized: false,
+            reason: AuthorizationReason::ExpiredOperator { expires },
+        }
+    );
+    assert_eq!(
+        can_approve(&later, &operator).reason,
+        AuthorizationReason::ExpiredOperator { expires }
+    );
+
+    // dry-run matches execution
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: random.to_string(),
+        token_id: "grow".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &later, &addrs.info("spender"), transfer_msg)
+        .unwrap_err();
+
+    // unknown token
+    contract
+        .query_can_transfer(
+            deps.as_ref(),
+            &env,
+            demeter.to_string(),
+            "unknown".to_string(),
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn query_collection_stats() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let demeter = addrs.addr("demeter");
+    let ceres = addrs.addr("ceres");
+    let mut env = mock_env();
+
+    let stats = contract
+        .query_collection_stats(deps.as_ref().storage)
+        .unwrap();
+    assert_eq!(
+        stats,
+        CollectionStatsResponse {
+            minted: 0,
+            burned: 0,
+            supply: 0,
+            holders: 0,
+            first_mint_at: None,
+            last_mint_at: None,
+        }
+    );
+
+    let first_mint_at = env.block.time;
+    for (token_id, owner) in [("grow1", &demeter), ("grow2", &demeter), ("sing", &ceres)] {
+        let mint_msg = Cw721ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        contract
+            .execute(deps.as_mut(), &env, &addrs.info("minter"), mint_msg)
+            .unwrap();
+        env.block.time = env.block.time.plus_seconds(10);
+    }
+    let last_mint_at = first_mint_at.plus_seconds(20);
+    let stats = contract
+        .query_collection_stats(deps.as_ref().storage)
+        .unwrap();
+    assert_eq!(
+        stats,
+        CollectionStatsResponse {
+            minted: 3,
+            burned: 0,
+            supply: 3,
+            holders: 2,
+            first_mint_at: Some(first_mint_at),
+            last_mint_at: Some(last_mint_at),
+        }
+    );
+
+    // holders follow transfers
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: demeter.to_string(),
+        token_id: "sing".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("ceres"), transfer_msg)
+        .unwrap();
+    assert_eq!(
+        contract
+            .query_collection_stats(deps.as_ref().storage)
+            .unwrap()
+            .holders,
+        1
+    );
+
+    let burn_msg = Cw721ExecuteMsg::Burn {
+        token_id: "grow1".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), burn_msg)
+        .unwrap();
+    let stats = contract
+        .query_collection_stats(deps.as_ref().storage)
+        .unwrap();
+    assert_eq!(
+        stats,
+        CollectionStatsResponse {
+            minted: 3,
+            burned: 1,
+            supply: 2,
+            holders: 1,
+            first_mint_at: Some(first_mint_at),
+            last_mint_at: Some(last_mint_at),
+        }
+    );
+
+    // migration initializes stats of collections created before they were tracked
+    contract.config.stats.remove(deps.as_mut().storage);
+    contract.config.balances.clear(deps.as_mut().storage);
+    contract
+        .migrate(
+            deps.as_mut(),
+            env,
+            crate::msg::Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+            },
+            CONTRACT_NAME,
+            "2.0.0",
+        )
+        .unwrap();
+    let stats = contract
+        .query_collection_stats(deps.as_ref().storage)
+        .unwrap();
+    assert_eq!(
+        stats,
+        CollectionStatsResponse {
+            minted: 2,
+            burned: 0,
+            supply: 2,
+            holders: 1,
+            first_mint_at: None,
+            last_mint_at: None,
+        }
+    );
+}
+
+#[test]
+fn query_holders() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let env = mock_env();
+
+    let demeter = addrs.addr("demeter");
+    let mut expected = vec![];
+    for (owner, balance) in [
+        (&demeter, 1),
+        (&addrs.addr("ceres"), 2),
+        (&addrs.addr("flora"), 3),
+    ] {
+        for i in 0..balance {
+            let mint_msg = Cw721ExecuteMsg::Mint {
+                token_id: format!("{owner}-{i}"),
+                owner: owner.to_string(),
+                token_uri: None,
+                extension: None,
+            };
+            contract
+                .execute(deps.as_mut(), &env, &addrs.info("minter"), mint_msg)
+                .unwrap();
+        }
+        expected.push(Holder {
+            owner: owner.to_string(),
+            balance,
+        });
+    }
+    expected.sort_by(|a, b| a.owner.cmp(&b.owner));
+
+    let res = contract.query_holders(deps.as_ref(), None, None).unwrap();
+    assert_eq!(res.holders, expected);
+    // paginate
+    let res = contract
+        .query_holders(deps.as_ref(), None, Some(2))
+        .unwrap();
+    assert_eq!(res.holders, expected[..2]);
+    let res = contract
+        .query_holders(deps.as_ref(), Some(expected[1].owner.clone()), Some(2))
+        .unwrap();
+    assert_eq!(res.holders, expected[2..]);
+
+    // owners without tokens are not listed
+    let burn_msg = Cw721ExecuteMsg::Burn {
+        token_id: format!("{demeter}-0"),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), burn_msg)
+        .unwrap();
+    let res = contract.query_holders(deps.as_ref(), None, None).unwrap();
+    assert_eq!(res.holders.len(), 2);
+    assert!(res.holders.iter().all(|h| h.owner != demeter.as_str()));
+}
+
+#[test]
+fn update_collection_extension_attributes() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let env = mock_env();
+    let attr = |attr_type: AttributeType, key: &str, value: &str| AttributeMsg {
+        attr_type,
+        key: key.to_string(),
+        value: value.to_string(),
+        data: None,
+    };
+    let update = |set: Vec<AttributeMsg>, remove: Vec<&str>| {
+        Cw721ExecuteMsg::UpdateCollectionExtensionAttributes {
+            set,
+            remove: remove.into_iter().map(String::from).collect(),
+        }
+    };
+
+    // only creator can update attributes
+    let msg = update(
+        vec![attr(AttributeType::U64, "max_supply", "10000")],
+        vec![],
+    );
+    let err = contract
+        .execute(deps.as_mut(), &env, &addrs.info("minter"), msg)
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::NotCreator {});
+
+    // add typed attributes
+    let msg = update(
+        vec![
+            attr(AttributeType::U64, "max_supply", "10000"),
+            attr(AttributeType::Boolean, "revealed", "false"),
+            attr(
+                AttributeType::Custom,
+                "socials",
+                r#"{"x":"@magic","links":["https://a.io","https://b.io"]}"#,
+            ),
+        ],
+        vec![],
+    );
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("creator"), msg)
+        .unwrap();
+    let max_supply = contract
+        .query_collection_extension_attribute(deps.as_ref(), "max_supply".to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(max_supply.value::<u64>().unwrap(), 10000);
+    #[cosmwasm_schema::cw_serde]
+    struct Socials {
+        x: String,
+        links: Vec<String>,
+    }
+    let socials = contract
+        .query_collection_extension_attribute(deps.as_ref(), "socials".to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        socials.value::<Socials>().unwrap(),
+        Socials {
+            x: "@magic".to_string(),
+            links: vec!["https://a.io".to_string(), "https://b.io".to_string()],
+        }
+    );
+
+    // update one and remove another attribute
+    let msg = update(
+        vec![attr(AttributeType::Boolean, "revealed", "true")],
+        vec!["max_supply"],
+    );
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("creator"), msg)
+        .unwrap();
+    let revealed = contract
+        .query_collection_extension_attribute(deps.as_ref(), "revealed".to_string())
+        .unwrap()
+        .unwrap();
+    assert!(revealed.value::<bool>().unwrap());
+    let max_supply = contract
+        .query_collection_extension_attribute(deps.as_ref(), "max_supply".to_string())
+        .unwrap();
+    assert_eq!(max_supply, None);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            &env,
+            &addrs.info("creator"),
+            update(vec![], vec!["max_supply"]),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::AttributeMissing("max_supply".to_string())
+    );
+
+    // invalid values are rejected
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            &env,
+            &addrs.info("creator"),
+            update(
+                vec![attr(AttributeType::Custom, "socials", "{\"x\":")],
+                vec![],
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::InvalidCustomAttributeValue {
+            key: "socials".to_string()
+        }
+    );
+    let too_large = format!(
+        "\"{}\"",
+        "a".repeat(MAX_COLLECTION_ATTRIBUTE_VALUE_SIZE as usize)
+    );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            &env,
+            &addrs.info("creator"),
+            update(
+                vec![attr(AttributeType::Custom, "lore", &too_large)],
+                vec![],
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::CollectionAttributeValueTooLarge {
+            key: "lore".to_string(),
+            max_size: MAX_COLLECTION_ATTRIBUTE_VALUE_SIZE
+        }
+    );
+
+    // keys of the default collection extension are reserved
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            &env,
+            &addrs.info("creator"),
+            update(
+                vec![attr(AttributeType::String, "description", "foo")],
+                vec![],
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::CollectionAttributeKeyReserved {
+            key: "description".to_string()
+        }
+    );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            &env,
+            &addrs.info("creator"),
+            update(vec![attr(AttributeType::String, "", "foo")], vec![]),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::CollectionAttributeKeyEmpty {});
+
+    // number of custom attributes is limited
+    let set = (0..MAX_COLLECTION_CUSTOM_ATTRIBUTES)
+        .map(|i| attr(AttributeType::U64, &format!("key_{i}"), "1"))
+        .collect();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            &env,
+            &addrs.info("creator"),
+            update(set, vec![]),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::MaxCollectionAttributesExceeded {
+            max: MAX_COLLECTION_CUSTOM_ATTRIBUTES
+        }
+    );
+}
+
+#[test]
+fn collection_extension_attribute_types_round_trip() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let env = mock_env();
+    let attr = |attr_type: AttributeType, key: &str, value: &str| AttributeMsg {
+        attr_type,
+        key: key.to_string(),
+        value: value.to_string(),
+        data: None,
+    };
+    let query = |deps: cosmwasm_std::Deps, key: &str| {
+        contract
+            .query_collection_extension_attribute(deps, key.to_string())
+            .unwrap()
+            .unwrap()
+    };
+
+    let msg = Cw721ExecuteMsg::UpdateCollectionExtensionAttributes {
+        set: vec![
+            attr(AttributeType::Addr, "treasury", creator.as_str()),
+            attr(
+                AttributeType::Uint128,
+                "max_supply",
+                "340282366920938463463374607431768211455",
+            ),
+            attr(AttributeType::Coin, "mint_price", "100ujuno"),
+            attr(
+                AttributeType::VecString,
+                "socials",
+                r#"["https://x.com/magic","https://discord.gg/magic"]"#,
+            ),
+            attr(AttributeType::Url, "website", "https://magic.io/"),
+            attr(
+                AttributeType::Json,
+                "phases",
+                r#"{"public":{"start":1},"allowlist":[1,2]}"#,
+            ),
+        ],
+        remove: vec![],
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("creator"), msg)
+        .unwrap();
+
+    assert_eq!(
+        query(deps.as_ref(), "treasury").value::<Addr>().unwrap(),
+        creator
+    );
+    assert_eq!(
+        query(deps.as_ref(), "max_supply")
+            .value::<Uint128>()
+            .unwrap(),
+        Uint128::MAX
+    );
+    assert_eq!(
+        query(deps.as_ref(), "mint_price").value::<Coin>().unwrap(),
+        Coin::new(100u128, "ujuno")
+    );
+    assert_eq!(
+        query(deps.as_ref(), "socials")
+            .value::<Vec<String>>()
+            .unwrap(),
+        vec!["https://x.com/magic", "https://discord.gg/magic"]
+    );
+    assert_eq!(
+        query(deps.as_ref(), "website").value::<url::Url>().unwrap(),
+        url::Url::parse("https://magic.io/").unwrap()
+    );
+    #[cosmwasm_schema::cw_serde]
+    struct Phases {
+        public: std::collections::BTreeMap<String, u64>,
+        allowlist: Vec<u64>,
+    }
+    assert_eq!(
+        query(deps.as_ref(), "phases").value::<Phases>().unwrap(),
+        Phases {
+            public: [("start".to_string(), 1)].into(),
+            allowlist: vec![1, 2],
+        }
+    );
+
+    // invalid values are rejected
+    for invalid in [
+        attr(AttributeType::Addr, "treasury", "invalid"),
+        attr(AttributeType::Uint128, "max_supply", "-1"),
+        attr(AttributeType::Coin, "mint_price", "ujuno"),
+        attr(AttributeType::VecString, "socials", "[1,2]"),
+        attr(AttributeType::Url, "website", "magic.io"),
+        attr(AttributeType::Json, "phases", "[1,2]"),
+    ] {
+        let msg = Cw721ExecuteMsg::UpdateCollectionExtensionAttributes {
+            set: vec![invalid],
+            remove: vec![],
+        };
+        contract
+            .execute(deps.as_mut(), &env, &addrs.info("creator"), msg)
+            .unwrap_err();
+    }
+}
+
+#[test]
+fn custom_collection_attributes_without_collection_extension() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    // instantiated without collection extension
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let env = mock_env();
+
+    let msg = Cw721ExecuteMsg::UpdateCollectionExtensionAttributes {
+        set: vec![AttributeMsg {
+            attr_type: AttributeType::U64,
+            key: "max_supply".to_string(),
+            value: "10000".to_string(),
+            data: None,
+        }],
+        remove: vec![],
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("creator"), msg)
+        .unwrap();
+
+    // custom attributes are not part of the collection extension
+    let info = contract
+        .query_collection_info_and_extension(deps.as_ref())
+        .unwrap();
+    assert_eq!(info.extension, None);
+    let msg = Cw721ExecuteMsg::UpdateCollectionInfo {
+        collection_info: CollectionInfoMsg {
+            name: Some("new name".to_string()),
+            symbol: None,
+            extension: None,
+        },
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("creator"), msg)
+        .unwrap();
+    let info = contract
+        .query_collection_info_and_extension(deps.as_ref())
+        .unwrap();
+    assert_eq!(info.name, "new name");
+    assert_eq!(info.extension, None);
+    let max_supply = contract
+        .query_collection_extension_attribute(deps.as_ref(), "max_supply".to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(max_supply.value::<u64>().unwrap(), 10000);
+}
+
+#[test]
+fn decrement_balance_without_backfilled_stats() {
+    let mut deps = mock_dependencies();
+    let owner = deps.api.addr_make("owner");
+    let config = Cw721Config::<DefaultOptionalNftExtension>::default();
+    // balance without holders count, e.g. stats not backfilled yet
+    config.balances.save(&mut deps.storage, &owner, &1).unwrap();
+
+    assert_eq!(
+        config.decrement_balance(&mut deps.storage, &owner).unwrap(),
+        0
+    );
+    assert_eq!(config.collection_stats(&deps.storage).unwrap().holders, 0);
+}
+
+#[test]
+fn migrate_backfills_balances_in_batches() {
+    let mut deps = mock_dependencies();
+    let mut addrs = MockAddrFactory::new(deps.api);
+    let creator = addrs.addr("creator");
+    let minter = addrs.addr("minter");
+    let contract = setup_contract(deps.as_mut(), &creator, &minter);
+    let env = mock_env();
+    let demeter = addrs.addr("demeter");
+    let ceres = addrs.addr("ceres");
+    let flora = addrs.addr("flora");
+    let mint = |deps: DepsMut, token_id: String, owner: &Addr| {
+        let mint_msg = Cw721ExecuteMsg::Mint {
+            token_id,
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        };
+        contract
+            .execute(deps, &env, &message_info(&minter, &[]), mint_msg)
+            .unwrap();
+    };
+    let balance_of = |deps: Deps, owner: &Addr| {
+        contract
+            .query_balance_of(deps, owner.to_string())
+            .unwrap()
+            .balance
+    };
+
+    // more tokens than a single backfill batch
+    for i in 0..102 {
+        let owner = if i % 2 == 0 { &demeter } else { &ceres };
+        mint(deps.as_mut(), format!("t{i:03}"), owner);
+    }
+    // collection created before balances and stats were tracked
+    contract.config.balances.clear(deps.as_mut().storage);
+    contract.config.stats.remove(deps.as_mut().storage);
+
+    let res = contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            crate::msg::Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+            },
+            CONTRACT_NAME,
+            "2.0.0",
+        )
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "backfill_done" && attr.value == "false"));
+    // pending tokens are counted while the backfill is pending
+    assert_eq!(balance_of(deps.as_ref(), &demeter), 51);
+    assert_eq!(balance_of(deps.as_ref(), &ceres), 51);
+
+    // tokens not backfilled yet can be minted, transferred and burned
+    mint(deps.as_mut(), "t102".to_string(), &flora);
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: flora.to_string(),
+        token_id: "t101".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("ceres"), transfer_msg)
+        .unwrap();
+    let burn_msg = Cw721ExecuteMsg::Burn {
+        token_id: "t100".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), burn_msg)
+        .unwrap();
+    // ... as well as backfilled ones
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: flora.to_string(),
+        token_id: "t000".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), &env, &addrs.info("demeter"), transfer_msg)
+        .unwrap();
+    assert_eq!(balance_of(deps.as_ref(), &demeter), 49);
+    assert_eq!(balance_of(deps.as_ref(), &ceres), 50);
+    assert_eq!(balance_of(deps.as_ref(), &flora), 3);
+
+    // anyone can continue the backfill
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            &env,
+            &addrs.info("random"),
+            Cw721ExecuteMsg::BackfillIndexes { limit: None },
+        )
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "backfill_done" && attr.value == "true"));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            &env,
+            &addrs.info("random"),
+            Cw721ExecuteMsg::BackfillIndexes { limit: None },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::NoBackfillPending {});
+    for (owner, balance) in [(&demeter, 49), (&ceres, 50), (&flora, 3)] {
+        assert_eq!(balance_of(deps.as_ref(), owner), balance);
+        assert_eq!(
+            contract
+                .config
+                .balance(deps.as_ref().storage, owner)
+                .unwrap(),
+            balance
+        );
+    }
+    // stats start at the supply on migration, holders are counted by the backfill
+    let stats = contract
+        .query_collection_stats(deps.as_ref().storage)
+        .unwrap();
+    assert_eq!(
+        (stats.minted, stats.burned, stats.supply, stats.holders),
+        (103, 1, 102, 3)
+    );
 }
@@ -4,7 +4,7 @@ use crate::{
     extension::Cw721OnchainExtensions,
     msg::{
         AdditionalMintersResponse, CollectionExtensionMsg, CollectionInfoMsg, Cw721ExecuteMsg,
-        Cw721InstantiateMsg, Cw721QueryMsg, NftExtensionMsg, RoyaltyInfoResponse,
+        Cw721InstantiateMsg, Cw721QueryMsg, NftByExtension, NftExtensionMsg, RoyaltyInfoResponse,
     },
     state::{
         NftExtension, Trait, CREATOR, MAX_COLLECTION_DESCRIPTION_LENGTH,
@@ -1561,6 +1561,38 @@ fn test_nft_mint() {
         assert!(res.is_some());
         let result = res.unwrap();
         assert_eq!(result.len(), 2);
+
+        // matches include token_id and owner
+        let filter = Some(NftExtension {
+            description: Some("other_description".into()),
+            ..NftExtension::default()
+        });
+        let res = contract
+            .query_nfts_by_extension(deps.as_ref().storage, filter.clone(), None, None)
+            .unwrap();
+        assert_eq!(
+            res.nfts,
+            vec![NftByExtension {
+                token_id: nft_2.to_string(),
+                owner: addrs.addr("allen").to_string(),
+                token_uri: uri_2,
+                extension: nft_info_2.extension,
+            }]
+        );
+        assert_eq!(res.next_start_after, None);
+
+        // a page without matches still returns a cursor
+        let res = contract
+            .query_nfts_by_extension(deps.as_ref().storage, filter.clone(), None, Some(1))
+            .unwrap();
+        assert!(res.nfts.is_empty());
+        assert_eq!(res.next_start_after, Some(nft_1.to_string()));
+        let res = contract
+            .query_nfts_by_extension(deps.as_ref().storage, filter, res.next_start_after, Some(1))
+            .unwrap();
+        assert_eq!(res.nfts.len(), 1);
+        assert_eq!(res.nfts[0].token_id, nft_2);
+        assert_eq!(res.next_start_after, Some(nft_2.to_string()));
     }
 }
 
@@ -1618,6 +1650,8 @@ fn test_additional_minters_add_remove() {
                 >::GetAdditionalMinters {
                     start_after: None,
                     limit: None,
+                    end_before: None,
+                    order: None,
                 },
             )
             .unwrap();
@@ -1663,6 +1697,8 @@ fn test_additional_minters_add_remove() {
                 >::GetAdditionalMinters {
                     start_after: None,
                     limit: None,
+                    end_before: None,
+                    order: None,
                 },
             )
             .unwrap();
@@ -1955,6 +1991,8 @@ fn test_additional_minters_query_pagination() {
                 >::GetAdditionalMinters {
                     start_after: None,
                     limit: None,
+                    end_before: None,
+                    order: None,
                 },
             )
             .unwrap();
@@ -1974,6 +2012,8 @@ fn test_additional_minters_query_pagination() {
                 >::GetAdditionalMinters {
                     start_after: None,
                     limit: Some(1),
+                    end_before: None,
+                    order: None,
                 },
             )
             .unwrap();
@@ -1994,6 +2034,8 @@ fn test_additional_minters_query_pagination() {
                 >::GetAdditionalMinters {
                     start_after: Some(first),
                     limit: Some(10),
+                    end_before: None,
+                    order: None,
                 },
             )
             .unwrap();
@@ -2150,6 +2192,8 @@ fn test_additional_minters_cleared_on_ownership_transfer() {
                 >::GetAdditionalMinters {
                     start_after: None,
                     limit: None,
+                    end_before: None,
+                    order: None,
                 },
             )
             .unwrap();
@@ -2182,6 +2226,8 @@ fn test_additional_minters_cleared_on_ownership_transfer() {
                 >::GetAdditionalMinters {
                     start_after: None,
                     limit: None,
+                    end_before: None,
+                    order: None,
                 },
             )
             .unwrap();
@@ -2211,6 +2257,8 @@ fn test_additional_minters_cleared_on_ownership_transfer() {
                 >::GetAdditionalMinters {
                     start_after: None,
                     limit: None,
+                    end_before: None,
+                    order: None,
                 },
             )
             .unwrap();
@@ -2316,6 +2364,8 @@ fn test_additional_minters_not_cleared_on_expired_transfer() {
                 >::GetAdditionalMinters {
                     start_after: None,
                     limit: None,
+                    end_before: None,
+                    order: None,
                 },
             )
             .unwrap();
@@ -13,26 +13,34 @@ use serde::{de::DeserializeOwned, Serialize};
 use crate::{
     error::Cw721ContractError,
     execute::{
-        add_additional_minter, approve, approve_all, burn_nft, initialize_creator,
-        initialize_minter, instantiate, instantiate_with_version, migrate, mint,
-        remove_additional_minter, remove_withdraw_address, revoke, revoke_all, send_nft,
-        set_withdraw_address, transfer_nft, update_collection_info, update_creator_ownership,
-        update_minter_ownership, update_nft_info, withdraw_funds,
+        add_additional_minter, approve, approve_all, burn_nft, execute_backfill_indexes,
+        initialize_creator, initialize_minter, instantiate, instantiate_with_version, migrate,
+        mint, remove_additional_minter, remove_withdraw_address, revoke, revoke_all, send_nft,
+        set_withdraw_address, transfer_nft, update_collection_extension_attributes,
+        update_collection_info, update_creator_ownership, update_minter_ownership, update_nft_info,
+        withdraw_funds,
     },
     msg::{
         AdditionalMintersResponse, AllNftInfoResponse, ApprovalResponse, ApprovalsResponse,
-        CollectionInfoAndExtensionResponse, CollectionInfoMsg, Cw721ExecuteMsg,
-        Cw721InstantiateMsg, Cw721MigrateMsg, Cw721QueryMsg, MinterResponse, NftInfoResponse,
-        NumTokensResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
+        ApprovedTokensResponse, AttributeMsg, AuthorizationResponse, BalanceOfResponse,
+        CollectionInfoAndExtensionResponse, CollectionInfoMsg, CollectionStatsResponse,
+        Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721MigrateMsg, Cw721QueryMsg, HoldersResponse,
+        MinterResponse, NftInfoResponse, NftsByExtensionResponse, NumTokensResponse,
+        OperatorGrantsResponse, OperatorResponse, OperatorsResponse, OrderBy, OwnerOfResponse,
+        TokensByFilterResponse, TokensResponse, TokensWithInfoResponse, TraitFilter,
     },
     query::{
-        query_additional_minters, query_all_nft_info, query_all_tokens, query_approval,
-        query_approvals, query_collection_extension_attributes, query_collection_info,
-        query_collection_info_and_extension, query_creator_ownership, query_minter,
-        query_minter_ownership, query_nft_info, query_num_tokens, query_operator, query_operators,
-        query_owner_of, query_tokens, query_withdraw_address,
+        query_additional_minters, query_all_nft_info, query_all_tokens, query_all_tokens_with_info,
+        query_approval, query_approvals, query_approved_tokens_for, query_balance_of,
+        query_can_approve, query_can_transfer, query_collection_extension_attribute,
+        query_collection_extension_attributes, query_collection_info,
+        query_collection_info_and_extension, query_collection_stats, query_creator_ownership,
+        query_holders, query_minter, query_minter_ownership, query_nft_info,
+        query_nfts_by_extension, query_num_tokens, query_operator, query_operator_grants_for,
+        query_operators, query_owner_of, query_tokens, query_tokens_by_filter,
+        query_tokens_by_trait, query_tokens_with_info, query_withdraw_address,
     },
-    state::CollectionInfo,
+    state::{CollectionInfo, Trait, RESERVED_COLLECTION_ATTRIBUTE_KEYS},
     Attribute,
 };
 use crate::{
@@ -52,10 +60,38 @@ use crate::{
 /// This will be removed once the `CustomMsg` trait is moved to the `cosmwasm_std` crate: https://github.com/CosmWasm/cosmwasm/issues/2056
 pub trait Cw721CustomMsg: Serialize + Clone + Debug + PartialEq + JsonSchema {}
 
-pub trait Cw721State: Serialize + DeserializeOwned + Clone + Debug {}
+pub trait Cw721State: Serialize + DeserializeOwned + Clone + Debug {
+    /// Traits (e.g. `NftExtension.attributes`) evaluated by `TraitFilter`.
+    /// Default is none, so extensions without traits never match a filter.
+    fn traits(&self) -> Vec<Trait> {
+        vec![]
+    }
+
+    /// `(trait_type, value)` pairs stored in the trait index, queryable via `TokensByTrait`.
+    /// Default indexes all `traits`.
+    fn indexed_traits(&self) -> Vec<(String, String)> {
+        self.traits()
+            .into_iter()
+            .map(|t| (t.trait_type, t.value))
+            .collect()
+    }
+}
 
 impl Cw721State for Empty {}
-impl<T> Cw721State for Option<T> where T: Cw721State {}
+impl<T> Cw721State for Option<T>
+where
+    T: Cw721State,
+{
+    fn traits(&self) -> Vec<Trait> {
+        self.as_ref().map(|ext| ext.traits()).unwrap_or_default()
+    }
+
+    fn indexed_traits(&self) -> Vec<(String, String)> {
+        self.as_ref()
+            .map(|ext| ext.indexed_traits())
+            .unwrap_or_default()
+    }
+}
 
 impl Cw721CustomMsg for Empty {}
 impl<T> Cw721CustomMsg for Option<T> where T: Cw721CustomMsg {}
@@ -128,11 +164,18 @@ impl<T> FromAttributesState for Option<T>
 where
     T: FromAttributesState,
 {
+    /// Only reserved keys belong to the extension, custom attributes set via
+    /// `UpdateCollectionExtensionAttributes` are ignored.
     fn from_attributes_state(value: &[Attribute]) -> Result<Self, Cw721ContractError> {
-        if value.is_empty() {
+        let reserved: Vec<Attribute> = value
+            .iter()
+            .filter(|attr| RESERVED_COLLECTION_ATTRIBUTE_KEYS.contains(&attr.key.as_str()))
+            .cloned()
+            .collect();
+        if reserved.is_empty() {
             Ok(None)
         } else {
-            T::from_attributes_state(value).map(Some)
+            T::from_attributes_state(&reserved).map(Some)
         }
     }
 }
@@ -223,6 +266,10 @@ pub trait Cw721Execute<
             Cw721ExecuteMsg::RemoveMinter { minter } => {
                 self.remove_additional_minter(deps, info, minter)
             }
+            Cw721ExecuteMsg::UpdateCollectionExtensionAttributes { set, remove } => {
+                self.update_collection_extension_attributes(deps, info, set, remove)
+            }
+            Cw721ExecuteMsg::BackfillIndexes { limit } => self.backfill_indexes(deps, limit),
             #[allow(deprecated)]
             Cw721ExecuteMsg::UpdateOwnership(action) => {
                 self.update_minter_ownership(deps, env, info, action)
@@ -260,7 +307,7 @@ pub trait Cw721Execute<
         contract_name: &str,
         contract_version: &str,
     ) -> Result<Response, Cw721ContractError> {
-        migrate(deps, env, msg, contract_name, contract_version)
+        migrate::<TNftExtension>(deps, env, msg, contract_name, contract_version)
     }
 
     // ------- ERC721-based functions -------
@@ -365,6 +412,24 @@ pub trait Cw721Execute<
         remove_additional_minter::<TCustomResponseMsg>(deps, info, minter)
     }
 
+    fn update_collection_extension_attributes(
+        &self,
+        deps: DepsMut,
+        info: &MessageInfo,
+        set: Vec<AttributeMsg>,
+        remove: Vec<String>,
+    ) -> Result<Response<TCustomResponseMsg>, Cw721ContractError> {
+        update_collection_extension_attributes::<TCustomResponseMsg>(deps, info, set, remove)
+    }
+
+    fn backfill_indexes(
+        &self,
+        deps: DepsMut,
+        limit: Option<u32>,
+    ) -> Result<Response<TCustomResponseMsg>, Cw721ContractError> {
+        execute_backfill_indexes::<TNftExtension, TCustomResponseMsg>(deps, limit)
+    }
+
     // ------- opionated cw721 functions -------
     fn initialize_creator(
         &self,
@@ -539,6 +604,26 @@ pub trait Cw721Query<
                 start_after,
                 limit,
             )?)?),
+            Cw721QueryMsg::NftsByExtension {
+                extension,
+                start_after,
+                limit,
+            } => Ok(to_json_binary(&self.query_nfts_by_extension(
+                deps.storage,
+                extension,
+                start_after,
+                limit,
+            )?)?),
+            Cw721QueryMsg::TokensByFilter {
+                filter,
+                start_after,
+                limit,
+            } => Ok(to_json_binary(&self.query_tokens_by_filter(
+                deps.storage,
+                filter,
+                start_after,
+                limit,
+            )?)?),
             Cw721QueryMsg::OwnerOf {
                 token_id,
                 include_expired,
@@ -573,6 +658,8 @@ pub trait Cw721Query<
                 include_expired,
                 start_after,
                 limit,
+                end_before,
+                order,
             } => Ok(to_json_binary(&self.query_operators(
                 deps,
                 env,
@@ -580,24 +667,118 @@ pub trait Cw721Query<
                 include_expired.unwrap_or(false),
                 start_after,
                 limit,
+                end_before,
+                order,
+            )?)?),
+            Cw721QueryMsg::ApprovedTokensFor {
+                spender,
+                include_expired,
+                start_after,
+                limit,
+            } => Ok(to_json_binary(&self.query_approved_tokens_for(
+                deps,
+                env,
+                spender,
+                include_expired.unwrap_or(false),
+                start_after,
+                limit,
+            )?)?),
+            Cw721QueryMsg::OperatorGrantsFor {
+                operator,
+                include_expired,
+                start_after,
+                limit,
+            } => Ok(to_json_binary(&self.query_operator_grants_for(
+                deps,
+                env,
+                operator,
+                include_expired.unwrap_or(false),
+                start_after,
+                limit,
             )?)?),
+            Cw721QueryMsg::CanTransfer { sender, token_id } => Ok(to_json_binary(
+                &self.query_can_transfer(deps, env, sender, token_id)?,
+            )?),
+            Cw721QueryMsg::CanApprove { sender, token_id } => Ok(to_json_binary(
+                &self.query_can_approve(deps, env, sender, token_id)?,
+            )?),
             Cw721QueryMsg::NumTokens {} => {
                 Ok(to_json_binary(&self.query_num_tokens(deps.storage)?)?)
             }
+            Cw721QueryMsg::CollectionStats {} => {
+                Ok(to_json_binary(&self.query_collection_stats(deps.storage)?)?)
+            }
+            Cw721QueryMsg::BalanceOf { owner } => {
+                Ok(to_json_binary(&self.query_balance_of(deps, owner)?)?)
+            }
+            Cw721QueryMsg::Holders { start_after, limit } => Ok(to_json_binary(
+                &self.query_holders(deps, start_after, limit)?,
+            )?),
             Cw721QueryMsg::Tokens {
                 owner,
                 start_after,
                 limit,
+                end_before,
+                order,
             } => Ok(to_json_binary(&self.query_tokens(
                 deps,
                 env,
                 owner,
                 start_after,
                 limit,
+                end_before,
+                order,
+            )?)?),
+            Cw721QueryMsg::AllTokens {
+                start_after,
+                limit,
+                end_before,
+                order,
+            } => Ok(to_json_binary(&self.query_all_tokens(
+                deps,
+                env,
+                start_after,
+                limit,
+                end_before,
+                order,
+            )?)?),
+            Cw721QueryMsg::TokensWithInfo {
+                owner,
+                start_after,
+                limit,
+                include_expired,
+            } => Ok(to_json_binary(&self.query_tokens_with_info(
+                deps,
+                env,
+                owner,
+                start_after,
+                limit,
+                include_expired.unwrap_or(false),
+            )?)?),
+            Cw721QueryMsg::TokensByTrait {
+                trait_type,
+                value,
+                start_after,
+                limit,
+            } => Ok(to_json_binary(&self.query_tokens_by_trait(
+                deps,
+                env,
+                trait_type,
+                value,
+                start_after,
+                limit,
+            )?)?),
+            Cw721QueryMsg::AllTokensWithInfo {
+                start_after,
+                limit,
+                include_expired,
+            } => Ok(to_json_binary(&self.query_all_tokens_with_info(
+                deps,
+                env,
+                start_after,
+                limit,
+                include_expired.unwrap_or(false),
             )?)?),
-            Cw721QueryMsg::AllTokens { start_after, limit } => Ok(to_json_binary(
-                &self.query_all_tokens(deps, env, start_after, limit)?,
-            )?),
             Cw721QueryMsg::Approval {
                 token_id,
                 spender,
@@ -628,13 +809,25 @@ pub trait Cw721Query<
             Cw721QueryMsg::GetCreatorOwnership {} => Ok(to_json_binary(
                 &self.query_creator_ownership(deps.storage)?,
             )?),
-            Cw721QueryMsg::GetAdditionalMinters { start_after, limit } => Ok(to_json_binary(
-                &self.query_additional_minters(deps, start_after, limit)?,
-            )?),
+            Cw721QueryMsg::GetAdditionalMinters {
+                start_after,
+                limit,
+                end_before,
+                order,
+            } => Ok(to_json_binary(&self.query_additional_minters(
+                deps,
+                start_after,
+                limit,
+                end_before,
+                order,
+            )?)?),
             Cw721QueryMsg::Extension { msg } => self.query_extension(deps, env, msg),
             Cw721QueryMsg::GetCollectionExtension { msg } => {
                 self.query_custom_collection_extension(deps, env, msg)
             }
+            Cw721QueryMsg::GetCollectionExtensionAttribute { key } => Ok(to_json_binary(
+                &self.query_collection_extension_attribute(deps, key)?,
+            )?),
             Cw721QueryMsg::GetWithdrawAddress {} => {
                 Ok(to_json_binary(&self.query_withdraw_address(deps)?)?)
             }
@@ -661,8 +854,10 @@ pub trait Cw721Query<
         deps: Deps,
         start_after: Option<String>,
         limit: Option<u32>,
+        end_before: Option<String>,
+        order: Option<OrderBy>,
     ) -> StdResult<AdditionalMintersResponse> {
-        query_additional_minters(deps, start_after, limit)
+        query_additional_minters(deps, start_after, limit, end_before, order)
     }
 
     fn query_collection_info(&self, deps: Deps) -> StdResult<CollectionInfo> {
@@ -673,6 +868,14 @@ pub trait Cw721Query<
         query_collection_extension_attributes(deps)
     }
 
+    fn query_collection_extension_attribute(
+        &self,
+        deps: Deps,
+        key: String,
+    ) -> StdResult<Option<Attribute>> {
+        query_collection_extension_attribute(deps, key)
+    }
+
     fn query_all_collection_info(
         &self,
         deps: Deps,
@@ -698,10 +901,85 @@ pub trait Cw721Query<
         query_all_info(deps, env)
     }
 
+    fn query_approved_tokens_for(
+        &self,
+        deps: Deps,
+        env: &Env,
+        spender: String,
+        include_expired_approval: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ApprovedTokensResponse> {
+        query_approved_tokens_for(
+            deps,
+            env,
+            spender,
+            include_expired_approval,
+            start_after,
+            limit,
+        )
+    }
+
+    fn query_operator_grants_for(
+        &self,
+        deps: Deps,
+        env: &Env,
+        operator: String,
+        include_expired_approval: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<OperatorGrantsResponse> {
+        query_operator_grants_for(
+            deps,
+            env,
+            operator,
+            include_expired_approval,
+            start_after,
+            limit,
+        )
+    }
+
+    fn query_can_transfer(
+        &self,
+        deps: Deps,
+        env: &Env,
+        sender: String,
+        token_id: String,
+    ) -> StdResult<AuthorizationResponse> {
+        query_can_transfer(deps, env, sender, token_id)
+    }
+
+    fn query_can_approve(
+        &self,
+        deps: Deps,
+        env: &Env,
+        sender: String,
+        token_id: String,
+    ) -> StdResult<AuthorizationResponse> {
+        query_can_approve(deps, env, sender, token_id)
+    }
+
     fn query_num_tokens(&self, storage: &dyn Storage) -> StdResult<NumTokensResponse> {
         query_num_tokens(storage)
     }
 
+    fn query_collection_stats(&self, storage: &dyn Storage) -> StdResult<CollectionStatsResponse> {
+        query_collection_stats(storage)
+    }
+
+    fn query_holders(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<HoldersResponse> {
+        query_holders(deps, start_after, limit)
+    }
+
+    fn query_balance_of(&self, deps: Deps, owner: String) -> StdResult<BalanceOfResponse> {
+        query_balance_of(deps, owner)
+    }
+
     fn query_nft_info(
         &self,
         storage: &dyn Storage,
@@ -720,6 +998,26 @@ pub trait Cw721Query<
         query_nft_by_extension::<TNftExtension>(storage, extension, start_after, limit)
     }
 
+    fn query_nfts_by_extension(
+        &self,
+        storage: &dyn Storage,
+        extension: TNftExtension,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<NftsByExtensionResponse<TNftExtension>> {
+        query_nfts_by_extension::<TNftExtension>(storage, extension, start_after, limit)
+    }
+
+    fn query_tokens_by_filter(
+        &self,
+        storage: &dyn Storage,
+        filter: TraitFilter,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensByFilterResponse> {
+        query_tokens_by_filter::<TNftExtension>(storage, filter, start_after, limit)
+    }
+
     fn query_owner_of(
         &self,
         deps: Deps,
@@ -743,6 +1041,7 @@ pub trait Cw721Query<
     }
 
     /// operators returns all operators owner given access to
+    #[allow(clippy::too_many_arguments)]
     fn query_operators(
         &self,
         deps: Deps,
@@ -751,6 +1050,8 @@ pub trait Cw721Query<
         include_expired_approval: bool,
         start_after: Option<String>,
         limit: Option<u32>,
+        end_before: Option<String>,
+        order: Option<OrderBy>,
     ) -> StdResult<OperatorsResponse> {
         query_operators(
             deps,
@@ -759,6 +1060,8 @@ pub trait Cw721Query<
             include_expired_approval,
             start_after,
             limit,
+            end_before,
+            order,
         )
     }
 
@@ -790,6 +1093,7 @@ pub trait Cw721Query<
         query_approvals(deps, env, token_id, include_expired_approval)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn query_tokens(
         &self,
         deps: Deps,
@@ -797,8 +1101,10 @@ pub trait Cw721Query<
         owner: String,
         start_after: Option<String>,
         limit: Option<u32>,
+        end_before: Option<String>,
+        order: Option<OrderBy>,
     ) -> StdResult<TokensResponse> {
-        query_tokens(deps, _env, owner, start_after, limit)
+        query_tokens(deps, _env, owner, start_after, limit, end_before, order)
     }
 
     fn query_all_tokens(
@@ -807,8 +1113,58 @@ pub trait Cw721Query<
         _env: &Env,
         start_after: Option<String>,
         limit: Option<u32>,
+        end_before: Option<String>,
+        order: Option<OrderBy>,
     ) -> StdResult<TokensResponse> {
-        query_all_tokens(deps, _env, start_after, limit)
+        query_all_tokens(deps, _env, start_after, limit, end_before, order)
+    }
+
+    fn query_tokens_with_info(
+        &self,
+        deps: Deps,
+        env: &Env,
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        include_expired_approval: bool,
+    ) -> StdResult<TokensWithInfoResponse<TNftExtension>> {
+        query_tokens_with_info::<TNftExtension>(
+            deps,
+            env,
+            owner,
+            start_after,
+            limit,
+            include_expired_approval,
+        )
+    }
+
+    fn query_all_tokens_with_info(
+        &self,
+        deps: Deps,
+        env: &Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        include_expired_approval: bool,
+    ) -> StdResult<TokensWithInfoResponse<TNftExtension>> {
+        query_all_tokens_with_info::<TNftExtension>(
+            deps,
+            env,
+            start_after,
+            limit,
+            include_expired_approval,
+        )
+    }
+
+    fn query_tokens_by_trait(
+        &self,
+        deps: Deps,
+        env: &Env,
+        trait_type: String,
+        value: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        query_tokens_by_trait(deps, env, trait_type, value, start_after, limit)
     }
 
     fn query_all_nft_info(
@@ -953,6 +1309,8 @@ pub trait Cw721Calls<
             include_expired: Some(include_expired),
             start_after,
             limit,
+            end_before: None,
+            order: None,
         };
         let res: OperatorsResponse = self.query(querier, req)?;
         Ok(res.operators)
@@ -964,6 +1322,14 @@ pub trait Cw721Calls<
         Ok(res.count)
     }
 
+    fn balance_of<T: Into<String>>(&self, querier: &QuerierWrapper, owner: T) -> StdResult<u64> {
+        let req = Cw721QueryMsg::BalanceOf {
+            owner: owner.into(),
+        };
+        let res: BalanceOfResponse = self.query(querier, req)?;
+        Ok(res.balance)
+    }
+
     /// This is a helper to get the metadata and extension data in one call
     fn config<U: DeserializeOwned>(
         &self,
@@ -1020,6 +1386,8 @@ pub trait Cw721Calls<
             owner: owner.into(),
             start_after,
             limit,
+            end_before: None,
+            order: None,
         };
         self.query(querier, req)
     }
@@ -1031,7 +1399,12 @@ pub trait Cw721Calls<
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<TokensResponse> {
-        let req = Cw721QueryMsg::AllTokens { start_after, limit };
+        let req = Cw721QueryMsg::AllTokens {
+            start_after,
+            limit,
+            end_before: None,
+            order: None,
+        };
         self.query(querier, req)
     }
 